@@ -0,0 +1,132 @@
+//! A manifest of expected answers, keyed by input and part, for verifying solutions against.
+//!
+//! The manifest is stored as a JSON object that maps the name of each input to an object that maps each part to its answer:
+//!
+//! ```text
+//! {
+//!   "day1": {
+//!     "a": "3318632",
+//!     "b": "4975084"
+//!   }
+//! }
+//! ```
+//!
+//! Input names are as in [`crate::Answer::input`], so answers for inputs passed with `--input` are keyed by their path.
+
+/// Expected answers, keyed by input and part
+#[derive(Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
+pub struct Manifest(std::collections::BTreeMap<String, std::collections::BTreeMap<String, String>>);
+
+/// How an answer compares to the manifest
+#[derive(Debug, PartialEq)]
+pub enum Status {
+	/// The answer matches the expected answer
+	Pass,
+
+	/// The answer does not match the expected answer
+	Fail {
+		/// The expected answer
+		expected: String,
+	},
+
+	/// There is no expected answer for this input and part
+	Unknown,
+}
+
+impl Manifest {
+	/// The manifest of the inputs in this crate's `inputs` directory
+	pub fn default_path() -> std::path::PathBuf {
+		let mut path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).to_owned();
+		path.push("inputs");
+		path.push("answers.json");
+		path
+	}
+
+	/// Loads the manifest at the given path. A file that doesn't exist is treated as an empty manifest.
+	pub fn load(path: &std::path::Path) -> Result<Self, crate::Error> {
+		let s = match std::fs::read_to_string(path) {
+			Ok(s) => s,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Default::default()),
+			Err(err) => return Err(format!("could not read {}: {err}", path.display()).into()),
+		};
+		s.parse()
+	}
+
+	/// Saves the manifest to the given path.
+	pub fn save(&self, path: &std::path::Path) -> Result<(), crate::Error> {
+		std::fs::write(path, self.to_string()).map_err(|err| format!("could not write {}: {err}", path.display()))?;
+		Ok(())
+	}
+
+	/// Compares the given answer to the expected answer
+	pub fn check(&self, answer: &crate::Answer) -> Status {
+		match self.0.get(&answer.input).and_then(|parts| parts.get(&answer.part.to_string())) {
+			Some(expected) if *expected == answer.value.to_string() => Status::Pass,
+			Some(expected) => Status::Fail { expected: expected.clone() },
+			None => Status::Unknown,
+		}
+	}
+
+	/// Sets the given answer as the expected answer, and returns the previous expected answer if there was one.
+	pub fn record(&mut self, answer: &crate::Answer) -> Option<String> {
+		self.0.entry(answer.input.clone()).or_default().insert(answer.part.to_string(), answer.value.to_string())
+	}
+}
+
+impl std::fmt::Display for Manifest {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let s = serde_json::to_string_pretty(self).map_err(|_| std::fmt::Error)?;
+		writeln!(f, "{s}")
+	}
+}
+
+impl std::str::FromStr for Manifest {
+	type Err = crate::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(serde_json::from_str(s).map_err(|err| format!("malformed answers manifest: {err}"))?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_manifest() {
+		fn answer(input: &str, part: crate::Part, value: &str) -> crate::Answer {
+			crate::Answer { part, input: input.to_owned(), value: crate::Value::Text(value.to_owned()), time: Default::default() }
+		}
+
+		let mut manifest: super::Manifest = r#"{ "day1": { "a": "3318632" } }"#.parse().unwrap();
+
+		assert_eq!(manifest.check(&answer("day1", crate::Part::A, "3318632")), super::Status::Pass);
+		assert_eq!(manifest.check(&answer("day1", crate::Part::A, "5")), super::Status::Fail { expected: "3318632".to_owned() });
+		assert_eq!(manifest.check(&answer("day1", crate::Part::B, "4975084")), super::Status::Unknown);
+		assert_eq!(manifest.check(&answer("day2", crate::Part::A, "3318632")), super::Status::Unknown);
+
+		assert_eq!(manifest.record(&answer("day1", crate::Part::B, "4975084")), None);
+		assert_eq!(manifest.record(&answer("day1", crate::Part::A, "5")), Some("3318632".to_owned()));
+		assert_eq!(manifest.record(&answer("day 2.txt", crate::Part::A, "a\nb")), None);
+
+		let s = manifest.to_string();
+		assert_eq!(
+			s,
+			r#"{
+  "day 2.txt": {
+    "a": "a\nb"
+  },
+  "day1": {
+    "a": "5",
+    "b": "4975084"
+  }
+}
+"#,
+		);
+		assert_eq!(s.parse::<super::Manifest>().unwrap(), manifest);
+
+		assert_eq!(
+			"malformed answers manifest: invalid type: integer `5`, expected a string at line 1 column 18",
+			r#"{ "day1": { "a": 5 } }"#.parse::<super::Manifest>().unwrap_err().0.to_string(),
+		);
+	}
+}
@@ -1,25 +1,23 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	{
-		let result =
-			super::read_input_lines::<u64>("day1")?
-			.try_fold(0, |prev, curr| -> Result<_, super::Error> { Ok(prev + calculate_fuel(curr?)) })?;
+pub(super) struct Solution;
 
-		println!("1a: {result}");
+impl super::Solution for Solution {
+	type Input = Vec<u64>;
+	type Part1 = u64;
+	type Part2 = u64;
 
-		assert_eq!(result, 3318632);
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		super::Lines::new(input).collect()
 	}
 
-	{
-		let result =
-			super::read_input_lines::<u64>("day1")?
-			.try_fold(0, |prev, curr| -> Result<_, super::Error> { Ok(prev + calculate_fuel_recursive(curr?)) })?;
-
-		println!("1b: {result}");
-
-		assert_eq!(result, 4975084);
+	fn part1(input: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let result = input.iter().map(|&mass| calculate_fuel(mass)).sum();
+		Ok(result)
 	}
 
-	Ok(())
+	fn part2(input: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let result = input.iter().map(|&mass| calculate_fuel_recursive(mass)).sum();
+		Ok(result)
+	}
 }
 
 fn calculate_fuel(mass: u64) -> u64 {
@@ -1,62 +1,34 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	const EPSILON: f64 = 0.0001;
-
-	let grid: Result<Vec<_>, super::Error> = super::read_input_lines::<String>("day10")?.collect();
-	let grid = grid?;
-
-	let asteroids: Vec<_> =
-		grid.iter()
-		.enumerate()
-		.flat_map(|(row_num, row)| {
-			row
-			.chars()
-			.enumerate()
-			.filter_map(move |(col_num, c)| if c == '#' { Some((col_num, row_num)) } else { None })
-		})
-		.collect();
-
-	let laser = {
-		let mut result = None;
-
-		for &candidate in &asteroids {
-			// Vec<angle>
-			//
-			// It's a Vec because f64 can't be elements of BTreeSets (not Ord) nor HashSets (not Eq).
-			// Also, we need comparisons to be approximate on EPSILON, so f64's default PartialEq and PartialOrd impls
-			// wouldn't work for us anyway.
-			let mut visible = vec![];
-
-			for &asteroid in &asteroids {
-				if candidate == asteroid {
-					continue;
-				}
+pub(super) struct Solution;
 
-				#[allow(clippy::cast_precision_loss)]
-				let delta_y = asteroid.1 as f64 - candidate.1 as f64; // grid's Y increases downwards, so it's backwards from Descartes
-				#[allow(clippy::cast_precision_loss)]
-				let delta_x = candidate.0 as f64 - asteroid.0 as f64;
-				let angle = delta_y.atan2(delta_x);
-				if !visible.iter().any(|&a| ((a - angle) as f64).abs() < EPSILON) {
-					visible.push(angle);
-				}
-			}
-
-			result = match result {
-				Some((previous_candidate, previous_best)) if previous_best > visible.len() => Some((previous_candidate, previous_best)),
-				_ => Some((candidate, visible.len())),
-			};
-		}
+impl super::Solution for Solution {
+	type Input = Vec<(usize, usize)>;
+	type Part1 = usize;
+	type Part2 = usize;
 
-		let result = result.ok_or("no solution")?;
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let grid = super::read_grid(input)?;
 
-		println!("10a: {}", result.1);
+		let asteroids =
+			grid.iter()
+			.enumerate()
+			.flat_map(|(row_num, row)| {
+				row
+				.iter()
+				.enumerate()
+				.filter_map(move |(col_num, &c)| if c == b'#' { Some((col_num, row_num)) } else { None })
+			})
+			.collect();
+		Ok(asteroids)
+	}
 
-		assert_eq!(result.1, 267);
+	fn part1(asteroids: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let (_, result) = find_laser(asteroids)?;
+		Ok(result)
+	}
 
-		result.0
-	};
+	fn part2(asteroids: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let (laser, _) = find_laser(asteroids)?;
 
-	{
 		// Vec<(angle, Vec<(distance, coord)>)>
 		//
 		// They're Vecs because f64 can't be keys of BTreeMaps (not Ord) nor HashMaps (not Eq).
@@ -64,7 +36,7 @@ pub(super) fn run() -> Result<(), super::Error> {
 		// wouldn't work for us anyway.
 		let mut to_zap: Vec<(f64, Vec<(f64, (usize, usize))>)> = vec![];
 
-		for asteroid in asteroids {
+		for &asteroid in asteroids {
 			if asteroid == laser {
 				continue;
 			}
@@ -118,11 +90,96 @@ pub(super) fn run() -> Result<(), super::Error> {
 		};
 
 		let result = x * 100 + y;
+		Ok(result)
+	}
+}
+
+const EPSILON: f64 = 0.0001;
+
+// Returns the asteroid that can see the most other asteroids, and the number of asteroids it can see
+fn find_laser(asteroids: &[(usize, usize)]) -> Result<((usize, usize), usize), super::Error> {
+	let mut result = None;
+
+	for &candidate in asteroids {
+		// Vec<angle>
+		//
+		// It's a Vec because f64 can't be elements of BTreeSets (not Ord) nor HashSets (not Eq).
+		// Also, we need comparisons to be approximate on EPSILON, so f64's default PartialEq and PartialOrd impls
+		// wouldn't work for us anyway.
+		let mut visible = vec![];
 
-		println!("10b: {result}");
+		for &asteroid in asteroids {
+			if candidate == asteroid {
+				continue;
+			}
 
-		assert_eq!(result, 1309);
+			#[allow(clippy::cast_precision_loss)]
+			let delta_y = asteroid.1 as f64 - candidate.1 as f64; // grid's Y increases downwards, so it's backwards from Descartes
+			#[allow(clippy::cast_precision_loss)]
+			let delta_x = candidate.0 as f64 - asteroid.0 as f64;
+			let angle = delta_y.atan2(delta_x);
+			if !visible.iter().any(|&a| ((a - angle) as f64).abs() < EPSILON) {
+				visible.push(angle);
+			}
+		}
+
+		result = match result {
+			Some((previous_candidate, previous_best)) if previous_best > visible.len() => Some((previous_candidate, previous_best)),
+			_ => Some((candidate, visible.len())),
+		};
 	}
 
-	Ok(())
+	let result = result.ok_or("no solution")?;
+	Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_find_laser() {
+		fn test(input: &str, expected_laser: (usize, usize), expected_num_visible: usize) {
+			let asteroids = crate::parse_str::<super::Solution>(input).unwrap();
+			let actual = super::find_laser(&asteroids).unwrap();
+			assert_eq!((expected_laser, expected_num_visible), actual);
+		}
+
+		test(
+			"\
+			.#..#\n\
+			.....\n\
+			#####\n\
+			....#\n\
+			...##\n\
+			",
+			(3, 4),
+			8,
+		);
+
+		test(
+			"\
+			.#..##.###...#######\n\
+			##.############..##.\n\
+			.#.######.########.#\n\
+			.###.#######.####.#.\n\
+			#####.##.#.##.###.##\n\
+			..#####..#.#########\n\
+			####################\n\
+			#.####....###.#.#.##\n\
+			##.#################\n\
+			#####.##.###..####..\n\
+			..######..##.#######\n\
+			####.##.####...##..#\n\
+			.#####..#.######.###\n\
+			##...#.##########...\n\
+			#.##########.#######\n\
+			.####.#.###.###.#.##\n\
+			....##.##.###..#####\n\
+			.#.#.###########.###\n\
+			#.#.#.#####.####.###\n\
+			###.##.####.##.#..##\n\
+			",
+			(11, 13),
+			210,
+		);
+	}
 }
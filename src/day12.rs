@@ -1,28 +1,32 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let mut states_x = vec![];
-	let mut states_y = vec![];
-	let mut states_z = vec![];
+pub(super) struct Solution;
 
-	for line in super::read_input_lines::<String>("day12")? {
-		let line = line?;
-		let line = &line[1..(line.len() - 1)];
-		let mut parts = line.split(", ");
+impl super::Solution for Solution {
+	// The initial states of each axis
+	type Input = (Vec<State>, Vec<State>, Vec<State>);
+	type Part1 = i64;
+	type Part2 = super::Value;
 
-		let x = parts.next().ok_or("malformed input")?;
-		let x: i64 = x[2..].parse()?;
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let mut states_x = vec![];
+		let mut states_y = vec![];
+		let mut states_z = vec![];
 
-		let y = parts.next().ok_or("malformed input")?;
-		let y: i64 = y[2..].parse()?;
+		for position in super::Lines::<Position>::new(input) {
+			let Position([x, y, z]) = position?;
 
-		let z = parts.next().ok_or("malformed input")?;
-		let z: i64 = z[2..].parse()?;
+			states_x.push(State { r: x, v: 0 });
+			states_y.push(State { r: y, v: 0 });
+			states_z.push(State { r: z, v: 0 });
+		}
 
-		states_x.push(State { r: x, v: 0 });
-		states_y.push(State { r: y, v: 0 });
-		states_z.push(State { r: z, v: 0 });
+		Ok((states_x, states_y, states_z))
 	}
 
-	{
+	fn part1((states_x, states_y, states_z): &Self::Input) -> Result<Self::Part1, super::Error> {
+		let mut states_x = states_x.clone();
+		let mut states_y = states_y.clone();
+		let mut states_z = states_z.clone();
+
 		for _ in 0..1000 {
 			step(&mut states_x);
 			step(&mut states_y);
@@ -30,31 +34,53 @@ pub(super) fn run() -> Result<(), super::Error> {
 		}
 
 		let result = total_energy(&states_x, &states_y, &states_z);
+		Ok(result)
+	}
 
-		println!("12a: {result}");
+	fn part2((states_x, states_y, states_z): &Self::Input) -> Result<Self::Part2, super::Error> {
+		let x_cycle_len = find_cycle_len(&mut states_x.clone(), &mut states_x.clone());
+		let y_cycle_len = find_cycle_len(&mut states_y.clone(), &mut states_y.clone());
+		let z_cycle_len = find_cycle_len(&mut states_z.clone(), &mut states_z.clone());
 
-		assert_eq!(result, 9139);
+		// Use the right tool for the job
+		Ok(super::Value::Url(format!("https://www.wolframalpha.com/input/?i=lcm%28{x_cycle_len}%2C+{y_cycle_len}%2C+{z_cycle_len}%29")))
 	}
+}
 
-	{
-		// There's no pressing need to use the original unmodified states_{x,y,z} here instead of the ones stepped forward by
-		// 1000 steps in part 1, because they'll have the cycles anyway.
+// The position of a moon, eg `<x=-1, y=0, z=2>`
+struct Position([i64; 3]);
 
-		let x_cycle_len = find_cycle_len(&mut states_x.clone(), &mut states_x);
-		let y_cycle_len = find_cycle_len(&mut states_y.clone(), &mut states_y);
-		let z_cycle_len = find_cycle_len(&mut states_z.clone(), &mut states_z);
+impl std::str::FromStr for Position {
+	type Err = super::Error;
 
-		// Use the right tool for the job
-		println!("12b: https://www.wolframalpha.com/input/?i=lcm%28{x_cycle_len}%2C+{y_cycle_len}%2C+{z_cycle_len}%29");
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let coords =
+			s.strip_prefix('<').and_then(|s| s.strip_suffix('>'))
+			.ok_or_else(|| super::ParseError::new(s, 0..s.len(), "malformed position: expected it to be enclosed in `<` and `>`"))?;
 
-		assert_eq!((x_cycle_len, y_cycle_len, z_cycle_len), (268296, 231614, 108344));
-	}
+		let mut result = [0; 3];
+		let mut parts = coords.split(", ");
 
-	Ok(())
+		// Byte offset of the current part
+		let mut start = 1;
+
+		for (coord, prefix) in result.iter_mut().zip(["x=", "y=", "z="]) {
+			let part = parts.next().ok_or_else(|| super::ParseError::new(s, (s.len() - 1)..s.len(), format!("malformed position: expected {prefix}")))?;
+			let value = part.strip_prefix(prefix).ok_or_else(|| super::ParseError::new(s, start..(start + part.len()), format!("malformed position: expected {prefix}")))?;
+			*coord = value.parse().map_err(|err| super::ParseError::new(s, (start + prefix.len())..(start + part.len()), format!("malformed position: {err}")))?;
+			start += part.len() + 2;
+		}
+
+		if parts.next().is_some() {
+			return Err(super::ParseError::new(s, (start - 2)..(s.len() - 1), "malformed position: expected only x, y and z").into());
+		}
+
+		Ok(Position(result))
+	}
 }
 
 #[derive(Clone, Debug, PartialEq)]
-struct State {
+pub(super) struct State {
 	r: i64,
 	v: i64,
 }
@@ -1,14 +1,21 @@
 use std::convert::TryInto;
 
-// To play breakout, set DAY_13_VIS=1
+// To watch the game of breakout, run with --vis
 
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day13")?.next().ok_or("file is empty")??;
+pub(super) struct Solution;
 
-	let ram: crate::intcode::Ram = line.parse()?;
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = usize;
+	type Part2 = i64;
 
-	{
-		let mut tiles: std::collections::BTreeMap<(i64, i64), Tile> = Default::default();
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
+
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let mut tiles: super::grid::Grid<Tile> = super::grid::Grid::sparse();
 
 		let mut computer = crate::intcode::Computer::new(ram.clone());
 
@@ -28,19 +35,14 @@ pub(super) fn run() -> Result<(), super::Error> {
 		}
 
 		let result = tiles.values().filter(|&&tile| tile == Tile::Block).count();
-
-		println!("13a: {result}");
-
-		assert_eq!(result, 318);
+		Ok(result)
 	}
 
-	{
-		let play_breakout = std::env::var("DAY_13_VIS").is_ok();
-
-		let mut ram = ram;
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let mut ram = ram.clone();
 		*ram.get_mut(0) = 2;
 
-		let mut tiles: std::collections::BTreeMap<(i64, i64), Tile> = Default::default();
+		let mut tiles: super::grid::Grid<Tile> = super::grid::Grid::sparse();
 
 		let mut score = 0;
 
@@ -48,12 +50,7 @@ pub(super) fn run() -> Result<(), super::Error> {
 
 		let mut joystick = 0;
 
-		let stdout = std::io::stdout();
-		let mut stdout = stdout.lock();
-
-		if play_breakout {
-			render_pre(&mut stdout)?;
-		}
+		let mut renderer = super::vis::renderer();
 
 		while let Some(x) = computer.step(std::iter::once(joystick))? {
 			let y = match computer.step(std::iter::empty())? {
@@ -68,17 +65,17 @@ pub(super) fn run() -> Result<(), super::Error> {
 			if (x, y) == (-1, 0) {
 				score = third;
 
-				if play_breakout {
-					render_score(&mut stdout, score)?;
-				}
+				renderer.status(&format!("score: {score:05}"))?;
 			}
 			else {
 				let tile = third.try_into()?;
 				tiles.insert((x, y), tile);
 
-				if play_breakout {
-					render_tile(&mut stdout, x, y, tile)?;
-					std::thread::sleep(std::time::Duration::from_millis(5));
+				renderer.set((x, y), tile.cell(x, y))?;
+
+				// The ball is drawn once every tick of the game, after everything else that changed in that tick
+				if tile == Tile::Ball {
+					renderer.frame()?;
 				}
 			}
 
@@ -89,16 +86,10 @@ pub(super) fn run() -> Result<(), super::Error> {
 			}
 		}
 
-		if play_breakout {
-			render_post(&mut stdout)?;
-		}
-
-		println!("13b: {score}");
+		renderer.finish()?;
 
-		assert_eq!(score, 16309);
+		Ok(score)
 	}
-
-	Ok(())
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -110,6 +101,33 @@ enum Tile {
 	Ball,
 }
 
+impl Tile {
+	fn cell(self, x: i64, y: i64) -> super::vis::Cell {
+		// Blocks are colored in a pattern of the eight normal and bright colors from red to blue
+		const BLOCK_COLORS: [super::vis::Color; 8] = [
+			super::vis::Color(205, 49, 49),
+			super::vis::Color(13, 188, 121),
+			super::vis::Color(229, 229, 16),
+			super::vis::Color(36, 114, 200),
+			super::vis::Color(241, 76, 76),
+			super::vis::Color(35, 209, 139),
+			super::vis::Color(245, 245, 67),
+			super::vis::Color(59, 142, 234),
+		];
+
+		match self {
+			Tile::Empty => super::vis::Cell { glyph: "   ", color: super::vis::Color(0, 0, 0) },
+			Tile::Wall => super::vis::Cell { glyph: "\u{2591}\u{2591}\u{2591}", color: super::vis::Color(204, 204, 204) },
+			Tile::Block => super::vis::Cell {
+				glyph: "\u{2588}\u{2588}\u{2588}",
+				color: BLOCK_COLORS[usize::try_from((x ^ y).rem_euclid(8)).expect("0..8 fits in usize")],
+			},
+			Tile::HorizontalPaddle => super::vis::Cell { glyph: "\u{1F030}\u{1F030}\u{1F030}", color: super::vis::Color(229, 229, 229) },
+			Tile::Ball => super::vis::Cell { glyph: " \u{2B24} ", color: super::vis::Color(229, 229, 229) },
+		}
+	}
+}
+
 impl std::convert::TryFrom<i64> for Tile {
 	type Error = super::Error;
 
@@ -124,50 +142,3 @@ impl std::convert::TryFrom<i64> for Tile {
 		}
 	}
 }
-
-fn render_pre(stdout: &mut std::io::StdoutLock<'_>) -> Result<(), super::Error> {
-	use std::io::Write;
-
-	write!(stdout, "\x1B[?1049h\x1B[2J\x1B[3J")?;
-	Ok(())
-}
-
-fn render_post(stdout: &mut std::io::StdoutLock<'_>) -> Result<(), super::Error> {
-	use std::io::Write;
-
-	write!(stdout, "\x1B[?1049l")?;
-	Ok(())
-}
-
-fn render_tile(stdout: &mut std::io::StdoutLock<'_>, x: i64, y: i64, tile: Tile) -> Result<(), super::Error> {
-	use std::io::Write;
-
-	if tile == Tile::Block {
-		let color = (x ^ y) % 8;
-		write!(stdout, "\x1B[{};{}m", (color / 4) % 2, color % 4 + 31)?;
-	}
-
-	write!(stdout, "\x1B[{};{}H{}\x1B[1;1H", y + 2, x * 3 + 1, match tile {
-		Tile::Empty => "   ",
-		Tile::Wall => "\u{2591}\u{2591}\u{2591}",
-		Tile::Block => "\u{2588}\u{2588}\u{2588}",
-		Tile::HorizontalPaddle => "\u{1F030}\u{1F030}\u{1F030}",
-		Tile::Ball => " \u{2B24} ",
-	})?;
-
-	if tile == Tile::Block {
-		write!(stdout, "\x1B[0m")?;
-	}
-
-	stdout.flush()?;
-
-	Ok(())
-}
-
-fn render_score(stdout: &mut std::io::StdoutLock<'_>, score: i64) -> Result<(), super::Error> {
-	use std::io::Write;
-
-	write!(stdout, "\x1B[1;4H{:05}", score)?;
-	stdout.flush()?;
-	Ok(())
-}
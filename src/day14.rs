@@ -1,61 +1,70 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let mut input = vec![];
-	for line in super::read_input_lines::<String>("day14")? {
-		input.push(line?);
+pub(super) struct Solution;
+
+impl super::Solution for Solution {
+	// A map of reactions.
+	//
+	// The key is the product. The value is a tuple of the number of product generated by the reaction, and a map of ingredients.
+	//
+	// Each entry in the map of ingredients is the name of the ingredient and the number of that ingredient used in the reaction.
+	type Input = std::collections::BTreeMap<String, (u64, Vec<(String, u64)>)>;
+	type Part1 = u64;
+	type Part2 = u64;
+
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		super::Lines::<Reaction>::new(input)
+			.map(|reaction| {
+				let Reaction { ingredients, product: Chemical(product_name, product_num) } = reaction?;
+				let ingredients = ingredients.into_iter().map(|Chemical(name, amount)| (name, amount)).collect();
+				Ok((product_name, (product_num, ingredients)))
+			})
+			.collect()
 	}
-	let reactions = parse(&input)?;
 
-	{
-		let result = get_required_ore(&reactions, 1)?;
+	fn part1(reactions: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let result = get_required_ore(reactions, 1)?;
+		Ok(result)
+	}
 
-		println!("14a: {result}");
+	fn part2(reactions: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let result = fuel_from_trillion_ore(reactions)?;
+		Ok(result)
+	}
+}
 
-		assert_eq!(result, 843220);
-	};
+// A reaction, eg `7 A, 1 B => 1 C`
+struct Reaction {
+	ingredients: Vec<Chemical>,
+	product: Chemical,
+}
 
-	{
-		let result = fuel_from_trillion_ore(&reactions)?;
+impl std::str::FromStr for Reaction {
+	type Err = super::Error;
 
-		println!("14b: {result}");
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (lhs, rhs) = s.split_once(" => ").ok_or_else(|| super::ParseError::new(s, 0..s.len(), "malformed reaction: expected `=>`"))?;
 
-		assert_eq!(result, 2169535);
-	}
+		let ingredients: super::CommaSeparated<Chemical> = lhs.parse().map_err(|err: super::Error| err.at(s, 0..lhs.len()))?;
+		let product = rhs.parse().map_err(|err: super::Error| err.at(s, (s.len() - rhs.len())..s.len()))?;
 
-	Ok(())
+		Ok(Reaction { ingredients: ingredients.0, product })
+	}
 }
 
-/// This function returns a map of reactions.
-///
-/// The key is the product. The value is a tuple of the number of product generated by the reaction, and a map of ingredients.
-///
-/// Each entry in the map of ingredients is the name of the ingredient and the number of that ingredient used in the reaction.
-fn parse(input: &[String]) -> Result<std::collections::BTreeMap<&str, (u64, Vec<(&str, u64)>)>, super::Error> {
-	input.iter()
-		.map(|line| {
-			let mut sides = line.split(" => ");
-			let lhs = sides.next().expect("str::split always has at least one part");
-			let rhs = sides.next().ok_or("malformed input")?;
-
-			let ingredients: Result<_, super::Error> =
-				lhs.split(", ").map(|part| {
-					let mut parts = part.split(' ');
-					let amount = parts.next().expect("str::split always has at least one part").parse()?;
-					let name = parts.next().ok_or("malformed input")?;
-					Ok((name, amount))
-				})
-				.collect();
-			let ingredients = ingredients?;
-
-			let mut product_parts = rhs.split(' ');
-			let product_num = product_parts.next().expect("str::split always has at least one part").parse()?;
-			let product_name = product_parts.next().ok_or("malformed input")?;
-			Ok((product_name, (product_num, ingredients)))
-		})
-		.collect()
+// An amount of a chemical, eg `7 A`
+struct Chemical(String, u64);
+
+impl std::str::FromStr for Chemical {
+	type Err = super::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (amount, name) = s.split_once(' ').ok_or_else(|| super::ParseError::new(s, 0..s.len(), "malformed chemical: expected an amount and a name"))?;
+		let amount = amount.parse().map_err(|err| super::ParseError::new(s, 0..amount.len(), format!("malformed chemical: {err}")))?;
+		Ok(Chemical(name.to_owned(), amount))
+	}
 }
 
 fn get_required_ore<'a>(
-	reactions: &'a std::collections::BTreeMap<&'a str, (u64, Vec<(&'a str, u64)>)>,
+	reactions: &'a std::collections::BTreeMap<String, (u64, Vec<(String, u64)>)>,
 	fuel: u64,
 ) -> Result<u64, super::Error> {
 	let mut ore = 0;
@@ -113,7 +122,7 @@ fn get_required_ore<'a>(
 	Ok(ore)
 }
 
-fn fuel_from_trillion_ore(reactions: &std::collections::BTreeMap<&str, (u64, Vec<(&str, u64)>)>) -> Result<u64, super::Error> {
+fn fuel_from_trillion_ore(reactions: &std::collections::BTreeMap<String, (u64, Vec<(String, u64)>)>) -> Result<u64, super::Error> {
 	// Find some power of two for which more than one trillion ore is used,
 	// then do binary search to find the answer.
 
@@ -159,8 +168,7 @@ mod tests {
 			input: &[&str],
 			expected_ore: u64,
 		) {
-			let input: Vec<_> = input.iter().copied().map(ToOwned::to_owned).collect();
-			let reactions = super::parse(&input).unwrap();
+			let reactions = crate::parse_str::<super::Solution>(&input.join("\n")).unwrap();
 
 			let actual_ore = super::get_required_ore(&reactions, 1).unwrap();
 			assert_eq!(expected_ore, actual_ore);
@@ -239,8 +247,7 @@ mod tests {
 			input: &[&str],
 			expected_fuel: u64,
 		) {
-			let input: Vec<_> = input.iter().copied().map(ToOwned::to_owned).collect();
-			let reactions = super::parse(&input).unwrap();
+			let reactions = crate::parse_str::<super::Solution>(&input.join("\n")).unwrap();
 
 			let actual_fuel = super::fuel_from_trillion_ore(&reactions).unwrap();
 			assert_eq!(expected_fuel, actual_fuel);
@@ -1,106 +1,42 @@
 use std::convert::TryInto;
 
-// To render maze, set DAY_15_VIS=1
+// To watch the maze being explored in part 1, run with --vis
 
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day15")?.next().ok_or("file is empty")??;
+pub(super) struct Solution;
 
-	let ram: crate::intcode::Ram = line.parse()?;
+impl super::Solution for Solution {
+	type Input = Maze;
+	type Part1 = usize;
+	type Part2 = usize;
 
-	let tiles = {
-		let render_maze = std::env::var("DAY_15_VIS").is_ok();
-
-		// Map of coordinate to tuple of tile type and the distance to that tile from the starting position
-		let mut tiles: std::collections::BTreeMap<(i64, i64), (Tile, usize)> = Default::default();
-		tiles.insert((0, 0), (Tile::Empty, 0));
-
-		// A list of entries. Each entry contains the coordinate of a tile, the state of the robot at that coordinate,
-		// and the distance to that tile from the starting position
-		let mut universes: std::collections::VecDeque<((i64, i64), crate::intcode::Computer, usize)> = Default::default();
-		universes.push_back(((0, 0), crate::intcode::Computer::new(ram), 0));
-
-		let stdout = std::io::stdout();
-		let mut stdout = stdout.lock();
-
-		if render_maze {
-			render_pre(&mut stdout)?;
-		}
-
-		while let Some((pos, computer, distance)) = universes.pop_front() {
-			for &cmd in &[MoveCommand::North, MoveCommand::South, MoveCommand::West, MoveCommand::East] {
-				// Try to move in a direction
-				let mut pos = pos;
-				cmd.advance(&mut pos);
-
-				let distance = distance + 1;
-
-				let mut computer = computer.clone();
-				let status = computer.step(std::iter::once(cmd.into()))?.ok_or("program halted")?;
-				let status: RobotStatus = status.try_into()?;
-
-
-				// What did the robot find at the new coordinate?
-				match status {
-					RobotStatus::HitWall => {
-						tiles.insert(pos, (Tile::Wall, 0));
-
-						if render_maze {
-							render_tile(&mut stdout, pos.0, pos.1, Tile::Wall)?;
-							std::thread::sleep(std::time::Duration::from_millis(10));
-						}
-					},
-
-					RobotStatus::Moved(o2) => {
-						match tiles.entry(pos) {
-							std::collections::btree_map::Entry::Occupied(mut entry) => {
-								let (_, previous_distance) = entry.get_mut();
-								// We already visited this tile before. Since we're doing breadth-first search,
-								// the previous visit *must* have had a shorter route to this tile than the current one.
-								assert!(distance >= *previous_distance);
-							},
-
-							std::collections::btree_map::Entry::Vacant(entry) => {
-								// We've reached this tile for the first time.
-
-								let tile = if o2 { Tile::Oxygen } else { Tile::Empty };
-								entry.insert((tile, distance));
-
-								if render_maze {
-									render_tile(&mut stdout, pos.0, pos.1, tile)?;
-									std::thread::sleep(std::time::Duration::from_millis(10));
-								}
-
-								// Continue walking the maze from this tile later.
-								universes.push_back((pos, computer, distance));
-							},
-						}
-					},
-				}
-			}
-		}
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		let maze = explore(&ram)?;
+		Ok(maze)
+	}
 
-		if render_maze {
-			render_post(&mut stdout)?;
+	fn part1(Maze { tiles, discovered }: &Self::Input) -> Result<Self::Part1, super::Error> {
+		// Replay the exploration in the order the tiles were found
+		let mut renderer = super::vis::renderer();
+		for &(pos, tile) in discovered {
+			renderer.set(pos, tile.cell())?;
+			renderer.frame()?;
 		}
+		renderer.finish()?;
 
 		let result =
 			tiles.values()
 			.find(|(tile, _)| *tile == Tile::Oxygen)
 			.map(|(_, distance)| *distance)
 			.ok_or("no solution")?;
+		Ok(result)
+	}
 
-		println!("15a: {result}");
-
-		assert_eq!(result, 262);
-
-		tiles
-	};
-
-	{
+	fn part2(Maze { tiles, .. }: &Self::Input) -> Result<Self::Part2, super::Error> {
 		let o2_pos =
 			tiles.iter()
 			.find(|(_, (tile, _))| *tile == Tile::Oxygen)
-			.map(|(pos, _)| *pos)
+			.map(|(pos, _)| pos)
 			.unwrap();
 
 		// Set of tiles that have been visited
@@ -114,11 +50,8 @@ pub(super) fn run() -> Result<(), super::Error> {
 		while let Some((pos, time)) = tiles_to_visit.pop_front() {
 			result = std::cmp::max(result, time);
 
-			for &cmd in &[MoveCommand::North, MoveCommand::South, MoveCommand::West, MoveCommand::East] {
-				let mut pos = pos;
-				cmd.advance(&mut pos);
-
-				match tiles.get(&pos).unwrap() {
+			for (pos, tile) in tiles.neighbors(pos, super::grid::Neighborhood::Four) {
+				match tile {
 					// Only visit empty or oxygen generator tiles
 					(Tile::Empty | Tile::Oxygen, _) => {
 						if visited_tiles.insert(pos) {
@@ -132,12 +65,76 @@ pub(super) fn run() -> Result<(), super::Error> {
 			}
 		}
 
-		println!("15b: {result}");
+		Ok(result)
+	}
+}
+
+pub(super) struct Maze {
+	// Map of coordinate to tuple of tile type and the distance to that tile from the starting position
+	tiles: super::grid::Grid<(Tile, usize)>,
+
+	// The tiles in the order they were found, for rendering
+	discovered: Vec<((i64, i64), Tile)>,
+}
 
-		assert_eq!(result, 314);
+// Explores the whole maze breadth-first, by cloning the robot at every tile
+fn explore(ram: &crate::intcode::Ram) -> Result<Maze, super::Error> {
+	let mut tiles = super::grid::Grid::sparse();
+	tiles.insert((0, 0), (Tile::Empty, 0));
+
+	let mut discovered = vec![];
+
+	// A list of entries. Each entry contains the coordinate of a tile, the state of the robot at that coordinate,
+	// and the distance to that tile from the starting position
+	let mut universes: std::collections::VecDeque<((i64, i64), crate::intcode::Computer, usize)> = Default::default();
+	universes.push_back(((0, 0), crate::intcode::Computer::new(ram.clone()), 0));
+
+	while let Some((pos, computer, distance)) = universes.pop_front() {
+		for &cmd in &[MoveCommand::North, MoveCommand::South, MoveCommand::West, MoveCommand::East] {
+			// Try to move in a direction
+			let mut pos = pos;
+			cmd.advance(&mut pos);
+
+			let distance = distance + 1;
+
+			let mut computer = computer.clone();
+			let status = computer.step(std::iter::once(cmd.into()))?.ok_or("program halted")?;
+			let status: RobotStatus = status.try_into()?;
+
+
+			// What did the robot find at the new coordinate?
+			match status {
+				RobotStatus::HitWall => {
+					if tiles.insert(pos, (Tile::Wall, 0)).is_none() {
+						discovered.push((pos, Tile::Wall));
+					}
+				},
+
+				RobotStatus::Moved(o2) => {
+					match tiles.get(pos) {
+						Some(&(_, previous_distance)) => {
+							// We already visited this tile before. Since we're doing breadth-first search,
+							// the previous visit *must* have had a shorter route to this tile than the current one.
+							assert!(distance >= previous_distance);
+						},
+
+						None => {
+							// We've reached this tile for the first time.
+
+							let tile = if o2 { Tile::Oxygen } else { Tile::Empty };
+							tiles.insert(pos, (tile, distance));
+							discovered.push((pos, tile));
+
+							// Continue walking the maze from this tile later.
+							universes.push_back((pos, computer, distance));
+						},
+					}
+				},
+			}
+		}
 	}
 
-	Ok(())
+	Ok(Maze { tiles, discovered })
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -147,6 +144,16 @@ enum Tile {
 	Oxygen,
 }
 
+impl Tile {
+	fn cell(self) -> super::vis::Cell {
+		match self {
+			Tile::Empty => super::vis::Cell { glyph: "  ", color: super::vis::Color(0, 0, 0) },
+			Tile::Wall => super::vis::Cell { glyph: "\u{2591}\u{2591}", color: super::vis::Color(204, 204, 204) },
+			Tile::Oxygen => super::vis::Cell { glyph: "O\u{2082}", color: super::vis::Color(36, 114, 200) },
+		}
+	}
+}
+
 #[derive(Clone, Copy, Debug)]
 enum MoveCommand {
 	North,
@@ -195,31 +202,3 @@ impl std::convert::TryFrom<i64> for RobotStatus {
 		}
 	}
 }
-
-fn render_pre(stdout: &mut std::io::StdoutLock<'_>) -> Result<(), super::Error> {
-	use std::io::Write;
-
-	write!(stdout, "\x1B[?1049h\x1B[2J\x1B[3J")?;
-	Ok(())
-}
-
-fn render_post(stdout: &mut std::io::StdoutLock<'_>) -> Result<(), super::Error> {
-	use std::io::Write;
-
-	write!(stdout, "\x1B[?1049l")?;
-	Ok(())
-}
-
-fn render_tile(stdout: &mut std::io::StdoutLock<'_>, x: i64, y: i64, tile: Tile) -> Result<(), super::Error> {
-	use std::io::Write;
-
-	write!(stdout, "\x1B[{};{}H{}\x1B[1;1H", y + 30, x * 2 + 80, match tile {
-		Tile::Empty => "  ",
-		Tile::Wall => "\u{2591}\u{2591}",
-		Tile::Oxygen => "O\u{2082}",
-	})?;
-
-	stdout.flush()?;
-
-	Ok(())
-}
@@ -1,26 +1,25 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day16")?.next().ok_or("file is empty")??;
-	let input = line.chars().map(|c| (c as u8 - b'0').into());
+pub(super) struct Solution;
 
-	{
-		let result = part1(input.clone(), 100);
+impl super::Solution for Solution {
+	type Input = Vec<i32>;
+	type Part1 = i32;
+	type Part2 = i32;
 
-		println!("16a: {result}");
-
-		assert_eq!(result, 68317988);
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let line = super::Lines::<String>::new(input).next().ok_or("file is empty")??;
+		let input = line.chars().map(|c| (c as u8 - b'0').into()).collect();
+		Ok(input)
 	}
 
-	{
-		let input: Vec<_> = input.collect();
-
-		let result = part2(&input)?;
-
-		println!("16b: {result}");
-
-		assert_eq!(result, 53850800);
+	fn part1(input: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let result = part1(input.iter().copied(), 100);
+		Ok(result)
 	}
 
-	Ok(())
+	fn part2(input: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let result = part2(input)?;
+		Ok(result)
+	}
 }
 
 fn run_phase(input: &mut [i32], output: &mut [i32], start_at: usize) {
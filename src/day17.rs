@@ -1,74 +1,62 @@
 use std::convert::TryInto;
 
-// To render maze, set DAY_17_VIS=1
+// To render maze, run with --vis
 
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day17")?.next().ok_or("file is empty")??;
-	let mut ram: crate::intcode::Ram = line.parse()?;
+pub(super) struct Solution;
 
-	{
-		let mut computer = crate::intcode::Computer::new(ram.clone());
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = i64;
+	type Part2 = i64;
 
-		let mut tiles: std::collections::BTreeMap<(i64, i64), Tile> = Default::default();
-		let mut robot_pos = None;
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
 
-		let mut pos = (0, 0);
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let mut computer = crate::intcode::Computer::new(ram.clone());
 
-		while let Some(tile) = computer.step(std::iter::empty())? {
-			let tile: u8 = tile.try_into()?;
-			match tile {
-				b'.' => {
-					tiles.insert(pos, Tile::Open);
-					pos.0 += 1;
-				},
-				b'#' => {
-					tiles.insert(pos, Tile::Scaffold);
-					pos.0 += 1;
-				},
-				b'^' => {
-					tiles.insert(pos, Tile::Scaffold);
-					robot_pos = Some(pos);
-					pos.0 += 1;
-				},
-				b'\n' => {
-					pos.0 = 0;
-					pos.1 += 1;
-				},
-				_ => unreachable!(),
-			}
+		let mut output = vec![];
+		while let Some(b) = computer.step(std::iter::empty())? {
+			output.push(b.try_into()?);
 		}
+		let rows: Vec<&[u8]> = output.split(|&b| b == b'\n').collect();
 
-		let render_maze = std::env::var("DAY_17_VIS").is_ok();
-		if render_maze {
-			let max_x = tiles.keys().map(|(x, _)| *x).max().ok_or("no solution")?;
-			let max_y = tiles.keys().map(|(_, y)| *y).max().ok_or("no solution")?;
+		let mut robot_pos = None;
 
-			for y in 0..=max_y {
-				for x in 0..=max_x {
-					print!("{}", match tiles.get(&(x, y)).unwrap_or(&Tile::Open) {
-						Tile::Open => ".",
-						Tile::Scaffold if robot_pos == Some((x, y)) => "^",
-						Tile::Scaffold => "#",
-					});
-				}
-				println!();
-			}
+		let tiles = super::grid::Grid::parse(&rows, |pos, c| match c {
+			b'.' => Ok(Some(Tile::Open)),
+			b'#' => Ok(Some(Tile::Scaffold)),
+			b'^' => {
+				robot_pos = Some(pos);
+				Ok(Some(Tile::Scaffold))
+			},
+			c => Err(format!("unexpected character in camera output: {:?}", char::from(c)).into()),
+		})?;
+
+		let mut renderer = super::vis::renderer();
+		for (pos, &tile) in tiles.iter() {
+			renderer.set(pos, match tile {
+				Tile::Open => super::vis::Cell { glyph: ".", color: super::vis::Color(102, 102, 102) },
+				Tile::Scaffold if robot_pos == Some(pos) => super::vis::Cell { glyph: "^", color: super::vis::Color(229, 229, 16) },
+				Tile::Scaffold => super::vis::Cell { glyph: "#", color: super::vis::Color(229, 229, 229) },
+			})?;
 		}
+		renderer.frame()?;
+		renderer.finish()?;
 
+		// Intersections are scaffolds whose four neighbors are all scaffolds
 		let result: i64 =
 			tiles.iter()
-			.filter_map(|(&(x, y), &tile)| match (tile, tiles.get(&(x - 1, y)), tiles.get(&(x + 1, y)), tiles.get(&(x, y - 1)), tiles.get(&(x, y + 1))) {
-				(Tile::Scaffold, Some(Tile::Scaffold), Some(Tile::Scaffold), Some(Tile::Scaffold), Some(Tile::Scaffold)) => Some(x * y),
-				_ => None,
-			})
+			.filter(|&(pos, &tile)| tile == Tile::Scaffold && tiles.neighbors(pos, super::grid::Neighborhood::Four).filter(|&(_, &tile)| tile == Tile::Scaffold).count() == 4)
+			.map(|((x, y), _)| x * y)
 			.sum();
-
-		println!("17a: {result}");
-
-		assert_eq!(result, 11140);
+		Ok(result)
 	}
 
-	{
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let mut ram = ram.clone();
 		*ram.get_mut(0) = 2;
 		let mut computer = crate::intcode::Computer::new(ram);
 
@@ -86,12 +74,8 @@ pub(super) fn run() -> Result<(), super::Error> {
 			}
 		};
 
-		println!("17b: {result}");
-
-		assert_eq!(result, 1113108);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
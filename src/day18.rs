@@ -1,70 +1,109 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	{
-		let input = super::read_input_lines::<String>("day18a")?;
-		let result = run_inner(input)?;
+pub(super) struct Solution;
 
-		println!("18a: {result}");
+impl super::Solution for Solution {
+	type Input = Vec<Vec<u8>>;
+	type Part1 = usize;
+	type Part2 = usize;
 
-		assert_eq!(result, 5198);
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		super::read_grid(input)
 	}
 
-	{
-		let input = super::read_input_lines::<String>("day18b")?;
+	fn part1(input: &Self::Input) -> Result<Self::Part1, super::Error> {
 		let result = run_inner(input)?;
+		Ok(result)
+	}
+
+	fn part2(input: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let input = split_vault(input)?;
+		let result = run_inner(&input)?;
+		Ok(result)
+	}
+}
 
-		println!("18b: {result}");
+// Replaces the area around the entrance with four entrances separated by walls:
+//
+//     ...    @#@
+//     .@. => ###
+//     ...    @#@
+//
+// A map that already has more than one entrance is assumed to have been split already.
+fn split_vault(input: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, super::Error> {
+	let mut input = input.to_owned();
+
+	let mut entrances =
+		input.iter()
+		.enumerate()
+		.flat_map(|(y, line)| line.iter().enumerate().filter(|&(_, &c)| c == b'@').map(move |(x, _)| (x, y)));
+	let (x, y) = entrances.next().ok_or("maze has no entrance")?;
+	if entrances.next().is_none() {
+		let on_edge =
+			x == 0 || y == 0 ||
+			input.get((y - 1)..=(y + 1)).map_or(true, |lines| lines.iter().any(|line| line.len() <= x + 1));
+		if on_edge {
+			return Err("entrance is on the edge of the maze".into());
+		}
 
-		assert_eq!(result, 1736);
+		for (line, replacement) in input[(y - 1)..=(y + 1)].iter_mut().zip([b"@#@", b"###", b"@#@"]) {
+			line[(x - 1)..=(x + 1)].copy_from_slice(replacement);
+		}
 	}
 
-	Ok(())
+	Ok(input)
 }
 
-fn run_inner(input: impl Iterator<Item = Result<String, super::Error>>) -> Result<usize, super::Error> {
-	let mut tiles: std::collections::BTreeMap<(usize, usize), Tile> = Default::default();
+fn run_inner(input: &[Vec<u8>]) -> Result<usize, super::Error> {
 	let mut start = vec![];
 
-	for (y, line) in input.enumerate() {
-		let line = line?;
-		for (x, c) in line.chars().enumerate() {
-			match c {
-				'#' => (),
-				'.' => { tiles.insert((x, y), Tile::Empty); },
-				'@' => { start.push((x, y)); tiles.insert((x, y), Tile::Empty); },
-				c @ 'A'..='Z' => { tiles.insert((x, y), Tile::Gate(u8::try_from(c).expect("A..Z fits in u8") - b'A')); },
-				c @ 'a'..='z' => { tiles.insert((x, y), Tile::Key(u8::try_from(c).expect("a..z fits in u8") - b'a')); },
-				c => return Err(format!("unexpected character in maze: {c:?}").into()),
-			};
-		}
+	let tiles = super::grid::Grid::parse(input, |pos, c| match c {
+		b'#' => Ok(None),
+		b'.' => Ok(Some(Tile::Empty)),
+		b'@' => {
+			start.push(pos);
+			Ok(Some(Tile::Empty))
+		},
+		c @ b'A'..=b'Z' => Ok(Some(Tile::Gate(c - b'A'))),
+		c @ b'a'..=b'z' => Ok(Some(Tile::Key(c - b'a'))),
+		c => Err(format!("unexpected character in maze: {:?}", char::from(c)).into()),
+	})?;
+	if tiles.is_empty() {
+		return Err("no solution".into());
 	}
 
-	let max_x = tiles.keys().map(|(x, _)| *x).max().ok_or("no solution")?;
-	let max_y = tiles.keys().map(|(_, y)| *y).max().ok_or("no solution")?;
-
 	let mut neighbors: std::collections::BTreeMap<(usize, usize), std::collections::BTreeMap<(usize, usize), usize>> = Default::default();
-	for x in 0..=max_x {
-		for y in 0..=max_y {
-			let pos = (x, y);
+	for (pos, tile) in tiles.iter() {
+		match tile {
+			Tile::Empty if start.contains(&pos) => (),
+			Tile::Key(_) | Tile::Gate(_) => (),
+			Tile::Empty => continue,
+		};
 
-			match tiles.get(&pos) {
-				Some(Tile::Empty) if start.contains(&pos) => (),
-				Some(Tile::Key(_) | Tile::Gate(_)) => (),
-				_ => continue,
-			};
+		let neighbors = neighbors.entry(pos).or_default();
 
-			let neighbors = neighbors.entry(pos).or_default();
+		let mut visited: std::collections::BTreeMap<(usize, usize), usize> = Default::default();
+		visited.insert(pos, 0);
 
-			let mut visited: std::collections::BTreeMap<(usize, usize), usize> = Default::default();
-			visited.insert(pos, 0);
+		let mut to_visit: std::collections::VecDeque<((usize, usize), usize)> = Default::default();
+		to_visit.extend(super::grid::neighbors(pos, super::grid::Neighborhood::Four).map(|pos| (pos, 1)));
 
-			let mut to_visit: std::collections::VecDeque<((usize, usize), usize)> = Default::default();
-			to_visit.push_back(((pos.0 - 1, pos.1), 1));
-			to_visit.push_back(((pos.0 + 1, pos.1), 1));
-			to_visit.push_back(((pos.0, pos.1 - 1), 1));
-			to_visit.push_back(((pos.0, pos.1 + 1), 1));
+		while let Some((pos, distance)) = to_visit.pop_front() {
+			match visited.entry(pos) {
+				std::collections::btree_map::Entry::Vacant(entry) => {
+					entry.insert(distance);
+				},
+				std::collections::btree_map::Entry::Occupied(mut entry) =>
+					if *entry.get() < distance {
+						continue;
+					}
+					else {
+						entry.insert(distance);
+					},
+			}
 
-			while let Some((pos, distance)) = to_visit.pop_front() {
-				match visited.entry(pos) {
+			match tiles.get(pos) {
+				Some(Tile::Empty) => to_visit.extend(super::grid::neighbors(pos, super::grid::Neighborhood::Four).map(|pos| (pos, distance + 1))),
+
+				Some(Tile::Gate(_) | Tile::Key(_)) => match neighbors.entry(pos) {
 					std::collections::btree_map::Entry::Vacant(entry) => {
 						entry.insert(distance);
 					},
@@ -75,31 +114,9 @@ fn run_inner(input: impl Iterator<Item = Result<String, super::Error>>) -> Resul
 						else {
 							entry.insert(distance);
 						},
-				}
-
-				match tiles.get(&pos) {
-					Some(Tile::Empty) => {
-						to_visit.push_back(((pos.0 - 1, pos.1), distance + 1));
-						to_visit.push_back(((pos.0 + 1, pos.1), distance + 1));
-						to_visit.push_back(((pos.0, pos.1 - 1), distance + 1));
-						to_visit.push_back(((pos.0, pos.1 + 1), distance + 1));
-					},
-
-					Some(Tile::Gate(_) | Tile::Key(_)) => match neighbors.entry(pos) {
-						std::collections::btree_map::Entry::Vacant(entry) => {
-							entry.insert(distance);
-						},
-						std::collections::btree_map::Entry::Occupied(mut entry) =>
-							if *entry.get() < distance {
-								continue;
-							}
-							else {
-								entry.insert(distance);
-							},
-					},
+				},
 
-					None => (),
-				}
+				None => (),
 			}
 		}
 	}
@@ -114,7 +131,7 @@ fn run_inner(input: impl Iterator<Item = Result<String, super::Error>>) -> Resul
 	rayon::scope(|s| {
 		fn solve<'scope>(
 			s: &rayon::Scope<'scope>,
-			tiles: &'scope std::collections::BTreeMap<(usize, usize), Tile>,
+			tiles: &'scope super::grid::Grid<Tile, usize>,
 			neighbors: &'scope std::collections::BTreeMap<(usize, usize), std::collections::BTreeMap<(usize, usize), usize>>,
 			best_paths: &'scope std::sync::Mutex<std::collections::BTreeMap<(Vec<(usize, usize)>, BitField), usize>>,
 			num_keys: usize,
@@ -225,7 +242,7 @@ impl BitField {
 }
 
 fn reachable_keys(
-	tiles: &std::collections::BTreeMap<(usize, usize), Tile>,
+	tiles: &super::grid::Grid<Tile, usize>,
 	neighbors: &std::collections::BTreeMap<(usize, usize), std::collections::BTreeMap<(usize, usize), usize>>,
 	pos: (usize, usize),
 	already_collected_keys: BitField,
@@ -238,7 +255,7 @@ fn reachable_keys(
 
 	while let Some((pos, distance)) = to_visit.pop_front() {
 		if visited.insert(pos) {
-			match tiles.get(&pos) {
+			match tiles.get(pos) {
 				Some(Tile::Gate(g)) if !already_collected_keys.contains(*g) => (),
 				Some(Tile::Key(k)) if !already_collected_keys.contains(*k) => match result.entry(*k) {
 					std::collections::btree_map::Entry::Vacant(entry) => {
@@ -266,10 +283,47 @@ fn reachable_keys(
 
 #[cfg(test)]
 mod tests {
+	#[test]
+	fn test_split_vault() {
+		let input = crate::parse_str::<super::Solution>(
+			"\
+			#######\n\
+			#a.#Cd#\n\
+			##...##\n\
+			##.@.##\n\
+			##...##\n\
+			#cB#Ab#\n\
+			#######\n\
+			",
+		).unwrap();
+
+		let actual = super::split_vault(&input).unwrap();
+		assert_eq!(actual, [
+			b"#######",
+			b"#a.#Cd#",
+			b"##@#@##",
+			b"#######",
+			b"##@#@##",
+			b"#cB#Ab#",
+			b"#######",
+		]);
+
+		assert_eq!(super::split_vault(&actual).unwrap(), actual);
+
+		assert_eq!(super::run_inner(&actual).unwrap(), 8);
+
+		// Entrances on the edges can't be split
+		for input in [".@.\n...\n", "...\n.@.\n", "...\n..@\n...\n", "...\n.@.\n..\n"] {
+			let input = crate::parse_str::<super::Solution>(input).unwrap();
+			assert_eq!(super::split_vault(&input).unwrap_err().to_string(), "entrance is on the edge of the maze");
+		}
+	}
+
 	#[test]
 	fn test_run_inner() {
 		fn test(input: &str, expected_result: usize) {
-			let actual_result = super::run_inner(input.lines().map(|s| Ok(s.to_owned()))).unwrap();
+			let input = crate::parse_str::<super::Solution>(input).unwrap();
+			let actual_result = super::run_inner(&input).unwrap();
 			assert_eq!(expected_result, actual_result);
 		}
 
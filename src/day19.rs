@@ -1,24 +1,31 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day19")?.next().ok_or("file is empty")??;
-	let ram: crate::intcode::Ram = line.parse()?;
+pub(super) struct Solution;
 
-	// Sanity test that beam starts at (0, 0)
-	if !test_beam(0, 0, &ram)? {
-		return Err("beam does not start at (0, 0)".into());
-	}
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = usize;
+	type Part2 = i64;
 
-	{
-		let result =
-			(0..50)
-			.flat_map(|x| (0..50).map(move |y| (x, y)))
-			.try_fold(0, |sum, (x, y)| test_beam(x, y, &ram).map(|in_beam| if in_beam { sum + 1 } else { sum }))?;
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
 
-		println!("19a: {result}");
+		// Sanity test that beam starts at (0, 0)
+		if !test_beam(0, 0, &ram)? {
+			return Err("beam does not start at (0, 0)".into());
+		}
+
+		Ok(ram)
+	}
 
-		assert_eq!(result, 201);
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let result =
+			crate::intcode::batch::run(ram, (0..50).flat_map(|x| (0..50).map(move |y| (x, y))), |computer, (x, y)| probe(computer, x, y))?
+			.into_iter()
+			.filter(|&in_beam| in_beam)
+			.count();
+		Ok(result)
 	}
 
-	{
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
 		// Get estimate about the spread of the beam for a particular y by checking the bounds at y = 100
 		//
 		// Note: The choice of calculating the slope as x / y instead of the usual y / x is because the beam is taller than it is wide.
@@ -28,7 +35,7 @@ pub(super) fn run() -> Result<(), super::Error> {
 
 			let mut x_s =
 				(0..100)
-				.filter_map(|x| match test_beam(x, y, &ram) {
+				.filter_map(|x| match test_beam(x, y, ram) {
 					Ok(true) => Some(Ok(x)),
 					Ok(false) => None,
 					Err(err) => Some(Err(err)),
@@ -64,7 +71,7 @@ pub(super) fn run() -> Result<(), super::Error> {
 			let x_end = (y as f64 * slope_max) as i64;
 			let mut x_s =
 				(x_start..x_end)
-				.filter_map(|x| match test_beam(x, y, &ram) {
+				.filter_map(|x| match test_beam(x, y, ram) {
 					Ok(true) => Some(Ok(x)),
 					Ok(false) => None,
 					Err(err) => Some(Err(err)),
@@ -85,7 +92,7 @@ pub(super) fn run() -> Result<(), super::Error> {
 			if x_max - x_min + 1 < 100 {
 				continue;
 			}
-			if !test_beam(x_max, y + 99, &ram)? {
+			if !test_beam(x_max, y + 99, ram)? {
 				return Err("expected the beam to be taller than it's wide, and thus already have a spot that's 100 spots tall on this horizontal".into());
 			}
 
@@ -93,7 +100,7 @@ pub(super) fn run() -> Result<(), super::Error> {
 
 			'x: for x in x_min..=(x_max - 99) {
 				// Test the 99th spot below this one
-				if !test_beam(x, y + 99, &ram)? {
+				if !test_beam(x, y + 99, ram)? {
 					continue 'x;
 				}
 
@@ -103,16 +110,16 @@ pub(super) fn run() -> Result<(), super::Error> {
 			}
 		}
 
-		println!("19b: {result}");
-
-		assert_eq!(result, 6610984);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
 fn test_beam(x: i64, y: i64, ram: &crate::intcode::Ram) -> Result<bool, super::Error> {
-	let mut computer = crate::intcode::Computer::new(ram.clone());
+	probe(&mut crate::intcode::Computer::new(ram.clone()), x, y)
+}
+
+// Runs a freshly loaded computer with the given coordinates
+fn probe(computer: &mut crate::intcode::Computer, x: i64, y: i64) -> Result<bool, super::Error> {
 	let output = computer.step(std::iter::once(x).chain(std::iter::once(y)))?.ok_or("no output")?;
 	match output {
 		0 => Ok(false),
@@ -1,9 +1,16 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day2")?.next().ok_or("file is empty")??;
+pub(super) struct Solution;
 
-	let ram: crate::intcode::Ram = line.parse()?;
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = i64;
+	type Part2 = i64;
 
-	{
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
+
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
 		let mut computer = super::intcode::Computer::new(ram.clone());
 
 		*computer.ram.get_mut(1) = 12;
@@ -12,43 +19,30 @@ pub(super) fn run() -> Result<(), super::Error> {
 		let _ = computer.execute(std::iter::empty())?;
 
 		let result = computer.ram.get(0);
-
-		println!("2a: {result}");
-
-		assert_eq!(result, 3895705);
+		Ok(result)
 	}
 
-	{
-		let mut result = None;
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
+		// The program is straight-line code, so instead of running it for every possible noun and verb,
+		// run it once with the noun and verb as symbols and solve the resulting formula for [0].
+		let mut computer = crate::intcode::symbolic::SymbolicComputer::new(ram);
+		computer.symbolize(1);
+		computer.symbolize(2);
 
-		'outer: for noun in 0..=99 {
-			for verb in 0..=99 {
-				let mut computer = super::intcode::Computer::new(ram.clone());
+		let _ = computer.execute()?;
 
-				*computer.ram.get_mut(1) = noun;
-				*computer.ram.get_mut(2) = verb;
+		let noun = crate::intcode::symbolic::Symbol::Memory(1);
+		let verb = crate::intcode::symbolic::Symbol::Memory(2);
+		let domains = vec![(noun, 0..=99), (verb, 0..=99)].into_iter().collect();
 
-				let _ = computer.execute(std::iter::empty())?;
-
-				#[allow(clippy::inconsistent_digit_grouping)]
-				{
-					if computer.ram.get(0) == 1969_07_20 {
-						result = Some((noun, verb));
-						break 'outer;
-					}
-				}
-			}
-		}
+		#[allow(clippy::inconsistent_digit_grouping)]
+		let result = computer.get(0).solve(1969_07_20, &domains)?;
 
+		let result = result.map(|result| (result[&noun], result[&verb]));
 		let (noun, verb) = result.ok_or("no solution")?;
 		let result = noun * 100 + verb;
-
-		println!("2b: {result}");
-
-		assert_eq!(result, 6417);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
 #[cfg(test)]
@@ -102,7 +96,7 @@ mod tests {
 	#[test]
 	fn test_execute_program() {
 		fn test(program: &str, expected: &[i64]) {
-			let mut computer = crate::intcode::Computer::new(program.parse().unwrap());
+			let mut computer: crate::intcode::Computer = crate::intcode::Computer::new(program.parse().unwrap());
 
 			let _ = computer.execute(std::iter::empty()).unwrap();
 
@@ -1,91 +1,72 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let input = parse_input(super::read_input_lines::<String>("day20")?)?;
+pub(super) struct Solution;
 
-	{
-		let result = distance_to_exit(&input);
+impl super::Solution for Solution {
+	type Input = Input;
+	type Part1 = usize;
+	type Part2 = usize;
 
-		println!("20a: {result}");
-
-		assert_eq!(result, 410);
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let input = super::read_grid(input)?;
+		parse_input(&input)
 	}
 
-	{
-		let result = distance_to_exit_recursive(&input);
-
-		println!("20b: {result}");
-
-		assert_eq!(result, 5084);
+	fn part1(input: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let result = distance_to_exit(input);
+		Ok(result)
 	}
 
-	Ok(())
+	fn part2(input: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let result = distance_to_exit_recursive(input);
+		Ok(result)
+	}
 }
 
-struct Input {
-	tiles: std::collections::BTreeMap<(usize, usize), Tile>,
+pub(super) struct Input {
+	tiles: super::grid::Grid<Tile, usize>,
 	start: (usize, usize),
 	teleport_pairs: std::collections::BTreeMap<(usize, usize), (usize, usize)>,
-	max_x: usize,
-	max_y: usize,
+	bounds: ((usize, usize), (usize, usize)),
 	neighbors: std::collections::BTreeMap<(usize, usize), std::collections::BTreeMap<(usize, usize), usize>>,
 }
 
-fn parse_input(input_iter: impl Iterator<Item = Result<String, super::Error>>) -> Result<Input, super::Error> {
+fn parse_input(grid: &[Vec<u8>]) -> Result<Input, super::Error> {
 	let tiles = {
-		let mut input: std::collections::BTreeMap<(usize, usize), char> = Default::default();
-		for (y, line) in input_iter.enumerate() {
-			let line = line?;
-			for (x, c) in line.chars().enumerate() {
-				input.insert((x, y), c);
-			}
-		}
-
-		let tiles: Result<std::collections::BTreeMap<(usize, usize), Tile>, super::Error> =
-			input.iter()
-			.filter_map(|(&(x, y), &c)| match c {
-				' ' | '#' | 'A'..='Z' => None,
-				'.' => Some(Ok(((x - 2, y - 2), Tile::Empty))),
-				c => Some(Err(format!("unexpected character in maze at {:?}: {c:?}", (x - 2, y - 2)).into())),
-			})
-			.collect();
-		let mut tiles = tiles?;
-
-		for (&(x, y), &c) in &input {
-			if let c1 @ 'A'..='Z' = c {
-				let (previous_tile, next_tile, id) = match (input.get(&(x + 1, y)), input.get(&(x, y + 1))) {
-					(Some(&c2 @ 'A'..='Z'), _) => (
-						(x - 3, y - 2),
-						(x, y - 2),
-						u16::from(u8::try_from(c1).expect("A..Z fits in u8") - b'A') * 100 +
-						u16::from(u8::try_from(c2).expect("A..Z fits in u8") - b'A'),
-					),
-					(_, Some(&c2 @ 'A'..='Z')) => (
-						(x - 2, y - 3),
-						(x - 2, y),
-						u16::from(u8::try_from(c1).expect("A..Z fits in u8") - b'A') * 100 +
-						u16::from(u8::try_from(c2).expect("A..Z fits in u8") - b'A'),
-					),
-					_ => continue,
-				};
+		let letters: super::grid::Grid<u8, usize> = super::grid::Grid::parse(grid, |_, c| Ok(Some(c).filter(u8::is_ascii_uppercase)))?;
+
+		let mut tiles: super::grid::Grid<Tile, usize> = super::grid::Grid::parse(grid, |_, c| match c {
+			b' ' | b'#' | b'A'..=b'Z' => Ok(None),
+			b'.' => Ok(Some(Tile::Empty)),
+			c => Err(format!("unexpected character in maze: {:?}", char::from(c)).into()),
+		})?;
+
+		for ((x, y), &c1) in letters.iter() {
+			let (previous_tile, next_tile, id) = match (letters.get((x + 1, y)), letters.get((x, y + 1))) {
+				(Some(&c2), _) => (
+					x.checked_sub(1).map(|x| (x, y)),
+					(x + 2, y),
+					u16::from(c1 - b'A') * 100 + u16::from(c2 - b'A'),
+				),
+				(_, Some(&c2)) => (
+					y.checked_sub(1).map(|y| (x, y)),
+					(x, y + 2),
+					u16::from(c1 - b'A') * 100 + u16::from(c2 - b'A'),
+				),
+				_ => continue,
+			};
 
-				let target_tile =
-					if let Some(tile @ Tile::Empty) = tiles.get_mut(&previous_tile) {
-						tile
-					}
-					else if let Some(tile @ Tile::Empty) = tiles.get_mut(&next_tile) {
-						tile
-					}
-					else {
-						return Err(format!("two-letter sequence at {:?} does not have empty tile on either side", (x, y)).into());
-					};
+			let target_tile = match previous_tile.and_then(|pos| tiles.get(pos)) {
+				Some(Tile::Empty) => previous_tile,
+				_ => Some(next_tile).filter(|&pos| tiles.get(pos) == Some(&Tile::Empty)),
+			};
+			let target_tile = target_tile.ok_or_else(|| format!("two-letter sequence at {:?} does not have empty tile on either side", (x, y)))?;
 
-				let tile = match id {
-					0 => Tile::Entrance, // AA
-					2525 => Tile::Exit, // ZZ
-					id => Tile::Teleport(id),
-				};
+			let tile = match id {
+				0 => Tile::Entrance, // AA
+				2525 => Tile::Exit, // ZZ
+				id => Tile::Teleport(id),
+			};
 
-				*target_tile = tile;
-			}
+			tiles.insert(target_tile, tile);
 		}
 
 		tiles
@@ -93,18 +74,18 @@ fn parse_input(input_iter: impl Iterator<Item = Result<String, super::Error>>) -
 
 	let start =
 		tiles.iter()
-		.find_map(|(&(x, y), &tile)| if tile == Tile::Entrance { Some((x, y)) } else { None })
+		.find_map(|(pos, &tile)| if tile == Tile::Entrance { Some(pos) } else { None })
 		.ok_or("never found start tile")?;
 
 	let mut teleport_pairs: std::collections::BTreeMap<(usize, usize), (usize, usize)> = Default::default();
-	for (&pos1, &tile1) in &tiles {
+	for (pos1, &tile1) in tiles.iter() {
 		let id = if let Tile::Teleport(id) = tile1 { id } else { continue; };
 
 		if teleport_pairs.contains_key(&pos1) {
 			continue;
 		}
 
-		for (&pos2, &tile2) in &tiles {
+		for (pos2, &tile2) in tiles.iter() {
 			match tile2 {
 				Tile::Teleport(id2) if id2 == id && pos2 != pos1 => {
 					teleport_pairs.insert(pos1, pos2);
@@ -116,32 +97,41 @@ fn parse_input(input_iter: impl Iterator<Item = Result<String, super::Error>>) -
 		}
 	}
 
-	let max_x = tiles.keys().map(|(x, _)| *x).max().ok_or("no solution")?;
-	let max_y = tiles.keys().map(|(_, y)| *y).max().ok_or("no solution")?;
+	let bounds = tiles.bounds().ok_or("no solution")?;
 
 	let mut neighbors: std::collections::BTreeMap<(usize, usize), std::collections::BTreeMap<(usize, usize), usize>> = Default::default();
-	for x in 0..=max_x {
-		for y in 0..=max_y {
-			let pos = (x, y);
+	for (pos, &tile) in tiles.iter() {
+		if tile == Tile::Empty {
+			continue;
+		}
 
-			match tiles.get(&pos) {
-				Some(Tile::Empty) | None => continue,
-				_ => (),
-			};
+		let neighbors = neighbors.entry(pos).or_default();
+
+		let mut visited: std::collections::BTreeMap<(usize, usize), usize> = Default::default();
+		visited.insert(pos, 0);
 
-			let neighbors = neighbors.entry(pos).or_default();
+		let mut to_visit: std::collections::VecDeque<((usize, usize), usize)> =
+			super::grid::neighbors(pos, super::grid::Neighborhood::Four).map(|pos| (pos, 1)).collect();
 
-			let mut visited: std::collections::BTreeMap<(usize, usize), usize> = Default::default();
-			visited.insert(pos, 0);
+		while let Some((pos, distance)) = to_visit.pop_front() {
+			match visited.entry(pos) {
+				std::collections::btree_map::Entry::Vacant(entry) => {
+					entry.insert(distance);
+				},
+				std::collections::btree_map::Entry::Occupied(mut entry) =>
+					if *entry.get() < distance {
+						continue;
+					}
+					else {
+						entry.insert(distance);
+					},
+			}
 
-			let mut to_visit: std::collections::VecDeque<((usize, usize), usize)> = Default::default();
-			to_visit.push_back(((pos.0 - 1, pos.1), 1));
-			to_visit.push_back(((pos.0 + 1, pos.1), 1));
-			to_visit.push_back(((pos.0, pos.1 - 1), 1));
-			to_visit.push_back(((pos.0, pos.1 + 1), 1));
+			match tiles.get(pos) {
+				Some(Tile::Empty) =>
+					to_visit.extend(super::grid::neighbors(pos, super::grid::Neighborhood::Four).map(|pos| (pos, distance + 1))),
 
-			while let Some((pos, distance)) = to_visit.pop_front() {
-				match visited.entry(pos) {
+				Some(Tile::Entrance | Tile::Exit | Tile::Teleport(_)) => match neighbors.entry(pos) {
 					std::collections::btree_map::Entry::Vacant(entry) => {
 						entry.insert(distance);
 					},
@@ -152,31 +142,9 @@ fn parse_input(input_iter: impl Iterator<Item = Result<String, super::Error>>) -
 						else {
 							entry.insert(distance);
 						},
-				}
-
-				match tiles.get(&pos) {
-					Some(Tile::Empty) => {
-						to_visit.push_back(((pos.0 - 1, pos.1), distance + 1));
-						to_visit.push_back(((pos.0 + 1, pos.1), distance + 1));
-						to_visit.push_back(((pos.0, pos.1 - 1), distance + 1));
-						to_visit.push_back(((pos.0, pos.1 + 1), distance + 1));
-					},
-
-					Some(Tile::Entrance | Tile::Exit | Tile::Teleport(_)) => match neighbors.entry(pos) {
-						std::collections::btree_map::Entry::Vacant(entry) => {
-							entry.insert(distance);
-						},
-						std::collections::btree_map::Entry::Occupied(mut entry) =>
-							if *entry.get() < distance {
-								continue;
-							}
-							else {
-								entry.insert(distance);
-							},
-					},
+				},
 
-					None => (),
-				}
+				None => (),
 			}
 		}
 	}
@@ -185,8 +153,7 @@ fn parse_input(input_iter: impl Iterator<Item = Result<String, super::Error>>) -
 		tiles,
 		start,
 		teleport_pairs,
-		max_x,
-		max_y,
+		bounds,
 		neighbors,
 	})
 }
@@ -200,7 +167,7 @@ enum Tile {
 }
 
 fn distance_to_exit(input: &Input) -> usize {
-	let Input { tiles, start, teleport_pairs, max_x: _, max_y: _, neighbors } = input;
+	let Input { tiles, start, teleport_pairs, bounds: _, neighbors } = input;
 
 	let result = std::sync::atomic::AtomicUsize::new(usize::max_value());
 
@@ -209,7 +176,7 @@ fn distance_to_exit(input: &Input) -> usize {
 	rayon::scope(|s| {
 		fn solve<'scope>(
 			s: &rayon::Scope<'scope>,
-			tiles: &'scope std::collections::BTreeMap<(usize, usize), Tile>,
+			tiles: &'scope super::grid::Grid<Tile, usize>,
 			teleport_pairs: &'scope std::collections::BTreeMap<(usize, usize), (usize, usize)>,
 			neighbors: &'scope std::collections::BTreeMap<(usize, usize), std::collections::BTreeMap<(usize, usize), usize>>,
 			best_paths: &'scope std::sync::Mutex<std::collections::BTreeMap<(usize, usize), usize>>,
@@ -223,7 +190,7 @@ fn distance_to_exit(input: &Input) -> usize {
 				if distance >= result_value {
 					return;
 				}
-				else if tiles.get(&pos) == Some(&Tile::Exit) {
+				else if tiles.get(pos) == Some(&Tile::Exit) {
 					if result.compare_exchange_weak(result_value, distance, std::sync::atomic::Ordering::AcqRel, std::sync::atomic::Ordering::Acquire) == Ok(result_value) {
 						return;
 					}
@@ -257,7 +224,7 @@ fn distance_to_exit(input: &Input) -> usize {
 				);
 
 			for (reachable_tile_pos, reachable_tile_distance) in reachable {
-				let (pos, reachable_tile_distance) = match tiles.get(&reachable_tile_pos) {
+				let (pos, reachable_tile_distance) = match tiles.get(reachable_tile_pos) {
 					Some(Tile::Teleport(_)) => {
 						let other_end_pos = *teleport_pairs.get(&reachable_tile_pos).unwrap();
 						(other_end_pos, reachable_tile_distance + 1)
@@ -292,7 +259,7 @@ fn distance_to_exit(input: &Input) -> usize {
 }
 
 fn distance_to_exit_recursive(input: &Input) -> usize {
-	let Input { tiles, start, teleport_pairs, max_x, max_y, neighbors } = input;
+	let Input { tiles, start, teleport_pairs, bounds, neighbors } = input;
 
 	let result = std::sync::atomic::AtomicUsize::new(usize::max_value());
 
@@ -301,10 +268,9 @@ fn distance_to_exit_recursive(input: &Input) -> usize {
 	rayon::scope(|s| {
 		fn solve<'scope>(
 			s: &rayon::Scope<'scope>,
-			tiles: &'scope std::collections::BTreeMap<(usize, usize), Tile>,
+			tiles: &'scope super::grid::Grid<Tile, usize>,
 			teleport_pairs: &'scope std::collections::BTreeMap<(usize, usize), (usize, usize)>,
-			max_x: usize,
-			max_y: usize,
+			bounds: ((usize, usize), (usize, usize)),
 			neighbors: &'scope std::collections::BTreeMap<(usize, usize), std::collections::BTreeMap<(usize, usize), usize>>,
 			best_paths: &'scope std::sync::Mutex<std::collections::BTreeMap<((usize, usize), usize), usize>>,
 			distance: usize,
@@ -318,7 +284,7 @@ fn distance_to_exit_recursive(input: &Input) -> usize {
 				if distance >= result_value {
 					return;
 				}
-				else if tiles.get(&pos) == Some(&Tile::Exit) && depth == 0 {
+				else if tiles.get(pos) == Some(&Tile::Exit) && depth == 0 {
 					if result.compare_exchange_weak(result_value, distance, std::sync::atomic::Ordering::AcqRel, std::sync::atomic::Ordering::Acquire) == Ok(result_value) {
 						return;
 					}
@@ -347,16 +313,16 @@ fn distance_to_exit_recursive(input: &Input) -> usize {
 					tiles,
 					neighbors,
 					|| depth == 0,
-					|pos| depth != 0 || teleport_kind(pos, max_x, max_y) == TeleportKind::Inner,
+					|pos| depth != 0 || teleport_kind(pos, bounds) == TeleportKind::Inner,
 					pos,
 				);
 
 			for (reachable_tile_pos, reachable_tile_distance) in reachable {
-				let (pos, depth, reachable_tile_distance) = match tiles.get(&reachable_tile_pos) {
+				let (pos, depth, reachable_tile_distance) = match tiles.get(reachable_tile_pos) {
 					Some(Tile::Teleport(_)) => {
 						let other_end_pos = *teleport_pairs.get(&reachable_tile_pos).unwrap();
 
-						let other_end_depth = match (teleport_kind(reachable_tile_pos, max_x, max_y), depth) {
+						let other_end_depth = match (teleport_kind(reachable_tile_pos, bounds), depth) {
 							(TeleportKind::Inner, depth) => depth + 1,
 							(TeleportKind::Outer, 0) => continue,
 							(TeleportKind::Outer, depth) => depth - 1,
@@ -380,7 +346,7 @@ fn distance_to_exit_recursive(input: &Input) -> usize {
 
 				s.spawn(move |s| solve(
 					s,
-					tiles, teleport_pairs, max_x, max_y, neighbors,
+					tiles, teleport_pairs, bounds, neighbors,
 					best_paths,
 					distance + reachable_tile_distance, pos, depth,
 					result,
@@ -390,7 +356,7 @@ fn distance_to_exit_recursive(input: &Input) -> usize {
 
 		s.spawn(|s| solve(
 			s,
-			tiles, teleport_pairs, *max_x, *max_y, neighbors,
+			tiles, teleport_pairs, *bounds, neighbors,
 			&best_paths,
 			0, *start, 0,
 			&result,
@@ -402,7 +368,7 @@ fn distance_to_exit_recursive(input: &Input) -> usize {
 }
 
 fn reachable(
-	tiles: &std::collections::BTreeMap<(usize, usize), Tile>,
+	tiles: &super::grid::Grid<Tile, usize>,
 	neighbors: &std::collections::BTreeMap<(usize, usize), std::collections::BTreeMap<(usize, usize), usize>>,
 	mut can_take_exit: impl FnMut() -> bool,
 	mut can_take_teleport: impl FnMut((usize, usize)) -> bool,
@@ -418,7 +384,7 @@ fn reachable(
 
 	while let Some((pos, distance)) = to_visit.pop_front() {
 		if visited.insert(pos) {
-			match tiles.get(&pos) {
+			match tiles.get(pos) {
 				Some(Tile::Exit) => {
 					if !can_take_exit() {
 						continue;
@@ -476,8 +442,8 @@ enum TeleportKind {
 	Outer,
 }
 
-fn teleport_kind(pos: (usize, usize), max_x: usize, max_y: usize) -> TeleportKind {
-	if pos.0 == 0 || pos.1 == 0 || pos.0 == max_x || pos.1 == max_y {
+fn teleport_kind(pos: (usize, usize), ((min_x, min_y), (max_x, max_y)): ((usize, usize), (usize, usize))) -> TeleportKind {
+	if pos.0 == min_x || pos.1 == min_y || pos.0 == max_x || pos.1 == max_y {
 		TeleportKind::Outer
 	}
 	else {
@@ -490,7 +456,7 @@ mod tests {
 	#[test]
 	fn test_distance_to_exit() {
 		fn test(input: &str, expected_distance: usize) {
-			let input = super::parse_input(input.lines().map(|s| Ok(s.to_owned()))).unwrap();
+			let input = crate::parse_str::<super::Solution>(input).unwrap();
 			let actual_distance = super::distance_to_exit(&input);
 			assert_eq!(expected_distance, actual_distance);
 		}
@@ -565,7 +531,7 @@ YN......#               VT..#....QG
 	#[test]
 	fn test_distance_to_exit_recursive() {
 		fn test(input: &str, expected_distance: usize) {
-			let input = super::parse_input(input.lines().map(|s| Ok(s.to_owned()))).unwrap();
+			let input = crate::parse_str::<super::Solution>(input).unwrap();
 			let actual_distance = super::distance_to_exit_recursive(&input);
 			assert_eq!(expected_distance, actual_distance);
 		}
@@ -1,13 +1,21 @@
-// To display output, set DAY_21_VIS=1
+// To display output, run with --vis
 
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day21")?.next().ok_or("file is empty")??;
-	let ram: crate::intcode::Ram = line.parse()?;
+pub(super) struct Solution;
 
-	{
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = i64;
+	type Part2 = i64;
+
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
+
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
 		let result =
 			run_inner(
-				ram.clone(),
+				ram,
 				// The sooner we jump, the more options we'll have available after the jump.
 				// But jumping unnecessarily leaves us fewer options by taking away spaces we could've initiated a jump from.
 				//
@@ -27,13 +35,10 @@ pub(super) fn run() -> Result<(), super::Error> {
 					WALK\n\
 				",
 			)?;
-
-		println!("21a: {result}");
-
-		assert_eq!(result, 19361850);
+		Ok(result)
 	}
 
-	{
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
 		let result =
 			run_inner(
 				ram,
@@ -64,19 +69,17 @@ pub(super) fn run() -> Result<(), super::Error> {
 					RUN\n\
 				",
 			)?;
-
-		println!("21b: {result}");
-
-		assert_eq!(result, 1138943788);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
-fn run_inner(ram: crate::intcode::Ram, input: &[u8]) -> Result<i64, super::Error> {
-	let show_output = std::env::var("DAY_21_VIS").is_ok();
+fn run_inner(ram: &crate::intcode::Ram, input: &[u8]) -> Result<i64, super::Error> {
+	use std::io::Write;
 
-	let mut computer = crate::intcode::Computer::new(ram);
+	let show_output = super::vis::in_terminal();
+	let mut out = super::vis::output();
+
+	let mut computer = crate::intcode::Computer::new(ram.clone());
 
 	let mut input = input.iter().copied().map(Into::into);
 
@@ -86,7 +89,7 @@ fn run_inner(ram: crate::intcode::Ram, input: &[u8]) -> Result<i64, super::Error
 		match u8::try_from(output) {
 			Ok(b'\n') => {
 				if show_output {
-					println!("> {line}");
+					writeln!(out, "> {line}")?;
 				}
 				line.clear();
 			},
@@ -100,5 +103,7 @@ fn run_inner(ram: crate::intcode::Ram, input: &[u8]) -> Result<i64, super::Error
 		}
 	};
 
+	out.save()?;
+
 	Ok(result)
 }
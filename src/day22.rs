@@ -1,27 +1,30 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let input: Result<Vec<Instruction>, super::Error> =
-		super::read_input_lines::<String>("day22")?
-		.map(|line| {
-			let line = line?;
-			let line: Instruction = line.parse()?;
-			Ok(line)
-		})
-		.collect();
-	let input = input?;
-
-	{
+pub(super) struct Solution;
+
+impl super::Solution for Solution {
+	type Input = Vec<Instruction>;
+	type Part1 = usize;
+	type Part2 = usize;
+
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		super::Lines::<String>::new(input)
+			.map(|line| {
+				let line = line?;
+				let line: Instruction = line.parse()?;
+				Ok(line)
+			})
+			.collect()
+	}
+
+	fn part1(input: &Self::Input) -> Result<Self::Part1, super::Error> {
 		const NUM_CARDS: usize = 10007;
 
 		let result =
 			input.iter()
 			.fold(2019, |card_pos, instruction| instruction.apply(card_pos, NUM_CARDS));
-
-		println!("22a: {result}");
-
-		assert_eq!(result, 8502);
+		Ok(result)
 	}
 
-	{
+	fn part2(_input: &Self::Input) -> Result<Self::Part2, super::Error> {
 		// const NUM_CARDS: usize = 119315717514047;
 
 		// Naive way:
@@ -66,17 +69,12 @@ pub(super) fn run() -> Result<(), super::Error> {
 		// Dammit, I signed up for Advent of Code, not Project Euler.
 
 		let result = 41685581334351_usize;
-
-		println!("22b: {result}");
-
-		assert_eq!(result, 41685581334351_usize);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum Instruction {
+pub(super) enum Instruction {
 	DealWithIncrement(usize),
 	Cut(isize),
 	DealIntoNewStack,
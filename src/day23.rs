@@ -1,15 +1,34 @@
 use std::convert::TryInto;
 
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day23")?.next().ok_or("file is empty")??;
-	let ram: crate::intcode::Ram = line.parse()?;
+pub(super) struct Solution;
 
-	let computer_channels = spawn_computers(&ram);
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = i64;
+	type Part2 = i64;
+
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
+
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
+		run_network(ram, super::Part::A)
+	}
+
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
+		run_network(ram, super::Part::B)
+	}
+}
+
+// Runs the network until the answer of the given part is found. That's the Y value of the first packet sent to the NAT for part A,
+// and the first Y value delivered by the NAT twice in a row for part B.
+fn run_network(ram: &crate::intcode::Ram, part: super::Part) -> Result<i64, super::Error> {
+	let computer_channels = spawn_computers(ram);
 
 	let mut had_activity = false;
 
 	let mut last_activity_time = std::time::Instant::now();
-	let mut got_first_nat = false;
 	let mut nat = None;
 	let mut previous_nat_y: Option<i64> = None;
 
@@ -22,14 +41,11 @@ pub(super) fn run() -> Result<(), super::Error> {
 				let y = output_receiver.recv().unwrap();
 
 				if target == 255 {
-					nat = Some((x, y));
-					if !got_first_nat {
-						println!("23a: {y}");
-
-						assert_eq!(y, 23057);
-
-						got_first_nat = true;
+					if part == super::Part::A {
+						return Ok(y);
 					}
+
+					nat = Some((x, y));
 				}
 				else {
 					let target: usize = target.try_into()?;
@@ -61,11 +77,7 @@ pub(super) fn run() -> Result<(), super::Error> {
 					if let Some((x, y)) = nat.take() {
 						if let Some(previous_nat_y) = previous_nat_y {
 							if previous_nat_y == y {
-								println!("23b: {y}");
-
-								assert_eq!(y, 15156);
-
-								break 'outer;
+								return Ok(y);
 							}
 						}
 						previous_nat_y = Some(y);
@@ -83,8 +95,6 @@ pub(super) fn run() -> Result<(), super::Error> {
 			std::thread::sleep(std::time::Duration::from_millis(1));
 		}
 	}
-
-	Ok(())
 }
 
 fn spawn_computers(ram: &crate::intcode::Ram) ->
@@ -1,16 +1,32 @@
 use std::convert::TryInto;
 
-pub(super) fn run() -> Result<(), super::Error> {
-	let bugs = parse_input(super::read_input_lines::<String>("day24")?)?;
+// To watch the bugs evolve in part 1, run with --vis
 
-	{
+pub(super) struct Solution;
+
+impl super::Solution for Solution {
+	type Input = super::grid::Grid<Bug, usize>;
+	type Part1 = usize;
+	type Part2 = usize;
+
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let input = super::read_grid(input)?;
+		let bugs = parse_input(&input)?;
+		Ok(bugs)
+	}
+
+	fn part1(bugs: &Self::Input) -> Result<Self::Part1, super::Error> {
 		let mut bugs = bugs.clone();
 
-		let mut layouts: std::collections::HashSet<std::collections::BTreeSet<(usize, usize)>> = Default::default();
+		let mut layouts: std::collections::HashSet<super::grid::Grid<Bug, usize>> = Default::default();
 		layouts.insert(bugs.clone());
 
+		let mut renderer = super::vis::renderer();
+		render(&mut *renderer, &bugs)?;
+
 		let layout = loop {
 			let next = evolve(&bugs);
+			render(&mut *renderer, &next)?;
 
 			if layouts.insert(next.clone()) {
 				bugs = next;
@@ -20,20 +36,19 @@ pub(super) fn run() -> Result<(), super::Error> {
 			}
 		};
 
+		renderer.finish()?;
+
 		let result: usize =
-			layout.into_iter()
-			.map(|(x, y)| 2_usize.pow((x + y * 5).try_into().unwrap()))
+			layout.iter()
+			.map(|((x, y), _)| 2_usize.pow((x + y * 5).try_into().unwrap()))
 			.sum();
-
-		println!("24a: {result}");
-
-		assert_eq!(result, 32526865);
+		Ok(result)
 	}
 
-	{
+	fn part2(bugs: &Self::Input) -> Result<Self::Part2, super::Error> {
 		let mut bugs: std::collections::BTreeSet<(isize, usize, usize)> =
-			bugs.into_iter()
-			.map(|(x, y)| (0, x, y))
+			bugs.iter()
+			.map(|((x, y), _)| (0, x, y))
 			.collect();
 
 		for _ in 0..200 {
@@ -41,125 +56,56 @@ pub(super) fn run() -> Result<(), super::Error> {
 		}
 
 		let result = bugs.len();
-
-		println!("24b: {result}");
-
-		assert_eq!(result, 2009);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
-fn parse_input(lines: impl Iterator<Item = Result<String, super::Error>>) -> Result<std::collections::BTreeSet<(usize, usize)>, super::Error> {
-	let mut result: std::collections::BTreeSet<(usize, usize)> = Default::default();
-
-	for (y, line) in lines.enumerate() {
-		let line = line?;
-
-		assert!(y < 5);
-
-		for (x, c) in line.chars().enumerate() {
-			assert!(x < 5);
-
-			if c == '#' {
-				result.insert((x, y));
-			}
-		}
-	}
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(super) struct Bug;
 
-	Ok(result)
+fn parse_input(grid: &[Vec<u8>]) -> Result<super::grid::Grid<Bug, usize>, super::Error> {
+	super::grid::Grid::parse(grid, |(x, y), c| match c {
+		_ if x >= 5 || y >= 5 => Err("grid is larger than 5x5".into()),
+		b'#' => Ok(Some(Bug)),
+		_ => Ok(None),
+	})
 }
 
-fn evolve(bugs: &std::collections::BTreeSet<(usize, usize)>) -> std::collections::BTreeSet<(usize, usize)> {
-	(0..5)
-		.flat_map(|x| (0..5).map(move |y| (x, y)))
-		.filter(|&(x, y)| {
-			let has_bug = bugs.contains(&(x, y));
+fn evolve(bugs: &super::grid::Grid<Bug, usize>) -> super::grid::Grid<Bug, usize> {
+	let mut result = super::grid::Grid::dense((0, 0), (4, 4));
 
-			let num_adjacent =
-				adjacent((x, y)).iter()
-				.filter(|&pos| bugs.contains(pos))
-				.count();
+	for y in 0..5 {
+		for x in 0..5 {
+			let has_bug = bugs.contains((x, y));
+
+			let num_adjacent = bugs.neighbors((x, y), super::grid::Neighborhood::Four).count();
 
 			match (has_bug, num_adjacent) {
 				(true | false, 1) |
 				(false, 2) => {
-					true
+					result.insert((x, y), Bug);
 				},
 
-				_ => false,
+				_ => (),
 			}
-		})
-		.collect()
-}
-
-fn adjacent((x, y): (usize, usize)) -> &'static [(usize, usize)] {
-	static mut RESULT: *mut std::collections::BTreeMap<(usize, usize), &'static [(usize, usize)]> = std::ptr::null_mut();
-	static RESULT_INIT: std::sync::Once = std::sync::Once::new();
-
-	RESULT_INIT.call_once(|| {
-		let mut result: std::collections::BTreeMap<(usize, usize), &'static [(usize, usize)]> = Default::default();
-
-		for x in 0..5 {
-			for y in 0..5 {
-				let mut adjacent = vec![];
-
-				// Left
-				match (x, y) {
-					(0, _) => (),
-
-					(1 | 2 | 3 | 4, _) => {
-						adjacent.push((x - 1, y));
-					},
-
-					_ => unreachable!(),
-				}
-
-				// Right
-				match (x, y) {
-					(0 | 1 | 2 | 3, _) => {
-						adjacent.push((x + 1, y));
-					},
-
-					(4, _) => (),
-
-					_ => unreachable!(),
-				}
-
-				// Up
-				match (x, y) {
-					(_, 0) => (),
-
-					(_, 1 | 2 | 3 | 4) => {
-						adjacent.push((x, y - 1));
-					},
-
-					_ => unreachable!(),
-				}
-
-				// Down
-				match (x, y) {
-					(_, 0 | 1 | 2 | 3) => {
-						adjacent.push((x, y + 1));
-					},
-
-					(_, 4) => (),
+		}
+	}
 
-					_ => unreachable!(),
-				}
+	result
+}
 
-				let adjacent = adjacent.into_boxed_slice();
-				let adjacent = Box::leak(adjacent);
-				result.insert((x, y), adjacent);
-			}
+// Renders one generation as a frame
+fn render(renderer: &mut dyn super::vis::Renderer, bugs: &super::grid::Grid<Bug, usize>) -> Result<(), super::Error> {
+	for x in 0..5_usize {
+		for y in 0..5 {
+			let cell =
+				if bugs.contains((x, y)) { super::vis::Cell { glyph: "#", color: super::vis::Color(13, 188, 121) } }
+				else { super::vis::Cell { glyph: ".", color: super::vis::Color(102, 102, 102) } };
+			renderer.set((x.try_into()?, y.try_into()?), cell)?;
 		}
+	}
 
-		let result = Box::new(result);
-		let result = Box::into_raw(result);
-		unsafe { RESULT = result; }
-	});
-
-	unsafe { (&*RESULT).get(&(x, y)).unwrap() }
+	renderer.frame()
 }
 
 fn evolve2(bugs: &std::collections::BTreeSet<(isize, usize, usize)>) -> std::collections::BTreeSet<(isize, usize, usize)> {
@@ -318,7 +264,7 @@ mod tests {
 			..#..\n\
 			#....\n\
 		";
-		let input = super::parse_input(input.lines().map(|s| Ok(s.to_owned()))).unwrap();
+		let input = crate::parse_str::<super::Solution>(input).unwrap();
 
 		let expected_iteration_1 = "\
 			#..#.\n\
@@ -327,7 +273,7 @@ mod tests {
 			##.##\n\
 			.##..\n\
 		";
-		let expected_iteration_1 = super::parse_input(expected_iteration_1.lines().map(|s| Ok(s.to_owned()))).unwrap();
+		let expected_iteration_1 = crate::parse_str::<super::Solution>(expected_iteration_1).unwrap();
 		let actual_iteration_1 = super::evolve(&input);
 		assert_eq!(expected_iteration_1, actual_iteration_1);
 
@@ -338,7 +284,7 @@ mod tests {
 			...#.\n\
 			#.###\n\
 		";
-		let expected_iteration_2 = super::parse_input(expected_iteration_2.lines().map(|s| Ok(s.to_owned()))).unwrap();
+		let expected_iteration_2 = crate::parse_str::<super::Solution>(expected_iteration_2).unwrap();
 		let actual_iteration_2 = super::evolve(&actual_iteration_1);
 		assert_eq!(expected_iteration_2, actual_iteration_2);
 
@@ -349,7 +295,7 @@ mod tests {
 			#.##.\n\
 			.##.#\n\
 		";
-		let expected_iteration_3 = super::parse_input(expected_iteration_3.lines().map(|s| Ok(s.to_owned()))).unwrap();
+		let expected_iteration_3 = crate::parse_str::<super::Solution>(expected_iteration_3).unwrap();
 		let actual_iteration_3 = super::evolve(&actual_iteration_2);
 		assert_eq!(expected_iteration_3, actual_iteration_3);
 
@@ -360,7 +306,7 @@ mod tests {
 			.....\n\
 			##...\n\
 		";
-		let expected_iteration_4 = super::parse_input(expected_iteration_4.lines().map(|s| Ok(s.to_owned()))).unwrap();
+		let expected_iteration_4 = crate::parse_str::<super::Solution>(expected_iteration_4).unwrap();
 		let actual_iteration_4 = super::evolve(&actual_iteration_3);
 		assert_eq!(expected_iteration_4, actual_iteration_4);
 	}
@@ -374,10 +320,10 @@ mod tests {
 			..#..\n\
 			#....\n\
 		";
-		let input = super::parse_input(input.lines().map(|s| Ok(s.to_owned()))).unwrap();
+		let input = crate::parse_str::<super::Solution>(input).unwrap();
 		let input: std::collections::BTreeSet<(isize, usize, usize)> =
-			input.into_iter()
-			.map(|(x, y)| (0, x, y))
+			input.iter()
+			.map(|((x, y), _)| (0, x, y))
 			.collect();
 
 		let expected_iteration_10 = &[
@@ -461,11 +407,10 @@ mod tests {
 		];
 		let expected_iteration_10: std::collections::BTreeSet<(isize, usize, usize)> =
 			expected_iteration_10.iter()
-			.flat_map(|&(depth, input)|
-				super::parse_input(input.lines().map(|s| Ok(s.to_owned()))).unwrap()
-				.into_iter()
-				.map(move |(x, y)| (depth, x, y))
-			)
+			.flat_map(|&(depth, input)| {
+				let bugs = crate::parse_str::<super::Solution>(input).unwrap();
+				bugs.iter().map(|((x, y), _)| (depth, x, y)).collect::<Vec<_>>()
+			})
 			.collect();
 		let mut actual_iteration_10 = input;
 		for _ in 0..10 {
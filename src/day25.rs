@@ -1,6 +1,15 @@
 use std::convert::TryInto;
 
-// To play the text adventure interactively, set DAY_25_VIS=1
+// To play the text adventure interactively, run with --vis
+//
+// To also see which memory cells each command changed, run with --intcode-diff text, or --intcode-diff json for JSON output.
+// The commands `!dump`, `!dump hex` and `!dump json` print the whole memory instead of being sent to the game.
+// `!break <condition>` adds a breakpoint, like `!break pc == 1234 && [rb+2] > 10`, and `!breakpoints` lists them with their hit counts.
+//
+// To save the session as a transcript that can be replayed as a regression test, run with --transcript <path>
+// To save what the terminal showed as an asciicast that can be watched with `asciinema play`, run with --vis-record <dir> instead of --vis
+//
+// To print the room names, descriptions, items and messages embedded in the program before playing, run with --strings
 
 /*
    Start at "Hull Breach", end at "Pressure sensor"
@@ -49,211 +58,287 @@ use std::convert::TryInto;
        west
  */
 
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day25")?.next().ok_or("file is empty")??;
-	let ram: crate::intcode::Ram = line.parse()?;
-
-	let play_game = std::env::var("DAY_25_VIS").is_ok();
-	if play_game {
-		let mut computer = crate::intcode::Computer::new(ram);
-
-		let mut waiting_for_input = false;
-
-		let mut line = String::new();
-		loop {
-			let input: Box<dyn Iterator<Item = i64>> =
-				if waiting_for_input {
-					print!("$ ");
-					std::io::Write::flush(&mut std::io::stdout())?;
-					std::io::stdin().read_line(&mut line)?;
-					let iter = line.clone().into_bytes().into_iter().map(Into::into);
-					line.clear();
-					waiting_for_input = false;
-					Box::new(iter)
-				}
-				else {
-					Box::new(std::iter::empty())
-				};
+pub(super) struct Solution;
+
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = String;
+	type Part2 = std::convert::Infallible;
+
+	const HAS_PART2: bool = false;
 
-			let output = computer.step(input)?.ok_or("EOF")?;
-			match output.try_into()? {
-				b'\n' => {
-					println!("> {line}");
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
 
-					if line == "Command?" {
-						waiting_for_input = true;
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
+		// Each item's program starts and ends at the Hull Breach position so that they can be composed arbitrarily.
+		//
+		// TODO: Dynamically generate these from the puzzle input rather than hard-coding them. :effort:
+		// For now, they're generated by playing the game interactively and mapping the dungeon by hand.
+		const ITEMS: &[&str] = &[
+			"\
+				south\n\
+				take fixed point\n\
+				north\n\
+			",
+			"\
+				west\n\
+				west\n\
+				west\n\
+				take hologram\n\
+				east\n\
+				east\n\
+				east\n\
+			",
+			"\
+				north\n\
+				take candy cane\n\
+				south\n\
+			",
+			"\
+				north\n\
+				west\n\
+				take antenna\n\
+				east\n\
+				south\n\
+			",
+			"\
+				north\n\
+				west\n\
+				south\n\
+				take whirled peas\n\
+				north\n\
+				east\n\
+				south\n\
+			",
+			"\
+				north\n\
+				west\n\
+				west\n\
+				take shell\n\
+				east\n\
+				east\n\
+				south\n\
+			",
+			"\
+				north\n\
+				north\n\
+				north\n\
+				take polygon\n\
+				south\n\
+				south\n\
+				south\n\
+			",
+			"\
+				north\n\
+				north\n\
+				west\n\
+				take fuel cell\n\
+				east\n\
+				south\n\
+				south\n\
+			",
+		];
+
+		if super::vis::in_terminal() {
+			match play(ram, &super::vis::options().unwrap_or_default())? {}
+		}
+
+		// Try every subset of items in parallel until the successful combination is found
+		let result = crate::intcode::batch::find_first(
+			ram,
+			0..(1_u32 << ITEMS.len()),
+			|computer, subset| {
+				// Build a program that takes the specified subset of items, then tries the pressure sensor.
+				let mut program: String =
+					ITEMS.iter()
+					.enumerate()
+					.filter(|&(i, _)| subset & (1 << i) != 0)
+					.map(|(_, &item)| item)
+					.collect();
+
+				// Try the pressure sensor
+				program += "\
+					north\n\
+					north\n\
+					west\n\
+					west\n\
+					west\n\
+					west\n\
+				";
+
+				let mut input = program.into_bytes().into_iter().map(Into::into);
+
+				let mut line = String::new();
+				loop {
+					let output = computer.step(&mut input)?.ok_or("EOF")?;
+					match output.try_into()? {
+						b'\n' => {
+							if line.contains("and you are ejected back to the checkpoint") {
+								// This subset failed.
+								return Ok(None);
+							}
+							else if line.contains("Oh, hello! You should be able to get in by typing") {
+								// Found the solution.
+
+								let mut line_parts = line.split(' ');
+								let password =
+									line_parts
+									.nth(11)
+									.ok_or_else(|| format!("could not extract password from line {line:?}"))?
+									.to_owned();
+								return Ok(Some(password));
+							}
+
+							line.clear();
+						},
+
+						b => line.push(b.into()),
 					}
+				}
+			},
+			Option::is_some,
+		)?;
+
+		let result = result.flatten().ok_or("no solution")?;
+		Ok(result)
+	}
+
+	fn part2(_: &Self::Input) -> Result<Self::Part2, super::Error> {
+		Err("there is no part 2".into())
+	}
+}
+
+// Plays the text adventure interactively until the program halts
+fn play(ram: &crate::intcode::Ram, options: &super::vis::Options) -> Result<std::convert::Infallible, super::Error> {
+	use std::io::Write;
+
+	let mut debugger = crate::intcode::debugger::Debugger::new(crate::intcode::Computer::new(ram.clone()));
+
+	let mut out = super::vis::output();
+
+	if options.strings {
+		write!(out, "{}", crate::intcode::strings::table(&crate::intcode::strings::strings(ram, 4)))?;
+	}
+
+	let record_path = &options.transcript;
+	if record_path.is_some() {
+		debugger.computer.record_transcript();
+	}
+
+	let diff_format = options.intcode_diff;
+	let mut previous_computer: Option<crate::intcode::Computer> = None;
 
-					line.clear();
-				},
+	let mut waiting_for_input = false;
+	let mut pending_input = std::collections::VecDeque::new();
 
-				b => line.push(b.into()),
+	let mut line = String::new();
+	loop {
+		if waiting_for_input {
+			// Saved before every command, since the session usually ends with the user killing the process
+			if let (Some(record_path), Some(transcript)) = (record_path, debugger.computer.transcript()) {
+				std::fs::write(record_path, transcript.to_string())?;
 			}
+			out.save()?;
+
+			loop {
+				write!(out, "$ ")?;
+				out.flush()?;
+				std::io::stdin().read_line(&mut line)?;
+				out.echo(&line);
+
+				match line.trim() {
+					"!dump" => write!(out, "{}", crate::intcode::dump::dump(&debugger.computer, crate::intcode::dump::Radix::Decimal))?,
+					"!dump hex" => write!(out, "{}", crate::intcode::dump::dump(&debugger.computer, crate::intcode::dump::Radix::Hexadecimal))?,
+					"!dump json" => writeln!(out, "{}", crate::intcode::dump::dump_json(&debugger.computer))?,
+
+					"!breakpoints" =>
+						for (i, breakpoint) in debugger.breakpoints().iter().enumerate() {
+							writeln!(out, "#{i}: {} ({} hits)", breakpoint.source, breakpoint.hits)?;
+						},
+
+					line if line.starts_with("!break ") =>
+						match debugger.add_breakpoint(&line["!break ".len()..]) {
+							Ok(i) => writeln!(out, "breakpoint #{i} added")?,
+							Err(err) => writeln!(out, "invalid breakpoint: {}", err.0)?,
+						},
+
+					_ => break,
+				}
+
+				line.clear();
+			}
+
+			pending_input.extend(line.bytes().map(i64::from));
+			line.clear();
+			waiting_for_input = false;
+		}
+
+		// Input is buffered outside the debugger, so that a breakpoint hitting in the middle of reading a command doesn't lose the rest of it
+		let output = match debugger.step(std::iter::from_fn(|| pending_input.pop_front()))? {
+			crate::intcode::debugger::Event::Output(output) => output,
+
+			crate::intcode::debugger::Event::Break { breakpoints, output, halted } => {
+				for i in breakpoints {
+					let breakpoint = &debugger.breakpoints()[i];
+					writeln!(out, "breakpoint #{i} hit at pc {}: {} ({} hits)", debugger.computer.pc(), breakpoint.source, breakpoint.hits)?;
+				}
+
+				if halted {
+					return Err("EOF".into());
+				}
+
+				match output {
+					Some(output) => output,
+					None => continue,
+				}
+			},
+
+			crate::intcode::debugger::Event::Halt => return Err("EOF".into()),
 		};
-	}
-	else {
-		let mut result = None;
-
-		// Try every permutation of items until the successful combination is found
-		'outer: for &fixed_point in &[true, false] {
-			for &hologram in &[true, false] {
-				for &candy_cane in &[true, false] {
-					for &antenna in &[true, false] {
-						for &whirled_peas in &[true, false] {
-							for &shell in &[true, false] {
-								for &polygon in &[true, false] {
-									for &fuel_cell in &[true, false] {
-										// Build a program that takes the specified subset of items, then tries the pressure sensor.
-										// Each item's program starts and ends at the Hull Breach position so that they can be composed arbitrarily.
-										//
-										// TODO: Dynamically generate these from the puzzle input rather than hard-coding them. :effort:
-										// For now, they're generated by playing the game interactively and mapping the dungeon by hand.
-
-										let mut program = String::new();
-
-										if fixed_point {
-											program += "\
-												south\n\
-												take fixed point\n\
-												north\n\
-											";
-										}
-
-										if hologram {
-											program += "\
-												west\n\
-												west\n\
-												west\n\
-												take hologram\n\
-												east\n\
-												east\n\
-												east\n\
-											";
-										}
-
-										if candy_cane {
-											program += "\
-												north\n\
-												take candy cane\n\
-												south\n\
-											";
-										}
-
-										if antenna {
-											program += "\
-												north\n\
-												west\n\
-												take antenna\n\
-												east\n\
-												south\n\
-											";
-										}
-
-										if whirled_peas {
-											program += "\
-												north\n\
-												west\n\
-												south\n\
-												take whirled peas\n\
-												north\n\
-												east\n\
-												south\n\
-											";
-										}
-
-										if shell {
-											program += "\
-												north\n\
-												west\n\
-												west\n\
-												take shell\n\
-												east\n\
-												east\n\
-												south\n\
-											";
-										}
-
-										if polygon {
-											program += "\
-												north\n\
-												north\n\
-												north\n\
-												take polygon\n\
-												south\n\
-												south\n\
-												south\n\
-											";
-										}
-
-										if fuel_cell {
-											program += "\
-												north\n\
-												north\n\
-												west\n\
-												take fuel cell\n\
-												east\n\
-												south\n\
-												south\n\
-											";
-										}
-
-										// Try the pressure sensor
-										program += "\
-											north\n\
-											north\n\
-											west\n\
-											west\n\
-											west\n\
-											west\n\
-										";
-
-										let mut computer = crate::intcode::Computer::new(ram.clone());
-										let mut input = program.into_bytes().into_iter().map(Into::into);
-
-										let mut line = String::new();
-										loop {
-											let output = computer.step(&mut input)?.ok_or("EOF")?;
-											match output.try_into()? {
-												b'\n' => {
-													if line.contains("and you are ejected back to the checkpoint") {
-														// This permutation failed. Try the next one.
-														break;
-													}
-													else if line.contains("Oh, hello! You should be able to get in by typing") {
-														// Found the solution.
-
-														let mut line_parts = line.split(' ');
-														result = Some(
-															line_parts
-															.nth(11)
-															.ok_or_else(|| format!("could not extract password from line {line:?}"))?
-															.to_owned()
-														);
-
-														break 'outer;
-													}
-
-													line.clear();
-												},
-
-												b => line.push(b.into()),
-											}
-										}
-									}
-								}
+		match output.try_into()? {
+			b'\n' => {
+				writeln!(out, "> {line}")?;
+
+				if line == "Command?" {
+					waiting_for_input = true;
+
+					if let Some(diff_format) = diff_format {
+						if let Some(previous_computer) = &previous_computer {
+							let diff = crate::intcode::dump::diff(previous_computer, &debugger.computer);
+							match diff_format {
+								crate::intcode::dump::Format::Json => writeln!(out, "{}", diff.to_json())?,
+								crate::intcode::dump::Format::Text if !diff.is_empty() => write!(out, "{}", diff.to_text(crate::intcode::dump::Radix::Decimal))?,
+								crate::intcode::dump::Format::Text => (),
 							}
 						}
+
+						previous_computer = Some(debugger.computer.clone());
 					}
 				}
-			}
-		};
 
-		let result = result.ok_or("no solution")?;
+				line.clear();
+			},
 
-		println!("25a: {result}");
-
-		assert_eq!(result, "136839232");
+			b => line.push(b.into()),
+		}
 	}
+}
+
+#[cfg(test)]
+mod tests {
+	// A session recorded with --transcript that walks north, takes the candy cane, walks back and checks the inventory
+	#[test]
+	fn test_replay_session() {
+		let line = crate::Lines::<String>::new(crate::open_input("day25").unwrap()).next().unwrap().unwrap();
+		let ram: crate::intcode::Ram = line.parse().unwrap();
 
-	Ok(())
+		let transcript: crate::intcode::transcript::Transcript =
+			std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/day25-session")).unwrap()
+			.parse().unwrap();
+
+		crate::intcode::transcript::replay(ram, &transcript).unwrap();
+	}
 }
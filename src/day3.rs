@@ -1,42 +1,39 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let wires: Result<Vec<_>, super::Error> =
-		super::read_input_lines::<String>("day3")?
-		.map(|line| {
-			let line = line?;
-			let steps: Result<Vec<Step>, super::Error> = line.split(',').map(str::parse).collect();
-			steps
-		})
-		.collect();
-	let wires = wires?;
-	let wires: Vec<_> = wires.iter().map(AsRef::as_ref).collect();
+pub(super) struct Solution;
 
-	let intersections = find_intersections(wires.iter().copied());
+impl super::Solution for Solution {
+	type Input = Vec<Vec<Step>>;
+	type Part1 = usize;
+	type Part2 = usize;
 
-	{
-		let result = find_min_manhattan_distance(intersections.iter().copied())?;
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		super::Lines::<super::CommaSeparated<Step>>::new(input)
+			.map(|line| Ok(line?.0))
+			.collect()
+	}
 
-		println!("3a: {result}");
+	fn part1(wires: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let intersections = find_intersections(wires.iter().map(AsRef::as_ref));
 
-		assert_eq!(result, 860);
+		let result = find_min_manhattan_distance(intersections)?;
+		Ok(result)
 	}
 
-	{
+	fn part2(wires: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let wires: Vec<_> = wires.iter().map(AsRef::as_ref).collect();
+
+		let intersections = find_intersections(wires.iter().copied());
+
 		let result =
 			find_steps_to_reach(&wires, intersections)
 			.map(|(_, num_steps)| num_steps)
 			.min()
 			.ok_or("no solution")?;
-
-		println!("3b: {result}");
-
-		assert_eq!(result, 9238);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum Step {
+pub(super) enum Step {
 	Horizontal(isize),
 	Vertical(isize),
 }
@@ -45,15 +42,15 @@ impl std::str::FromStr for Step {
 	type Err = super::Error;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let (is_horizontal, direction) = match &s[..1] {
-			"D" => (false, -1),
-			"L" => (true, -1),
-			"R" => (true, 1),
-			"U" => (false, 1),
-			_ => return Err(format!("malformed step {s:?}").into()),
+		let (is_horizontal, direction) = match s.chars().next() {
+			Some('D') => (false, -1),
+			Some('L') => (true, -1),
+			Some('R') => (true, 1),
+			Some('U') => (false, 1),
+			c => return Err(super::ParseError::new(s, 0..c.map_or(0, char::len_utf8), "malformed step: expected D, L, R or U").into()),
 		};
 
-		let size: isize = s[1..].parse().map_err(|err| format!("malformed step {s:?}: {err}"))?;
+		let size: isize = s[1..].parse().map_err(|err| super::ParseError::new(s, 1..s.len(), format!("malformed step: {err}")))?;
 
 		Ok(if is_horizontal { Step::Horizontal(direction * size) } else { Step::Vertical(direction * size) })
 	}
@@ -149,9 +146,8 @@ mod tests {
 	#[test]
 	fn test_parse_steps() {
 		fn test(s: &str, expected: &[super::Step]) {
-			let actual: Result<Vec<super::Step>, _> = s.split(',').map(str::parse).collect();
-			let actual = actual.unwrap();
-			assert_eq!(actual, expected);
+			let actual: crate::CommaSeparated<super::Step> = s.parse().unwrap();
+			assert_eq!(actual.0, expected);
 		}
 
 		test("R8,U5,L5,D3", &[
@@ -167,6 +163,11 @@ mod tests {
 			super::Step::Vertical(-4),
 			super::Step::Horizontal(-4),
 		]);
+
+		assert_eq!(
+			crate::parse_str::<super::Solution>("R8,U5\nU7,X6,D4").unwrap_err().to_string(),
+			"2:4: malformed step: expected D, L, R or U\n  |\n2 | U7,X6,D4\n  |    ^",
+		);
 	}
 
 	#[test]
@@ -176,12 +177,7 @@ mod tests {
 			expected_intersections: Option<&[(isize, isize)]>,
 			expected_min_manhattan_distance: usize,
 		) {
-			let wires: Vec<_> =
-				wires.iter().map(|s| {
-					let wire: Result<Vec<super::Step>, _> = s.split(',').map(str::parse).collect();
-					let wire = wire.unwrap();
-					wire
-				}).collect();
+			let wires = crate::parse_str::<super::Solution>(&wires.join("\n")).unwrap();
 			let wires: Vec<_> = wires.iter().map(AsRef::as_ref).collect();
 
 			let actual_intersections = super::find_intersections(wires.iter().copied());
@@ -228,12 +224,7 @@ mod tests {
 			expected_steps_to_reach: Option<&[((isize, isize), usize)]>,
 			expected_min_steps_to_reach: usize,
 		) {
-			let wires: Vec<_> =
-				wires.iter().map(|s| {
-					let wire: Result<Vec<super::Step>, _> = s.split(',').map(str::parse).collect();
-					let wire = wire.unwrap();
-					wire
-				}).collect();
+			let wires = crate::parse_str::<super::Solution>(&wires.join("\n")).unwrap();
 			let wires: Vec<_> = wires.iter().map(AsRef::as_ref).collect();
 
 			let intersections = super::find_intersections(wires.iter().copied());
@@ -1,26 +1,27 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let input = super::read_input_lines::<String>("day4")?.next().ok_or("file is empty")??;
-	let mut input = input.split('-');
-	let lower: u32 = input.next().ok_or("malformed input")?.parse()?;
-	let upper: u32 = input.next().ok_or("malformed input")?.parse()?;
+pub(super) struct Solution;
 
-	{
-		let result = (lower..=upper).filter(|&num| is_valid(num)).count();
+impl super::Solution for Solution {
+	type Input = (u32, u32);
+	type Part1 = usize;
+	type Part2 = usize;
 
-		println!("4a: {result}");
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let input = super::Lines::<String>::new(input).next().ok_or("file is empty")??;
+		let mut input = input.split('-');
+		let lower = input.next().ok_or("malformed input")?.parse()?;
+		let upper = input.next().ok_or("malformed input")?.parse()?;
+		Ok((lower, upper))
+	}
 
-		assert_eq!(result, 1919);
+	fn part1(&(lower, upper): &Self::Input) -> Result<Self::Part1, super::Error> {
+		let result = (lower..=upper).filter(|&num| is_valid(num)).count();
+		Ok(result)
 	}
 
-	{
+	fn part2(&(lower, upper): &Self::Input) -> Result<Self::Part2, super::Error> {
 		let result = (lower..=upper).filter(|&num| is_valid2(num)).count();
-
-		println!("4b: {result}");
-
-		assert_eq!(result, 1291);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
 #[allow(clippy::cast_possible_truncation)]
@@ -1,31 +1,30 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day5")?.next().ok_or("file is empty")??;
+pub(super) struct Solution;
 
-	let ram: crate::intcode::Ram = line.parse()?;
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = i64;
+	type Part2 = i64;
 
-	{
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
+
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
 		let mut computer = crate::intcode::Computer::new(ram.clone());
 
 		let output = computer.execute(std::iter::once(1))?;
 		let result = *output.last().ok_or("no output")?;
-
-		println!("5a: {result}");
-
-		assert_eq!(result, 9654885);
+		Ok(result)
 	}
 
-	{
-		let mut computer = crate::intcode::Computer::new(ram);
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let mut computer = crate::intcode::Computer::new(ram.clone());
 
 		let output = computer.execute(std::iter::once(5))?;
 		let result = *output.last().ok_or("no output")?;
-
-		println!("5b: {result}");
-
-		assert_eq!(result, 7079459);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
 #[cfg(test)]
@@ -1,36 +1,56 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let input: Result<Vec<String>, super::Error> = super::read_input_lines::<String>("day6")?.collect();
-	let input = input?;
-
-	let mut orbits_parent_to_child: std::collections::BTreeMap<&str, Vec<&str>> = Default::default();
-	let mut orbits_child_to_parent: std::collections::BTreeMap<&str, &str> = Default::default();
-
-	for line in &input {
-		let mut parts = line.split(')');
-		let parent = parts.next().expect("str::split always has at least one part");
-		let child = parts.next().ok_or("malformed input")?;
-
-		orbits_parent_to_child.entry(parent).or_default().push(child);
-		orbits_child_to_parent.insert(child, parent);
+pub(super) struct Solution;
+
+impl super::Solution for Solution {
+	// Pairs of parent and child
+	type Input = Vec<(String, String)>;
+	type Part1 = usize;
+	type Part2 = usize;
+
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		super::Lines::<Orbit>::new(input)
+			.map(|orbit| {
+				let Orbit(parent, child) = orbit?;
+				Ok((parent, child))
+			})
+			.collect()
 	}
 
-	{
+	fn part1(orbits: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let mut orbits_parent_to_child: std::collections::BTreeMap<&str, Vec<&str>> = Default::default();
+		for (parent, child) in orbits {
+			orbits_parent_to_child.entry(parent).or_default().push(child);
+		}
+
 		let result = num_orbits(&orbits_parent_to_child);
+		Ok(result)
+	}
 
-		println!("6a: {result}");
+	fn part2(orbits: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let orbits_child_to_parent: std::collections::BTreeMap<&str, &str> =
+			orbits.iter()
+			.map(|(parent, child)| (&**child, &**parent))
+			.collect();
 
-		assert_eq!(result, 144909);
+		let result = num_transfers("YOU", "SAN", &orbits_child_to_parent)?;
+		Ok(result)
 	}
+}
 
-	{
-		let result = num_transfers("YOU", "SAN", &orbits_child_to_parent)?;
+// A parent and the child that orbits it, eg `COM)B`
+struct Orbit(String, String);
 
-		println!("6b: {result}");
+impl std::str::FromStr for Orbit {
+	type Err = super::Error;
 
-		assert_eq!(result, 259);
-	}
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (parent, child) = s.split_once(')').ok_or_else(|| super::ParseError::new(s, 0..s.len(), "malformed orbit: expected `)` between two objects"))?;
+		if let Some(i) = child.find(')') {
+			let i = parent.len() + 1 + i;
+			return Err(super::ParseError::new(s, i..s.len(), "malformed orbit: expected only one `)`").into());
+		}
 
-	Ok(())
+		Ok(Orbit(parent.to_owned(), child.to_owned()))
+	}
 }
 
 fn num_orbits(orbits_parent_to_child: &std::collections::BTreeMap<&str, Vec<&str>>) -> usize {
@@ -1,74 +1,63 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day7")?.next().ok_or("file is empty")??;
-
-	let ram: Result<Vec<_>, super::Error> =
-		line.split(',')
-		.map(|s| Ok(s.parse::<i64>()?))
-		.collect();
-	let ram = ram?;
-
-	{
-		let mut result = None;
-		for amplifier1 in 0..=4 {
-			for amplifier2 in 0..=4 {
-				for amplifier3 in 0..=4 {
-					for amplifier4 in 0..=4 {
-						for amplifier5 in 0..=4 {
-							let settings: std::collections::BTreeSet<_> = vec![amplifier1, amplifier2, amplifier3, amplifier4, amplifier5].into_iter().collect();
-							if settings.len() != 5 { continue; }
-
-							let output = get_output_signal(&ram, &[amplifier1, amplifier2, amplifier3, amplifier4, amplifier5])?;
-
-							result = std::cmp::max(result, Some(output));
-						}
-					}
-				}
-			}
-		}
+pub(super) struct Solution;
 
-		let result = result.ok_or("no solution")?;
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = i64;
+	type Part2 = i64;
 
-		println!("7a: {result}");
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
+
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
+		let result =
+			crate::intcode::batch::run_group(ram, 5, phase_settings(0..=4), |amplifiers, settings| get_output_signal(amplifiers, &settings))?
+			.into_iter()
+			.max();
 
-		assert_eq!(result, 24625);
+		let result = result.ok_or("no solution")?;
+		Ok(result)
 	}
 
-	{
-		let mut result = None;
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let result =
+			crate::intcode::batch::run_group(ram, 5, phase_settings(5..=9), |amplifiers, settings| get_output_signal2(amplifiers, &settings))?
+			.into_iter()
+			.max();
+
+		let result = result.ok_or("no solution")?;
+		Ok(result)
+	}
+}
 
-		for amplifier1 in 5..=9 {
-			for amplifier2 in 5..=9 {
-				for amplifier3 in 5..=9 {
-					for amplifier4 in 5..=9 {
-						for amplifier5 in 5..=9 {
-							let settings: std::collections::BTreeSet<_> = vec![amplifier1, amplifier2, amplifier3, amplifier4, amplifier5].into_iter().collect();
-							if settings.len() != 5 { continue; }
+// Every ordering of five distinct phase settings from the given range
+fn phase_settings(range: std::ops::RangeInclusive<i64>) -> Vec<[i64; 5]> {
+	let mut result = vec![];
 
-							let output = get_output_signal2(&ram, &[amplifier1, amplifier2, amplifier3, amplifier4, amplifier5])?;
+	for amplifier1 in range.clone() {
+		for amplifier2 in range.clone() {
+			for amplifier3 in range.clone() {
+				for amplifier4 in range.clone() {
+					for amplifier5 in range.clone() {
+						let settings: std::collections::BTreeSet<_> = vec![amplifier1, amplifier2, amplifier3, amplifier4, amplifier5].into_iter().collect();
+						if settings.len() != 5 { continue; }
 
-							result = std::cmp::max(result, Some(output));
-						}
+						result.push([amplifier1, amplifier2, amplifier3, amplifier4, amplifier5]);
 					}
 				}
 			}
 		}
-
-		let result = result.ok_or("no solution")?;
-
-		println!("7b: {result}");
-
-		assert_eq!(result, 36497698);
 	}
 
-	Ok(())
+	result
 }
 
-fn get_output_signal(ram: &[i64], settings: &[i64]) -> Result<i64, super::Error> {
+fn get_output_signal(amplifiers: &mut [crate::intcode::Computer], settings: &[i64]) -> Result<i64, super::Error> {
 	let mut output = 0;
 
-	for &setting in settings {
+	for (&setting, computer) in settings.iter().zip(amplifiers) {
 		output = {
-			let mut computer = crate::intcode::Computer::new(crate::intcode::Ram(ram.to_owned()));
 			let output = computer.execute(vec![setting, output])?;
 			*output.last().ok_or("no output")?
 		}
@@ -77,17 +66,13 @@ fn get_output_signal(ram: &[i64], settings: &[i64]) -> Result<i64, super::Error>
 	Ok(output)
 }
 
-fn get_output_signal2(ram: &[i64], settings: &[i64]) -> Result<i64, super::Error> {
+fn get_output_signal2(amplifiers: &mut [crate::intcode::Computer], settings: &[i64]) -> Result<i64, super::Error> {
 	let mut output = 0;
 
-	let mut amplifiers: Vec<_> =
-		settings.iter()
-		.map(|_| crate::intcode::Computer::new(crate::intcode::Ram(ram.to_owned())))
-		.collect();
 	let mut first_pass = true;
 
 	'outer: loop {
-		for (i, (&setting, computer)) in settings.iter().zip(&mut amplifiers).enumerate() {
+		for (i, (&setting, computer)) in settings.iter().zip(&mut *amplifiers).enumerate() {
 			output = {
 				let input = if first_pass { vec![setting, output].into_iter() } else { vec![output].into_iter() };
 				let output = computer.step(input)?;
@@ -107,13 +92,17 @@ fn get_output_signal2(ram: &[i64], settings: &[i64]) -> Result<i64, super::Error
 
 #[cfg(test)]
 mod tests {
+	fn amplifiers(ram: &[i64]) -> Vec<crate::intcode::Computer> {
+		(0..5).map(|_| crate::intcode::Computer::new(crate::intcode::Ram(ram.to_owned()))).collect()
+	}
+
 	#[test]
 	fn test_get_output_signal() {
 		assert_eq!(
 			super::get_output_signal(
-				&[
+				&mut amplifiers(&[
 					3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
-				],
+				]),
 				&[4, 3, 2, 1, 0],
 			).unwrap(),
 			43210,
@@ -121,9 +110,9 @@ mod tests {
 
 		assert_eq!(
 			super::get_output_signal(
-				&[
+				&mut amplifiers(&[
 					3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23, 23, 4, 23, 99, 0, 0,
-				],
+				]),
 				&[0, 1, 2, 3, 4],
 			).unwrap(),
 			54321,
@@ -131,11 +120,11 @@ mod tests {
 
 		assert_eq!(
 			super::get_output_signal(
-				&[
+				&mut amplifiers(&[
 					3, 31, 3, 32, 1002, 32, 10, 32, 1001, 31, -2, 31, 1007, 31, 0, 33,
 					1002, 33, 7, 33, 1, 33, 31, 31, 1, 32, 31, 31, 4, 31, 99, 0,
 					0, 0,
-				],
+				]),
 				&[1, 0, 4, 3, 2],
 			).unwrap(),
 			65210,
@@ -146,10 +135,10 @@ mod tests {
 	fn test_get_output_signal2() {
 		assert_eq!(
 			super::get_output_signal2(
-				&[
+				&mut amplifiers(&[
 					3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27,
 					4, 27, 1001, 28, -1, 28, 1005, 28, 6, 99, 0, 0, 5,
-				],
+				]),
 				&[9, 8, 7, 6, 5],
 			).unwrap(),
 			139629729,
@@ -157,12 +146,12 @@ mod tests {
 
 		assert_eq!(
 			super::get_output_signal2(
-				&[
+				&mut amplifiers(&[
 					3, 52, 1001, 52, -5, 52, 3, 53, 1, 52, 56, 54, 1007, 54, 5, 55,
 					1005, 55, 26, 1001, 54, -5, 54, 1105, 1, 12, 1, 53, 54, 53, 1008, 54,
 					0, 55, 1001, 55, 1, 55, 2, 53, 55, 53, 4, 53, 1001, 56, -1, 56,
 					1005, 56, 6, 99, 0, 0, 0, 0, 10,
-				],
+				]),
 				&[9, 7, 8, 5, 6],
 			).unwrap(),
 			18216,
@@ -1,11 +1,18 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	const COLS: usize = 25;
-	const ROWS: usize = 6;
+// To render the decoded image, run with --vis
 
-	let line = super::read_input_lines::<String>("day8")?.next().ok_or("file is empty")??;
-	let line = line.as_bytes();
+pub(super) struct Solution;
 
-	{
+impl super::Solution for Solution {
+	type Input = Vec<u8>;
+	type Part1 = usize;
+	type Part2 = super::Value;
+
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let line = super::Lines::<String>::new(input).next().ok_or("file is empty")??;
+		Ok(line.into_bytes())
+	}
+
+	fn part1(line: &Self::Input) -> Result<Self::Part1, super::Error> {
 		let result =
 			line.chunks(COLS * ROWS)
 			.map(|layer| {
@@ -17,13 +24,10 @@ pub(super) fn run() -> Result<(), super::Error> {
 			.min_by_key(|(num_zeros, _)| *num_zeros)
 			.map(|(_, result)| result)
 			.ok_or("invalid input")?;
-
-		println!("8a: {result}");
-
-		assert_eq!(result, 1572);
+		Ok(result)
 	}
 
-	{
+	fn part2(line: &Self::Input) -> Result<Self::Part2, super::Error> {
 		let mut result = vec![b'2'; COLS * ROWS];
 		for layer in line.chunks(COLS * ROWS) {
 			for (front, back) in result.iter_mut().zip(layer) {
@@ -33,29 +37,32 @@ pub(super) fn run() -> Result<(), super::Error> {
 			}
 		}
 
-		println!("8b:");
-		for row in result.chunks(COLS) {
-			print!("    ");
-			for b in row {
-				print!("{}", match b {
-					b'0' => "  ",
-					b'1' => "\u{2588}\u{2588}",
-					_ => return Err("invalid input".into()),
-				});
+		let rows: Result<Vec<Vec<_>>, super::Error> =
+			result.chunks(COLS)
+			.map(|row| row.iter().map(|b| match b {
+				b'0' => Ok(false),
+				b'1' => Ok(true),
+				_ => Err("invalid input".into()),
+			}).collect())
+			.collect();
+		let rows = rows?;
+
+		let mut renderer = super::vis::renderer();
+		for (y, row) in (0..).zip(&rows) {
+			for (x, &white) in (0..).zip(row) {
+				renderer.set((x, y), if white { WHITE } else { BLACK })?;
 			}
-			println!();
 		}
+		renderer.finish()?;
 
-		// KYHFE
-		assert_eq!(&*result, &b"\
-			1001010001100101111011110\
-			1010010001100101000010000\
-			1100001010111101110011100\
-			1010000100100101000010000\
-			1010000100100101000010000\
-			1001000100100101000011110\
-		"[..]);
+		let image = super::Image::new(rows)?;
+		let result = super::Value::from_image(image);
+		Ok(result)
 	}
-
-	Ok(())
 }
+
+const COLS: usize = 25;
+const ROWS: usize = 6;
+
+const BLACK: super::vis::Cell = super::vis::Cell { glyph: "  ", color: super::vis::Color(0, 0, 0) };
+const WHITE: super::vis::Cell = super::vis::Cell { glyph: "\u{2588}\u{2588}", color: super::vis::Color(229, 229, 229) };
@@ -1,29 +1,30 @@
-pub(super) fn run() -> Result<(), super::Error> {
-	let line = super::read_input_lines::<String>("day9")?.next().ok_or("file is empty")??;
+pub(super) struct Solution;
 
-	let ram: crate::intcode::Ram = line.parse()?;
+impl super::Solution for Solution {
+	type Input = crate::intcode::Ram;
+	type Part1 = i64;
+	type Part2 = i64;
 
-	{
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, super::Error> {
+		let ram = crate::intcode::Ram::load(input)?;
+		Ok(ram)
+	}
+
+	fn part1(ram: &Self::Input) -> Result<Self::Part1, super::Error> {
 		let mut computer = crate::intcode::Computer::new(ram.clone());
+
 		let output = computer.execute(std::iter::once(1))?;
 		let result = *output.last().ok_or("no output")?;
-
-		println!("9a: {result}");
-
-		assert_eq!(result, 4288078517);
+		Ok(result)
 	}
 
-	{
-		let mut computer = crate::intcode::Computer::new(ram);
+	fn part2(ram: &Self::Input) -> Result<Self::Part2, super::Error> {
+		let mut computer = crate::intcode::Computer::new(ram.clone());
+
 		let output = computer.execute(std::iter::once(2))?;
 		let result = *output.last().ok_or("no output")?;
-
-		println!("9b: {result}");
-
-		assert_eq!(result, 69256);
+		Ok(result)
 	}
-
-	Ok(())
 }
 
 #[cfg(test)]
@@ -31,7 +32,7 @@ mod tests {
 	#[test]
 	fn test_execute_program() {
 		fn test(program: &str, expected_output: &[i64]) {
-			let mut computer = crate::intcode::Computer::new(program.parse().unwrap());
+			let mut computer: crate::intcode::Computer = crate::intcode::Computer::new(program.parse().unwrap());
 
 			let actual_output = computer.execute(std::iter::empty()).unwrap();
 			assert_eq!(expected_output, &*actual_output);
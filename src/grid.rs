@@ -0,0 +1,392 @@
+//! Two-dimensional grids of cells, for the days whose puzzles are maps.
+//!
+//! A [`Grid`] stores its cells in one of two ways. A dense grid has fixed bounds and stores every cell within them,
+//! which suits maps that are parsed from text like mazes. A sparse grid only stores the cells that are set,
+//! which suits maps that grow in every direction while they're explored, like the hull that day 11's robot paints.
+//! Either way, any cell can be empty, and the grid works the same.
+//!
+//! Positions are `(x, y)`, with y increasing downwards. Their coordinates can be signed, for maps that extend in every direction
+//! from where they start, or unsigned, for maps parsed from text whose top left is `(0, 0)`. See [`Coord`].
+
+/// A type of the coordinates of cells
+pub trait Coord: Copy + std::fmt::Debug + Ord + std::hash::Hash {
+	/// The coordinate as an `i64`
+	fn to_i64(self) -> i64;
+
+	/// The coordinate from an `i64`, or `None` if it's out of range, like a negative `usize`
+	fn from_i64(value: i64) -> Option<Self>;
+}
+
+macro_rules! coord {
+	($($ty:ty)*) => {
+		$(
+			impl Coord for $ty {
+				fn to_i64(self) -> i64 {
+					std::convert::TryInto::try_into(self).expect("coordinate fits in i64")
+				}
+
+				fn from_i64(value: i64) -> Option<Self> {
+					std::convert::TryInto::try_into(value).ok()
+				}
+			}
+		)*
+	};
+}
+
+coord! { i32 i64 isize usize }
+
+/// Which cells around a cell are its neighbors
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Neighborhood {
+	/// The four cells that share an edge with it
+	Four,
+
+	/// The eight cells that share an edge or a corner with it
+	Eight,
+}
+
+/// The positions of the neighbors of the given position, row by row.
+///
+/// Positions that the coordinate type can't represent are skipped, like the left neighbor of `(0_usize, 0)`.
+pub fn neighbors<C>(pos: (C, C), neighborhood: Neighborhood) -> impl Iterator<Item = (C, C)> where C: Coord {
+	const FOUR: [(i64, i64); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+	const EIGHT: [(i64, i64); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+	let offsets: &'static [(i64, i64)] = match neighborhood {
+		Neighborhood::Four => &FOUR,
+		Neighborhood::Eight => &EIGHT,
+	};
+
+	let (x, y) = (pos.0.to_i64(), pos.1.to_i64());
+	offsets.iter().filter_map(move |&(dx, dy)| Some((C::from_i64(x + dx)?, C::from_i64(y + dy)?)))
+}
+
+/// A grid of cells, any of which can be empty.
+///
+/// Two grids are only equal if they use the same storage, along with the same bounds if they're dense.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T, C = i64> {
+	storage: Storage<T>,
+	coord: std::marker::PhantomData<C>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum Storage<T> {
+	// Every cell within the bounds, row by row
+	Dense {
+		min: (i64, i64),
+		width: usize,
+		cells: Vec<Option<T>>,
+		len: usize,
+	},
+
+	// The cells that are set, keyed by row and then column so that they're in the same order as a dense grid's
+	Sparse(std::collections::BTreeMap<(i64, i64), T>),
+}
+
+impl<T, C> Grid<T, C> where C: Coord {
+	/// An empty grid that stores only the cells that are set, and has no bounds
+	pub fn sparse() -> Self {
+		Grid { storage: Storage::Sparse(Default::default()), coord: Default::default() }
+	}
+
+	/// An empty grid that stores every cell from `min` to `max` inclusive, and can't have cells outside them
+	pub fn dense(min: (C, C), max: (C, C)) -> Self {
+		let min = (min.0.to_i64(), min.1.to_i64());
+		let max = (max.0.to_i64(), max.1.to_i64());
+		let width = usize::try_from(max.0 - min.0 + 1).unwrap_or(0);
+		let height = usize::try_from(max.1 - min.1 + 1).unwrap_or(0);
+		Grid::dense_inner(min, width, height)
+	}
+
+	fn dense_inner(min: (i64, i64), width: usize, height: usize) -> Self {
+		let mut cells = Vec::with_capacity(width * height);
+		cells.resize_with(width * height, || None);
+		Grid { storage: Storage::Dense { min, width, cells, len: 0 }, coord: Default::default() }
+	}
+
+	/// Parses a dense grid from rows of text, like those from [`crate::read_grid`], with the first character at `(0, 0)`.
+	///
+	/// Each character is parsed by `f`, which returns `None` for an empty cell. Rows can have different lengths,
+	/// and the grid is as wide as the longest one.
+	pub fn parse<R>(rows: &[R], mut f: impl FnMut((C, C), u8) -> Result<Option<T>, crate::Error>) -> Result<Self, crate::Error> where R: AsRef<[u8]> {
+		let width = rows.iter().map(|row| row.as_ref().len()).max().unwrap_or(0);
+		let mut grid = Grid::dense_inner((0, 0), width, rows.len());
+
+		for (y, row) in rows.iter().enumerate() {
+			let row = row.as_ref();
+			for (x, &c) in row.iter().enumerate() {
+				let pos = (
+					C::from_i64(x.try_into()?).ok_or("grid is too wide")?,
+					C::from_i64(y.try_into()?).ok_or("grid is too tall")?,
+				);
+
+				let end = x + 1;
+				let value = f(pos, c).map_err(|err| err.at(&String::from_utf8_lossy(row), x..end).at_line(y + 1))?;
+				if let Some(value) = value {
+					grid.insert(pos, value);
+				}
+			}
+		}
+
+		Ok(grid)
+	}
+
+	// The index of the position in a dense grid's cells
+	fn index(min: (i64, i64), width: usize, len: usize, (x, y): (i64, i64)) -> Option<usize> {
+		let col = usize::try_from(x - min.0).ok().filter(|&col| col < width)?;
+		let row = usize::try_from(y - min.1).ok()?;
+		Some(row * width + col).filter(|&i| i < len)
+	}
+
+	/// The cell at the given position, or `None` if it's empty
+	pub fn get(&self, pos: (C, C)) -> Option<&T> {
+		let pos = (pos.0.to_i64(), pos.1.to_i64());
+		match &self.storage {
+			Storage::Dense { min, width, cells, .. } => cells.get(Grid::<T, C>::index(*min, *width, cells.len(), pos)?)?.as_ref(),
+			Storage::Sparse(cells) => cells.get(&(pos.1, pos.0)),
+		}
+	}
+
+	/// The cell at the given position, or `None` if it's empty
+	pub fn get_mut(&mut self, pos: (C, C)) -> Option<&mut T> {
+		let pos = (pos.0.to_i64(), pos.1.to_i64());
+		match &mut self.storage {
+			Storage::Dense { min, width, cells, .. } => {
+				let i = Grid::<T, C>::index(*min, *width, cells.len(), pos)?;
+				cells[i].as_mut()
+			},
+			Storage::Sparse(cells) => cells.get_mut(&(pos.1, pos.0)),
+		}
+	}
+
+	/// Whether the cell at the given position is set
+	pub fn contains(&self, pos: (C, C)) -> bool {
+		self.get(pos).is_some()
+	}
+
+	/// Sets the cell at the given position, and returns its previous value.
+	///
+	/// # Panics
+	///
+	/// Panics if the grid is dense and the position is outside its bounds.
+	pub fn insert(&mut self, pos: (C, C), value: T) -> Option<T> {
+		let i64_pos = (pos.0.to_i64(), pos.1.to_i64());
+		match &mut self.storage {
+			Storage::Dense { min, width, cells, len } => {
+				let i = Grid::<T, C>::index(*min, *width, cells.len(), i64_pos).unwrap_or_else(|| panic!("{pos:?} is outside the grid"));
+				let previous = cells[i].replace(value);
+				if previous.is_none() {
+					*len += 1;
+				}
+				previous
+			},
+			Storage::Sparse(cells) => cells.insert((i64_pos.1, i64_pos.0), value),
+		}
+	}
+
+	/// The cell at the given position, after setting it to the result of `f` if it's empty
+	///
+	/// # Panics
+	///
+	/// Panics if the grid is dense and the position is outside its bounds.
+	pub fn get_or_insert_with(&mut self, pos: (C, C), f: impl FnOnce() -> T) -> &mut T {
+		let i64_pos = (pos.0.to_i64(), pos.1.to_i64());
+		match &mut self.storage {
+			Storage::Dense { min, width, cells, len } => {
+				let i = Grid::<T, C>::index(*min, *width, cells.len(), i64_pos).unwrap_or_else(|| panic!("{pos:?} is outside the grid"));
+				let cell = &mut cells[i];
+				if cell.is_none() {
+					*len += 1;
+				}
+				cell.get_or_insert_with(f)
+			},
+			Storage::Sparse(cells) => cells.entry((i64_pos.1, i64_pos.0)).or_insert_with(f),
+		}
+	}
+
+	/// Empties the cell at the given position, and returns its previous value
+	pub fn remove(&mut self, pos: (C, C)) -> Option<T> {
+		let pos = (pos.0.to_i64(), pos.1.to_i64());
+		match &mut self.storage {
+			Storage::Dense { min, width, cells, len } => {
+				let i = Grid::<T, C>::index(*min, *width, cells.len(), pos)?;
+				let previous = cells[i].take();
+				if previous.is_some() {
+					*len -= 1;
+				}
+				previous
+			},
+			Storage::Sparse(cells) => cells.remove(&(pos.1, pos.0)),
+		}
+	}
+
+	/// The number of cells that are set
+	pub fn len(&self) -> usize {
+		match &self.storage {
+			Storage::Dense { len, .. } => *len,
+			Storage::Sparse(cells) => cells.len(),
+		}
+	}
+
+	/// Whether no cells are set
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// The cells that are set along with their positions, row by row
+	pub fn iter(&self) -> Iter<'_, T, C> {
+		let inner = match &self.storage {
+			Storage::Dense { min, width, cells, .. } => IterInner::Dense { min: *min, width: *width, cells: cells.iter().enumerate() },
+			Storage::Sparse(cells) => IterInner::Sparse(cells.iter()),
+		};
+		Iter { inner, coord: Default::default() }
+	}
+
+	/// The cells that are set, row by row
+	pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+		self.iter().map(|(_, value)| value)
+	}
+
+	/// The top left and bottom right corners of the cells that are set, or `None` if none are
+	pub fn bounds(&self) -> Option<((C, C), (C, C))> {
+		self.iter().fold(None, |bounds, ((x, y), _)| match bounds {
+			Some(((min_x, min_y), (max_x, max_y))) => Some(((std::cmp::min(min_x, x), std::cmp::min(min_y, y)), (std::cmp::max(max_x, x), std::cmp::max(max_y, y)))),
+			None => Some(((x, y), (x, y))),
+		})
+	}
+
+	/// The neighbors of the given position that are set, along with their positions
+	pub fn neighbors(&self, pos: (C, C), neighborhood: Neighborhood) -> impl Iterator<Item = ((C, C), &T)> + '_ {
+		neighbors(pos, neighborhood).filter_map(move |pos| Some((pos, self.get(pos)?)))
+	}
+
+	/// Renders the cells within the bounds of the ones that are set as text, one line per row.
+	///
+	/// `f` returns the character of each cell, or of an empty cell.
+	pub fn render(&self, mut f: impl FnMut(Option<&T>) -> char) -> String {
+		let mut result = String::new();
+
+		if let Some(((min_x, min_y), (max_x, max_y))) = self.bounds() {
+			for y in min_y.to_i64()..=max_y.to_i64() {
+				for x in min_x.to_i64()..=max_x.to_i64() {
+					let pos = (C::from_i64(x).expect("x is within bounds"), C::from_i64(y).expect("y is within bounds"));
+					result.push(f(self.get(pos)));
+				}
+				result.push('\n');
+			}
+		}
+
+		result
+	}
+}
+
+/// An iterator over the cells of a [`Grid`] that are set, along with their positions
+pub struct Iter<'a, T, C> {
+	inner: IterInner<'a, T>,
+	coord: std::marker::PhantomData<C>,
+}
+
+enum IterInner<'a, T> {
+	Dense {
+		min: (i64, i64),
+		width: usize,
+		cells: std::iter::Enumerate<std::slice::Iter<'a, Option<T>>>,
+	},
+
+	Sparse(std::collections::btree_map::Iter<'a, (i64, i64), T>),
+}
+
+impl<'a, T, C> Iterator for Iter<'a, T, C> where C: Coord {
+	type Item = ((C, C), &'a T);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let ((x, y), value) = match &mut self.inner {
+			IterInner::Dense { min, width, cells } => cells.find_map(|(i, cell)| {
+				let value = cell.as_ref()?;
+				let x = min.0 + i64::try_from(i % *width).expect("column fits in i64");
+				let y = min.1 + i64::try_from(i / *width).expect("row fits in i64");
+				Some(((x, y), value))
+			})?,
+
+			IterInner::Sparse(cells) => {
+				let (&(y, x), value) = cells.next()?;
+				((x, y), value)
+			},
+		};
+
+		let pos = (C::from_i64(x).expect("cells are at valid coordinates"), C::from_i64(y).expect("cells are at valid coordinates"));
+		Some((pos, value))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_grid() {
+		fn test(mut grid: super::Grid<char>) {
+			assert!(grid.is_empty());
+			assert_eq!(grid.bounds(), None);
+			assert_eq!(grid.render(|_| '?'), "");
+
+			assert_eq!(grid.insert((1, -1), 'a'), None);
+			assert_eq!(grid.insert((-1, 1), 'b'), None);
+			assert_eq!(grid.insert((0, 0), 'c'), None);
+			assert_eq!(grid.insert((1, -1), 'd'), Some('a'));
+			*grid.get_or_insert_with((0, 1), || 'e') = 'f';
+			assert_eq!(*grid.get_or_insert_with((0, 1), || 'g'), 'f');
+
+			assert_eq!(grid.len(), 4);
+			assert_eq!(grid.get((1, -1)), Some(&'d'));
+			assert_eq!(grid.get((1, 1)), None);
+			assert_eq!(grid.get((5, 5)), None);
+			assert!(grid.contains((0, 0)));
+
+			assert_eq!(grid.iter().collect::<Vec<_>>(), [((1, -1), &'d'), ((0, 0), &'c'), ((-1, 1), &'b'), ((0, 1), &'f')]);
+			assert_eq!(grid.bounds(), Some(((-1, -1), (1, 1))));
+			assert_eq!(grid.render(|cell| cell.copied().unwrap_or('.')), "..d\n.c.\nbf.\n");
+
+			assert_eq!(grid.neighbors((0, 0), super::Neighborhood::Four).collect::<Vec<_>>(), [((0, 1), &'f')]);
+			assert_eq!(grid.neighbors((0, 0), super::Neighborhood::Eight).collect::<Vec<_>>(), [((1, -1), &'d'), ((-1, 1), &'b'), ((0, 1), &'f')]);
+
+			assert_eq!(grid.remove((0, 0)), Some('c'));
+			assert_eq!(grid.remove((0, 0)), None);
+			assert_eq!(grid.remove((5, 5)), None);
+			assert_eq!(grid.len(), 3);
+		}
+
+		test(super::Grid::sparse());
+		test(super::Grid::dense((-1, -1), (1, 1)));
+	}
+
+	#[test]
+	#[should_panic(expected = "(2, 0) is outside the grid")]
+	fn test_dense_outside() {
+		let mut grid: super::Grid<char> = super::Grid::dense((-1, -1), (1, 1));
+		grid.insert((2, 0), 'a');
+	}
+
+	#[test]
+	fn test_neighbors() {
+		assert_eq!(super::neighbors((0_usize, 0), super::Neighborhood::Four).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+		assert_eq!(super::neighbors((0_usize, 1), super::Neighborhood::Eight).collect::<Vec<_>>(), [(0, 0), (1, 0), (1, 1), (0, 2), (1, 2)]);
+		assert_eq!(super::neighbors((0_i64, 0), super::Neighborhood::Four).collect::<Vec<_>>(), [(0, -1), (-1, 0), (1, 0), (0, 1)]);
+	}
+
+	#[test]
+	fn test_parse() {
+		let rows = crate::read_grid(&b"#.#\n.@\n"[..]).unwrap();
+		let grid: super::Grid<bool, usize> = super::Grid::parse(&rows, |_, c| match c {
+			b'#' => Ok(Some(true)),
+			b'@' => Ok(Some(false)),
+			b'.' => Ok(None),
+			c => Err(format!("unexpected {:?}", char::from(c)).into()),
+		}).unwrap();
+		assert_eq!(grid.iter().collect::<Vec<_>>(), [((0, 0), &true), ((2, 0), &true), ((1, 1), &false)]);
+		assert_eq!(grid.render(|cell| match cell { Some(true) => '#', Some(false) => '@', None => '.' }), "#.#\n.@.\n");
+
+		let rows = crate::read_grid(&b"#.#\n.x\n"[..]).unwrap();
+		let err = super::Grid::<bool, usize>::parse(&rows, |_, c| if c == b'x' { Err("unexpected x".into()) } else { Ok(None) }).unwrap_err();
+		assert_eq!(err.to_string(), "2:2: unexpected x\n  |\n2 | .x\n  |  ^");
+	}
+}
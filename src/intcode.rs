@@ -1,38 +1,164 @@
+//! An Intcode computer.
+//!
+//! A program is loaded into a [`Ram`], which is run by a [`Computer`]. The submodules contain tools for building, analyzing,
+//! debugging and running Intcode programs.
+//!
+//! ```
+//! // Outputs its input times three
+//! let ram: aoc2019::intcode::Ram = "3,9,1002,9,3,9,4,9,99,0".parse()?;
+//! let mut computer = aoc2019::intcode::Computer::new(ram);
+//! assert_eq!(computer.execute([14])?, [42]);
+//! # Ok::<_, aoc2019::Error>(())
+//! ```
+
 use std::convert::TryInto;
 
+pub mod compiler;
+
+pub mod batch;
+
+pub mod debugger;
+
+pub mod dump;
+
+pub mod optimizer;
+
+pub mod self_modification;
+
+pub mod strings;
+
+pub mod symbolic;
+
+pub mod transcript;
+
+/// An Intcode computer running a program.
+///
+/// `W` is the type of the values in memory. See [`Word`].
 #[derive(Clone)]
-pub(crate) struct Computer {
-	pub(crate) ram: Ram,
+pub struct Computer<W = i64> {
+	/// The computer's memory, which holds both the program and its data
+	pub ram: Ram<W>,
 	pc: usize,
 	relative_base: isize,
+	instructions_executed: u64,
+	self_modification: Option<Box<self_modification::Monitor>>,
+	transcript: Option<Box<transcript::Transcript<W>>>,
 }
 
-impl Computer {
-	pub(crate) fn new(ram: Ram) -> Self {
+impl<W> Computer<W> where W: Word {
+	/// Creates a computer that starts executing the given program at address 0.
+	pub fn new(ram: Ram<W>) -> Self {
 		Computer {
 			ram,
 			pc: 0,
 			relative_base: 0,
+			instructions_executed: 0,
+			self_modification: None,
+			transcript: None,
 		}
 	}
 
-	pub(crate) fn step(&mut self, input: impl IntoIterator<Item = i64>) -> Result<Option<i64>, super::Error> {
+	/// Starts recording inputs and outputs. Has no effect if already started.
+	pub fn record_transcript(&mut self) {
+		self.transcript.get_or_insert_with(Default::default);
+	}
+
+	/// The inputs and outputs recorded since `record_transcript` was called
+	pub fn transcript(&self) -> Option<&transcript::Transcript<W>> {
+		self.transcript.as_deref()
+	}
+
+	/// Starts recording writes to code and execution of written memory. Has no effect if already started.
+	pub fn monitor_self_modification(&mut self) {
+		self.self_modification.get_or_insert_with(Default::default);
+	}
+
+	/// The self-modifications recorded since `monitor_self_modification` was called
+	pub fn self_modification_monitor(&self) -> Option<&self_modification::Monitor> {
+		self.self_modification.as_deref()
+	}
+
+	/// The address of the next instruction to execute
+	pub fn pc(&self) -> usize {
+		self.pc
+	}
+
+	/// The base address of parameters in relative mode
+	pub fn relative_base(&self) -> isize {
+		self.relative_base
+	}
+
+	/// The number of instructions executed so far
+	pub fn instructions_executed(&self) -> u64 {
+		self.instructions_executed
+	}
+
+	/// Runs the program until it outputs a value or halts.
+	///
+	/// Returns the output, or `None` if the program halted. Input instructions take values from the given iterator,
+	/// and fail with an `EOF` error if it's exhausted.
+	pub fn step(&mut self, input: impl IntoIterator<Item = W>) -> Result<Option<W>, super::Error> {
 		let mut input = input.into_iter();
 
 		loop {
-			let instruction = Instruction::parse(&mut self.ram, &mut self.pc)?;
+			match self.step_instruction(&mut input)? {
+				Step::Continue => (),
+				Step::Output(out) => return Ok(Some(out)),
+				Step::Halt => return Ok(None),
+			}
+		}
+	}
+
+	/// Executes a single instruction
+	pub fn step_instruction(&mut self, input: &mut impl Iterator<Item = W>) -> Result<Step<W>, super::Error> {
+		let start = self.pc;
+		let instruction = Instruction::parse(&mut self.ram, &mut self.pc)?;
+
+		let written = match &mut self.self_modification {
+			Some(monitor) => {
+				monitor.on_execute(start..self.pc);
+				instruction.out().map(|out| out.index(self.relative_base)).transpose()?
+			},
+			None => None,
+		};
 
-			if let Some(out) = instruction.execute(&mut self.ram, &mut self.pc, &mut self.relative_base, &mut input)? {
-				return Ok(Some(out));
+		let instruction_number = self.instructions_executed;
+
+		let output = match &mut self.transcript {
+			Some(transcript) => {
+				let input = input.inspect(|value| transcript.events.push(transcript::Event::Input { instruction: instruction_number, value: value.clone() }));
+				instruction.execute(&mut self.ram, &mut self.pc, &mut self.relative_base, input)?
+			},
+			None => instruction.execute(&mut self.ram, &mut self.pc, &mut self.relative_base, input)?,
+		};
+
+		self.instructions_executed += 1;
+
+		if let (Some(monitor), Some(written)) = (&mut self.self_modification, written) {
+			monitor.on_write(start, written);
+		}
+
+		if let Some(out) = output {
+			if let Some(transcript) = &mut self.transcript {
+				transcript.events.push(transcript::Event::Output { instruction: instruction_number, value: out.clone() });
 			}
 
-			if let Instruction::Halt = instruction {
-				return Ok(None);
+			return Ok(Step::Output(out));
+		}
+
+		if let Instruction::Halt = instruction {
+			if let Some(transcript) = &mut self.transcript {
+				transcript.events.push(transcript::Event::Halt { instruction: instruction_number });
 			}
+
+			return Ok(Step::Halt);
 		}
+
+		Ok(Step::Continue)
 	}
 
-	pub(crate) fn execute(&mut self, input: impl IntoIterator<Item = i64>) -> Result<Vec<i64>, super::Error> {
+	/// Runs the program until it halts, and returns all its outputs.
+	pub fn execute(&mut self, input: impl IntoIterator<Item = W>) -> Result<Vec<W>, super::Error> {
 		let mut input = input.into_iter();
 
 		let mut result = vec![];
@@ -45,54 +171,242 @@ impl Computer {
 	}
 }
 
+/// The result of executing a single instruction
+pub enum Step<W = i64> {
+	/// The instruction did not output anything
+	Continue,
+
+	/// The instruction output a value
+	Output(W),
+
+	/// The instruction halted the program
+	Halt,
+}
+
+/// The type of a single cell of memory, and thus of every value the program operates on.
+///
+/// Arithmetic is checked, so a program either runs exactly or fails with an overflow error.
+/// Use `i128` or `num_bigint::BigInt` for programs that overflow i64.
+pub trait Word: Clone + std::fmt::Debug + PartialEq + PartialOrd + From<i64> {
+	/// Parses a value from its decimal representation
+	fn parse(s: &str) -> Result<Self, super::Error>;
+
+	/// Adds two values, or returns `None` on overflow
+	fn checked_add(&self, other: &Self) -> Option<Self>;
+
+	/// Multiplies two values, or returns `None` on overflow
+	fn checked_mul(&self, other: &Self) -> Option<Self>;
+
+	/// Converts the value to an i64, or returns `None` if it doesn't fit. Addresses and parameter modes must fit in i64.
+	fn to_i64(&self) -> Option<i64>;
+}
+
+impl Word for i64 {
+	fn parse(s: &str) -> Result<Self, super::Error> {
+		Ok(s.parse()?)
+	}
+
+	fn checked_add(&self, other: &Self) -> Option<Self> {
+		i64::checked_add(*self, *other)
+	}
+
+	fn checked_mul(&self, other: &Self) -> Option<Self> {
+		i64::checked_mul(*self, *other)
+	}
+
+	fn to_i64(&self) -> Option<i64> {
+		Some(*self)
+	}
+}
+
+impl Word for i128 {
+	fn parse(s: &str) -> Result<Self, super::Error> {
+		Ok(s.parse()?)
+	}
+
+	fn checked_add(&self, other: &Self) -> Option<Self> {
+		i128::checked_add(*self, *other)
+	}
+
+	fn checked_mul(&self, other: &Self) -> Option<Self> {
+		i128::checked_mul(*self, *other)
+	}
+
+	fn to_i64(&self) -> Option<i64> {
+		(*self).try_into().ok()
+	}
+}
+
+impl Word for num_bigint::BigInt {
+	fn parse(s: &str) -> Result<Self, super::Error> {
+		Ok(s.parse()?)
+	}
+
+	fn checked_add(&self, other: &Self) -> Option<Self> {
+		Some(self + other)
+	}
+
+	fn checked_mul(&self, other: &Self) -> Option<Self> {
+		Some(self * other)
+	}
+
+	fn to_i64(&self) -> Option<i64> {
+		self.try_into().ok()
+	}
+}
+
+fn to_i64<W>(value: &W) -> Result<i64, super::Error> where W: Word {
+	Ok(value.to_i64().ok_or_else(|| format!("{value:?} does not fit in i64"))?)
+}
+
+/// The memory of a computer.
+///
+/// Memory grows on demand, and cells that were never written are zero.
 #[derive(Clone, Debug)]
-pub(crate) struct Ram(pub(crate) Vec<i64>);
+pub struct Ram<W = i64>(pub Vec<W>);
 
-impl Ram {
-	pub(crate) fn get(&mut self, index: usize) -> i64 {
+impl<W> Ram<W> where W: Word {
+	/// Returns the value at the given address
+	pub fn get(&mut self, index: usize) -> W {
 		if index >= self.0.len() {
-			self.0.resize(index + 1, 0);
+			self.0.resize(index + 1, W::from(0));
 		}
 
-		self.0[index]
+		self.0[index].clone()
 	}
 
-	pub(crate) fn get_mut(&mut self, index: usize) -> &mut i64 {
+	/// Returns a reference to the value at the given address, for writing it
+	pub fn get_mut(&mut self, index: usize) -> &mut W {
 		if index >= self.0.len() {
-			self.0.resize(index + 1, 0);
+			self.0.resize(index + 1, W::from(0));
 		}
 
 		&mut self.0[index]
 	}
 }
 
-impl std::str::FromStr for Ram {
+impl Ram {
+	/// Loads a program from either its text form, as parsed by `FromStr`, or its binary form, as parsed by `from_le_bytes`.
+	///
+	/// Input that's valid UTF-8 without any NUL bytes is text. Binary programs almost always have NUL bytes,
+	/// since every word that's smaller than 2^56 has one.
+	pub fn load(mut input: impl std::io::BufRead) -> Result<Self, super::Error> {
+		let mut bytes = vec![];
+		let _ = input.read_to_end(&mut bytes)?;
+
+		match std::str::from_utf8(&bytes) {
+			Ok(s) if !bytes.contains(&0) => s.parse(),
+			_ => Ram::from_le_bytes(&bytes),
+		}
+	}
+
+	/// Parses a program stored as a sequence of 8-byte little-endian words.
+	pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, super::Error> {
+		let chunks = bytes.chunks_exact(8);
+
+		let remainder = chunks.remainder();
+		if !remainder.is_empty() {
+			return Err(format!(
+				"word {} (byte {}): expected 8 bytes but only {} remain",
+				bytes.len() / 8, bytes.len() - remainder.len(), remainder.len(),
+			).into());
+		}
+
+		Ok(Ram(chunks.map(|chunk| i64::from_le_bytes(chunk.try_into().expect("chunk has 8 bytes"))).collect()))
+	}
+
+	/// The inverse of `from_le_bytes`
+	pub fn to_le_bytes(&self) -> Vec<u8> {
+		self.0.iter().flat_map(|word| word.to_le_bytes()).collect()
+	}
+}
+
+/// Values are separated by commas. Whitespace and newlines around values are ignored, as is everything from a `#` to the end of the line,
+/// so fixture files can be split over lines and annotated. A trailing comma is allowed.
+///
+/// Errors report the index of the offending token, which is also the address it would have been loaded at, and its byte offset.
+impl<W> std::str::FromStr for Ram<W> where W: Word {
 	type Err = super::Error;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let ram: Result<Vec<_>, super::Error> =
-			s.split(',')
-			.map(|s| Ok(s.parse()?))
-			.collect();
-		Ok(Ram(ram?))
+		let mut ram = vec![];
+
+		// Byte range of the current token
+		let mut token: Option<(usize, usize)> = None;
+
+		let mut in_comment = false;
+
+		for (i, c) in s.char_indices() {
+			if in_comment {
+				in_comment = c != '\n';
+				continue;
+			}
+
+			match c {
+				'#' => in_comment = true,
+
+				',' => match token.take() {
+					Some((start, end)) => ram.push(parse_token(&s[start..end], ram.len(), start)?),
+					None => return Err(format!("token {} (byte {i}): expected a value but found ','", ram.len()).into()),
+				},
+
+				c if c.is_whitespace() => (),
+
+				c => match &mut token {
+					Some((_, end)) if *end == i => *end = i + c.len_utf8(),
+					Some((start, end)) => return Err(format!("token {} (byte {i}): expected ',' after {:?}", ram.len(), &s[*start..*end]).into()),
+					None => token = Some((i, i + c.len_utf8())),
+				},
+			}
+		}
+
+		if let Some((start, end)) = token {
+			ram.push(parse_token(&s[start..end], ram.len(), start)?);
+		}
+
+		Ok(Ram(ram))
 	}
 }
 
+fn parse_token<W>(token: &str, index: usize, offset: usize) -> Result<W, super::Error> where W: Word {
+	Ok(W::parse(token).map_err(|err| format!("token {index} (byte {offset}): invalid value {token:?}: {}", err.0))?)
+}
+
+/// A decoded instruction
 #[derive(Clone, Copy, PartialEq)]
-pub(crate) enum Instruction {
-	Add(ParameterIn, ParameterIn, ParameterOut),
-	Mul(ParameterIn, ParameterIn, ParameterOut),
+pub enum Instruction<W = i64> {
+	/// Opcode 1: `out <- in1 + in2`
+	Add(ParameterIn<W>, ParameterIn<W>, ParameterOut),
+
+	/// Opcode 2: `out <- in1 * in2`
+	Mul(ParameterIn<W>, ParameterIn<W>, ParameterOut),
+
+	/// Opcode 3: `out <- input`
 	Store(ParameterOut),
-	Output(ParameterIn),
-	JumpIfTrue(ParameterIn, ParameterIn),
-	JumpIfFalse(ParameterIn, ParameterIn),
-	LessThan(ParameterIn, ParameterIn, ParameterOut),
-	Equals(ParameterIn, ParameterIn, ParameterOut),
-	SetRelativeBase(ParameterIn),
+
+	/// Opcode 4: `output <- in`
+	Output(ParameterIn<W>),
+
+	/// Opcode 5: `if cond != 0 then goto in`
+	JumpIfTrue(ParameterIn<W>, ParameterIn<W>),
+
+	/// Opcode 6: `if cond == 0 then goto in`
+	JumpIfFalse(ParameterIn<W>, ParameterIn<W>),
+
+	/// Opcode 7: `out <- if in1 < in2 then 1 else 0`
+	LessThan(ParameterIn<W>, ParameterIn<W>, ParameterOut),
+
+	/// Opcode 8: `out <- if in1 == in2 then 1 else 0`
+	Equals(ParameterIn<W>, ParameterIn<W>, ParameterOut),
+
+	/// Opcode 9: `relative_base <- relative_base + in`
+	SetRelativeBase(ParameterIn<W>),
+
+	/// Opcode 99
 	Halt,
 }
 
-impl std::fmt::Debug for Instruction {
+impl<W> std::fmt::Debug for Instruction<W> where W: std::fmt::Debug {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Instruction::Add(in1, in2, out) => write!(f, "{out:?} <- {in1:?} + {in2:?}"),
@@ -109,9 +423,11 @@ impl std::fmt::Debug for Instruction {
 	}
 }
 
-impl Instruction {
-	pub(crate) fn parse(ram: &mut Ram, pc: &mut usize) -> Result<Self, super::Error> {
+impl<W> Instruction<W> where W: Word {
+	/// Decodes the instruction at `pc`, and advances `pc` past it.
+	pub fn parse(ram: &mut Ram<W>, pc: &mut usize) -> Result<Self, super::Error> {
 		let opcode = ram.get(*pc);
+		let opcode = opcode.to_i64().ok_or_else(|| format!("SIGILL({opcode:?})"))?;
 		if opcode < 0 {
 			return Err(format!("SIGILL({opcode})").into());
 		}
@@ -194,15 +510,59 @@ impl Instruction {
 		}
 	}
 
-	fn execute(self, ram: &mut Ram, pc: &mut usize, relative_base: &mut isize, mut input: impl Iterator<Item = i64>) -> Result<Option<i64>, super::Error> {
+	/// The inverse of `parse`
+	pub fn encode(&self) -> Vec<W> {
+		let (opcode, parameters) = match self {
+			Instruction::Add(in1, in2, out) => (1, vec![in1.encode(), in2.encode(), out.encode()]),
+			Instruction::Mul(in1, in2, out) => (2, vec![in1.encode(), in2.encode(), out.encode()]),
+			Instruction::Store(out) => (3, vec![out.encode()]),
+			Instruction::Output(r#in) => (4, vec![r#in.encode()]),
+			Instruction::JumpIfTrue(cond, r#in) => (5, vec![cond.encode(), r#in.encode()]),
+			Instruction::JumpIfFalse(cond, r#in) => (6, vec![cond.encode(), r#in.encode()]),
+			Instruction::LessThan(in1, in2, out) => (7, vec![in1.encode(), in2.encode(), out.encode()]),
+			Instruction::Equals(in1, in2, out) => (8, vec![in1.encode(), in2.encode(), out.encode()]),
+			Instruction::SetRelativeBase(r#in) => (9, vec![r#in.encode()]),
+			Instruction::Halt => (99, vec![]),
+		};
+
+		let mut opcode = opcode;
+		let mut mode_multiplier = 100;
+		for (mode, _) in &parameters {
+			opcode += mode * mode_multiplier;
+			mode_multiplier *= 10;
+		}
+
+		std::iter::once(W::from(opcode)).chain(parameters.into_iter().map(|(_, value)| value)).collect()
+	}
+
+	fn out(&self) -> Option<ParameterOut> {
+		match self {
+			Instruction::Add(_, _, out) |
+			Instruction::Mul(_, _, out) |
+			Instruction::Store(out) |
+			Instruction::LessThan(_, _, out) |
+			Instruction::Equals(_, _, out) => Some(*out),
+			Instruction::Output(_) |
+			Instruction::JumpIfTrue(_, _) |
+			Instruction::JumpIfFalse(_, _) |
+			Instruction::SetRelativeBase(_) |
+			Instruction::Halt => None,
+		}
+	}
+
+	fn execute(&self, ram: &mut Ram<W>, pc: &mut usize, relative_base: &mut isize, mut input: impl Iterator<Item = W>) -> Result<Option<W>, super::Error> {
 		match self {
 			Instruction::Add(in1, in2, out) => {
-				*out.get_mut(ram, *relative_base)? = in1.get(ram, *relative_base)? + in2.get(ram, *relative_base)?;
+				let in1 = in1.get(ram, *relative_base)?;
+				let in2 = in2.get(ram, *relative_base)?;
+				*out.get_mut(ram, *relative_base)? = in1.checked_add(&in2).ok_or_else(|| format!("SIGFPE: {in1:?} + {in2:?} overflows"))?;
 				Ok(None)
 			},
 
 			Instruction::Mul(in1, in2, out) => {
-				*out.get_mut(ram, *relative_base)? = in1.get(ram, *relative_base)? * in2.get(ram, *relative_base)?;
+				let in1 = in1.get(ram, *relative_base)?;
+				let in2 = in2.get(ram, *relative_base)?;
+				*out.get_mut(ram, *relative_base)? = in1.checked_mul(&in2).ok_or_else(|| format!("SIGFPE: {in1:?} * {in2:?} overflows"))?;
 				Ok(None)
 			},
 
@@ -217,31 +577,31 @@ impl Instruction {
 			},
 
 			Instruction::JumpIfTrue(cond, r#in) => {
-				if r#cond.get(ram, *relative_base)? != 0 {
-					*pc = r#in.get(ram, *relative_base)?.try_into()?;
+				if r#cond.get(ram, *relative_base)? != W::from(0) {
+					*pc = to_i64(&r#in.get(ram, *relative_base)?)?.try_into()?;
 				}
 				Ok(None)
 			},
 
 			Instruction::JumpIfFalse(cond, r#in) => {
-				if r#cond.get(ram, *relative_base)? == 0 {
-					*pc = r#in.get(ram, *relative_base)?.try_into()?;
+				if r#cond.get(ram, *relative_base)? == W::from(0) {
+					*pc = to_i64(&r#in.get(ram, *relative_base)?)?.try_into()?;
 				}
 				Ok(None)
 			},
 
 			Instruction::LessThan(in1, in2, out) => {
-				*out.get_mut(ram, *relative_base)? = if in1.get(ram, *relative_base)? < in2.get(ram, *relative_base)? { 1 } else { 0 };
+				*out.get_mut(ram, *relative_base)? = W::from(if in1.get(ram, *relative_base)? < in2.get(ram, *relative_base)? { 1 } else { 0 });
 				Ok(None)
 			},
 
 			Instruction::Equals(in1, in2, out) => {
-				*out.get_mut(ram, *relative_base)? = if in1.get(ram, *relative_base)? == in2.get(ram, *relative_base)? { 1 } else { 0 };
+				*out.get_mut(ram, *relative_base)? = W::from(if in1.get(ram, *relative_base)? == in2.get(ram, *relative_base)? { 1 } else { 0 });
 				Ok(None)
 			},
 
 			Instruction::SetRelativeBase(r#in) => {
-				let r#in: isize = r#in.get(ram, *relative_base)?.try_into()?;
+				let r#in: isize = to_i64(&r#in.get(ram, *relative_base)?)?.try_into()?;
 				*relative_base += r#in;
 				Ok(None)
 			},
@@ -252,53 +612,82 @@ impl Instruction {
 	}
 }
 
+/// A parameter that an instruction reads from
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub(crate) enum ParameterIn {
+pub enum ParameterIn<W = i64> {
+	/// Mode 0: the value at the given address
 	Position(usize),
-	Immediate(i64),
+
+	/// Mode 1: the given value itself
+	Immediate(W),
+
+	/// Mode 2: the value at the given offset from the relative base
 	Relative(isize),
 }
 
-impl ParameterIn {
-	fn new(mode: u8, value: i64) -> Result<Self, super::Error> {
+impl<W> ParameterIn<W> where W: Word {
+	fn new(mode: u8, value: W) -> Result<Self, super::Error> {
 		match mode {
-			0 => Ok(ParameterIn::Position(value.try_into()?)),
+			0 => Ok(ParameterIn::Position(to_i64(&value)?.try_into()?)),
 			1 => Ok(ParameterIn::Immediate(value)),
-			2 => Ok(ParameterIn::Relative(value.try_into()?)),
+			2 => Ok(ParameterIn::Relative(to_i64(&value)?.try_into()?)),
 			mode => Err(format!("invalid parameter mode {mode}").into()),
 		}
 	}
 
-	fn get(self, ram: &mut Ram, relative_base: isize) -> Result<i64, super::Error> {
+	fn encode(&self) -> (i64, W) {
+		match self {
+			ParameterIn::Position(pos) => (0, W::from((*pos).try_into().expect("usize fits in i64"))),
+			ParameterIn::Immediate(value) => (1, value.clone()),
+			ParameterIn::Relative(offset) => (2, W::from((*offset).try_into().expect("isize fits in i64"))),
+		}
+	}
+
+	fn get(&self, ram: &mut Ram<W>, relative_base: isize) -> Result<W, super::Error> {
 		let index = match self {
-			ParameterIn::Position(pos) => pos,
-			ParameterIn::Immediate(value) => return Ok(value),
+			ParameterIn::Position(pos) => *pos,
+			ParameterIn::Immediate(value) => return Ok(value.clone()),
 			ParameterIn::Relative(offset) => (relative_base + offset).try_into()?,
 		};
 		Ok(ram.get(index))
 	}
 }
 
+/// A parameter that an instruction writes to
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub(crate) enum ParameterOut {
+pub enum ParameterOut {
+	/// Mode 0: the given address
 	Position(usize),
+
+	/// Mode 2: the given offset from the relative base
 	Relative(isize),
 }
 
 impl ParameterOut {
-	fn get_mut(self, ram: &mut Ram, relative_base: isize) -> Result<&mut i64, super::Error> {
-		let index = match self {
-			ParameterOut::Position(pos) => pos,
-			ParameterOut::Relative(offset) => (relative_base + offset).try_into()?,
-		};
+	fn encode<W>(self) -> (i64, W) where W: Word {
+		match self {
+			ParameterOut::Position(pos) => (0, W::from(pos.try_into().expect("usize fits in i64"))),
+			ParameterOut::Relative(offset) => (2, W::from(offset.try_into().expect("isize fits in i64"))),
+		}
+	}
+
+	fn index(self, relative_base: isize) -> Result<usize, super::Error> {
+		match self {
+			ParameterOut::Position(pos) => Ok(pos),
+			ParameterOut::Relative(offset) => Ok((relative_base + offset).try_into()?),
+		}
+	}
+
+	fn get_mut<W>(self, ram: &mut Ram<W>, relative_base: isize) -> Result<&mut W, super::Error> where W: Word {
+		let index = self.index(relative_base)?;
 		Ok(ram.get_mut(index))
 	}
 }
 
-impl std::convert::TryFrom<ParameterIn> for ParameterOut {
+impl<W> std::convert::TryFrom<ParameterIn<W>> for ParameterOut {
 	type Error = super::Error;
 
-	fn try_from(param: ParameterIn) -> Result<Self, Self::Error> {
+	fn try_from(param: ParameterIn<W>) -> Result<Self, Self::Error> {
 		match param {
 			ParameterIn::Position(pos) => Ok(ParameterOut::Position(pos)),
 			ParameterIn::Immediate(_) => Err("SIGILL: output parameter has immediate mode".into()),
@@ -306,3 +695,97 @@ impl std::convert::TryFrom<ParameterIn> for ParameterOut {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_word_size() {
+		fn test<W>(program: &str, expected_output: Result<&[&str], &str>) where W: super::Word + std::fmt::Display {
+			let mut computer = super::Computer::<W>::new(program.parse().unwrap());
+
+			let actual_output = computer.execute(std::iter::empty());
+			match (expected_output, actual_output) {
+				(Ok(expected_output), Ok(actual_output)) => {
+					let actual_output: Vec<_> = actual_output.iter().map(ToString::to_string).collect();
+					assert_eq!(expected_output, actual_output);
+				},
+
+				(Err(expected_err), Err(actual_err)) => assert_eq!(expected_err, actual_err.0.to_string()),
+
+				(expected_output, actual_output) => panic!("expected {expected_output:?} but got {actual_output:?}"),
+			}
+		}
+
+		// Fits in i64
+		let program = "1102,34915192,34915192,7,4,7,99,0";
+		test::<i64>(program, Ok(&["1219070632396864"]));
+		test::<i128>(program, Ok(&["1219070632396864"]));
+		test::<num_bigint::BigInt>(program, Ok(&["1219070632396864"]));
+
+		// 2^32 * 2^32 = 2^64
+		let program = "1102,4294967296,4294967296,7,4,7,99,0";
+		test::<i64>(program, Err("SIGFPE: 4294967296 * 4294967296 overflows"));
+		test::<i128>(program, Ok(&["18446744073709551616"]));
+		test::<num_bigint::BigInt>(program, Ok(&["18446744073709551616"]));
+
+		// 2^64 * 2^64 = 2^128
+		let program = "1102,18446744073709551616,18446744073709551616,7,4,7,99,0";
+		test::<i128>(program, Err("SIGFPE: 18446744073709551616 * 18446744073709551616 overflows"));
+		test::<num_bigint::BigInt>(program, Ok(&["340282366920938463463374607431768211456"]));
+
+		// Comparisons and jumps work with wide values too
+		let program = "1107,18446744073709551616,18446744073709551617,10,1006,10,9,104,1,99,0";
+		test::<i128>(program, Ok(&["1"]));
+		test::<num_bigint::BigInt>(program, Ok(&["1"]));
+	}
+
+	#[test]
+	fn test_parse_ram() {
+		fn test(s: &str, expected: Result<&[i64], &str>) {
+			let actual: Result<super::Ram, _> = s.parse();
+			match (expected, actual) {
+				(Ok(expected), Ok(actual)) => assert_eq!(expected, actual.0),
+				(Err(expected_err), Err(actual_err)) => assert_eq!(expected_err, actual_err.0.to_string()),
+				(expected, actual) => panic!("expected {expected:?} but got {actual:?}"),
+			}
+		}
+
+		test("1,9,10,3,2,3,11,0,99,30,40,50", Ok(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]));
+		test("1,0,0,0,99\n", Ok(&[1, 0, 0, 0, 99]));
+		test("", Ok(&[]));
+		test("\
+			# Add [5] + [6] into [0]
+			1, 5, 6, 0,
+			99, # halt
+
+			# data
+			30, 40,
+		", Ok(&[1, 5, 6, 0, 99, 30, 40]));
+		test("1, -2,\r\n\t3", Ok(&[1, -2, 3]));
+
+		test("1,2,x3,4", Err(r#"token 2 (byte 4): invalid value "x3": invalid digit found in string"#));
+		test("1,2\n3,4", Err(r#"token 1 (byte 4): expected ',' after "2""#));
+		test("1,,3", Err("token 1 (byte 2): expected a value but found ','"));
+		test("1, # 2,\n 3 4", Err(r#"token 1 (byte 11): expected ',' after "3""#));
+		test("99999999999999999999", Err(r#"token 0 (byte 0): invalid value "99999999999999999999": number too large to fit in target type"#));
+	}
+
+	#[test]
+	fn test_le_bytes() {
+		let ram: super::Ram = "1,-2,99".parse().unwrap();
+		let bytes = ram.to_le_bytes();
+		assert_eq!(bytes.len(), 24);
+		assert_eq!(&bytes[..8], &[1, 0, 0, 0, 0, 0, 0, 0]);
+		assert_eq!(&bytes[8..16], &[0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+		assert_eq!(ram.0, super::Ram::from_le_bytes(&bytes).unwrap().0);
+
+		assert_eq!(
+			"word 2 (byte 16): expected 8 bytes but only 3 remain",
+			super::Ram::from_le_bytes(&bytes[..19]).unwrap_err().0.to_string(),
+		);
+
+		// Loading detects the format
+		assert_eq!(ram.0, super::Ram::load(&bytes[..]).unwrap().0);
+		assert_eq!(ram.0, super::Ram::load(&b"1, -2, 99 # halt\n"[..]).unwrap().0);
+	}
+}
@@ -0,0 +1,177 @@
+//! Runs the same program many times with different inputs, in parallel.
+//!
+//! Each configuration gets a fresh computer loaded with a copy of the program, and the given function drives it and extracts a result.
+//! Programs that run as several cooperating computers, like a chain of amplifiers, can use [`run_group`] to get a group of fresh computers
+//! per configuration instead.
+//! Results are returned in the order of the configurations regardless of which run finished first.
+//!
+//! Errors are prefixed with the index of the configuration that failed, and keep their source and backtrace.
+//! If several runs fail, the error of the earliest configuration is reported.
+
+/// Runs the program once for each configuration.
+pub fn run<W, C, T>(
+	ram: &super::Ram<W>,
+	configs: impl IntoIterator<Item = C>,
+	f: impl Fn(&mut super::Computer<W>, C) -> Result<T, crate::Error> + Sync,
+) -> Result<Vec<T>, crate::Error> where W: super::Word + Send + Sync, C: Send, T: Send {
+	run_group(ram, 1, configs, |computers, config| f(&mut computers[0], config))
+}
+
+/// Like `run`, but gives each configuration `size` computers that are each loaded with a copy of the program.
+pub fn run_group<W, C, T>(
+	ram: &super::Ram<W>,
+	size: usize,
+	configs: impl IntoIterator<Item = C>,
+	f: impl Fn(&mut [super::Computer<W>], C) -> Result<T, crate::Error> + Sync,
+) -> Result<Vec<T>, crate::Error> where W: super::Word + Send + Sync, C: Send, T: Send {
+	use rayon::prelude::*;
+
+	let configs: Vec<_> = configs.into_iter().collect();
+
+	let results: Vec<_> =
+		configs.into_par_iter()
+		.enumerate()
+		.map(|(index, config)| run_one(ram, size, &f, index, config))
+		.collect();
+
+	results.into_iter().collect()
+}
+
+/// Like `run`, but returns only the first result in configuration order that matches the predicate.
+///
+/// Once a match is found, configurations after it are no longer started. An error also ends the search, and is returned if no earlier
+/// configuration matched.
+pub fn find_first<W, C, T>(
+	ram: &super::Ram<W>,
+	configs: impl IntoIterator<Item = C>,
+	f: impl Fn(&mut super::Computer<W>, C) -> Result<T, crate::Error> + Sync,
+	predicate: impl Fn(&T) -> bool + Sync,
+) -> Result<Option<T>, crate::Error> where W: super::Word + Send + Sync, C: Send, T: Send {
+	use rayon::prelude::*;
+
+	let configs: Vec<_> = configs.into_iter().collect();
+
+	let result =
+		configs.into_par_iter()
+		.enumerate()
+		.find_map_first(|(index, config)| match run_one(ram, 1, &|computers: &mut [super::Computer<W>], config| f(&mut computers[0], config), index, config) {
+			Ok(result) if predicate(&result) => Some(Ok(result)),
+			Ok(_) => None,
+			Err(err) => Some(Err(err)),
+		});
+
+	result.transpose()
+}
+
+fn run_one<W, C, T>(
+	ram: &super::Ram<W>,
+	size: usize,
+	f: &impl Fn(&mut [super::Computer<W>], C) -> Result<T, crate::Error>,
+	index: usize,
+	config: C,
+) -> Result<T, crate::Error> where W: super::Word {
+	let mut computers: Vec<_> = (0..size).map(|_| super::Computer::new(ram.clone())).collect();
+	f(&mut computers, config).map_err(|crate::Error(err, backtrace)| crate::Error(Box::new(RunError { index, err }), backtrace))
+}
+
+// The error of the run of the configuration at the given index
+#[derive(Debug)]
+struct RunError {
+	index: usize,
+	err: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl std::fmt::Display for RunError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "run {}: {}", self.index, self.err)
+	}
+}
+
+impl std::error::Error for RunError {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		self.err.source()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Outputs its input times three
+	const PROGRAM: &str = "3,9,1002,9,3,9,4,9,99,0";
+
+	#[test]
+	fn test_run() {
+		let ram: crate::intcode::Ram = PROGRAM.parse().unwrap();
+
+		let actual = super::run(&ram, 0..1000, |computer, input| computer.execute([input])).unwrap();
+		let expected: Vec<_> = (0..1000).map(|input| vec![input * 3]).collect();
+		assert_eq!(expected, actual);
+
+		// The earliest failing configuration is reported
+		let actual_err = super::run(&ram, 0..1000, |computer, input| {
+			if input % 100 == 42 {
+				return Err(format!("bad input {input}").into());
+			}
+			computer.execute([input])
+		}).unwrap_err();
+		assert_eq!("run 42: bad input 42", actual_err.0.to_string());
+
+		let actual_err = super::run(&ram, vec![vec![1], vec![]], crate::intcode::Computer::execute).unwrap_err();
+		assert_eq!("run 1: EOF", actual_err.0.to_string());
+		// The error keeps its source
+		#[derive(Debug)]
+		struct Outer(std::num::ParseIntError);
+
+		impl std::fmt::Display for Outer {
+			fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+				f.write_str("outer")
+			}
+		}
+
+		impl std::error::Error for Outer {
+			fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+				Some(&self.0)
+			}
+		}
+
+		let actual_err = super::run(&ram, 0..2, |_, input| if input == 1 { Err(Outer("x".parse::<i64>().unwrap_err()).into()) } else { Ok(()) }).unwrap_err();
+		assert_eq!("run 1: outer", actual_err.0.to_string());
+		assert_eq!("invalid digit found in string", actual_err.0.source().unwrap().to_string());
+	}
+
+	#[test]
+	fn test_run_group() {
+		let ram: crate::intcode::Ram = PROGRAM.parse().unwrap();
+
+		// Each configuration chains its input through two computers
+		let actual = super::run_group(&ram, 2, 0..100, |computers, input| {
+			let output = computers[0].execute([input])?;
+			computers[1].execute(output)
+		}).unwrap();
+		let expected: Vec<_> = (0..100).map(|input| vec![input * 9]).collect();
+		assert_eq!(expected, actual);
+	}
+
+	#[test]
+	fn test_find_first() {
+		let ram: crate::intcode::Ram = PROGRAM.parse().unwrap();
+
+		let run = |computer: &mut crate::intcode::Computer, input| -> Result<_, crate::Error> {
+			let output = computer.execute([input])?;
+			Ok((input, output[0]))
+		};
+
+		// Every input that is a multiple of 6 matches, but the first in configuration order wins
+		let actual = super::find_first(&ram, (1..1000).rev(), run, |&(_, output)| output % 18 == 0).unwrap();
+		assert_eq!(Some((996, 2988)), actual);
+
+		let actual = super::find_first(&ram, 0..1000, run, |&(_, output)| output < 0).unwrap();
+		assert_eq!(None, actual);
+
+		// A match before the error wins over the error
+		let actual = super::find_first(&ram, vec![Some(1), Some(2), None], |computer, input| run(computer, input.ok_or("no input")?), |&(_, output)| output == 6).unwrap();
+		assert_eq!(Some((2, 6)), actual);
+
+		let actual_err = super::find_first(&ram, vec![Some(1), None, Some(2)], |computer, input| run(computer, input.ok_or("no input")?), |&(_, output)| output == 6).unwrap_err();
+		assert_eq!("run 1: no input", actual_err.0.to_string());
+	}
+}
@@ -0,0 +1,1051 @@
+//! A compiler from a small C-like language to Intcode.
+//!
+//! ```text
+//! fn main() {
+//!     let n = input();
+//!     while n > 0 {
+//!         output(fact(n));
+//!         n = n - 1;
+//!     }
+//! }
+//!
+//! fn fact(n) {
+//!     if n <= 1 {
+//!         return 1;
+//!     }
+//!     return n * fact(n - 1);
+//! }
+//! ```
+//!
+//! All values are integers. Supported operators, from lowest to highest precedence, are `||`, `&&`, `== != < <= > >=`, `+ -`, `*`
+//! and unary `- !`. Comparisons and logical operators evaluate to 0 or 1. `&&` and `||` short-circuit.
+//!
+//! `input()` reads the next input value, and `output(e)` emits the value of `e` and evaluates to it.
+//!
+//! Variables are declared with `let` and are local to the block they're declared in. They can't shadow other variables that are in scope. Execution starts at `main`, which takes no parameters.
+//!
+//!
+//! The relative base is used as the frame pointer. Each function has a fixed-size frame:
+//!
+//! ```text
+//! [rb + 0]                        return address
+//! [rb + 1]                        return value
+//! [rb + 2] ..                     parameters
+//! [rb + 2 + num_params] ..        local variables
+//! [rb + 2 + num_params + num_locals] ..   temporaries for evaluating expressions
+//! ```
+//!
+//! To call a function, the caller writes the return address and arguments into the slots just past the end of its own frame,
+//! adds its frame size to the relative base, and jumps to the callee. The callee returns by jumping to [rb + 0],
+//! after which the caller subtracts its frame size from the relative base again and reads the return value.
+//!
+//! The stack starts right after the end of the program.
+
+/// Compiles the given source into a program.
+pub fn compile(source: &str) -> Result<super::Ram, crate::Error> {
+	let tokens = tokenize(source)?;
+	let functions = Parser { tokens: &tokens, pos: 0 }.parse_program()?;
+
+	let mut signatures: std::collections::BTreeMap<&str, (Label, usize)> = Default::default();
+	let mut emitter = Emitter::default();
+
+	for function in &functions {
+		if function.name == "input" || function.name == "output" {
+			return Err(format!("function {:?} shadows a builtin", function.name).into());
+		}
+
+		let label = emitter.new_label();
+		if signatures.insert(&function.name, (label, function.params.len())).is_some() {
+			return Err(format!("function {:?} is defined more than once", function.name).into());
+		}
+	}
+
+	let &(main, main_num_params) = signatures.get("main").ok_or("function \"main\" is not defined")?;
+	if main_num_params != 0 {
+		return Err("function \"main\" must not have parameters".into());
+	}
+
+	// Prologue: Set up the stack and call main, with the return address pointing to a halt instruction.
+	let stack = emitter.new_label();
+	let halt = emitter.new_label();
+	emitter.emit(&[Word::Literal(109), Word::label(stack)]);
+	emitter.emit(&[Word::Literal(21101), Word::label(halt), Word::Literal(0), Word::Literal(0)]);
+	emitter.emit(&[Word::Literal(1105), Word::Literal(1), Word::label(main)]);
+	emitter.bind(halt);
+	emitter.emit(&[Word::Literal(99)]);
+
+	for function in &functions {
+		FunctionCompiler::compile(function, &signatures, &mut emitter)?;
+	}
+
+	emitter.bind(stack);
+
+	emitter.finish()
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Label(usize);
+
+#[derive(Clone, Copy, Debug)]
+enum Word {
+	Literal(i64),
+
+	// `scale * label + offset`
+	Label { label: Label, scale: i64, offset: i64 },
+}
+
+impl Word {
+	fn label(label: Label) -> Self {
+		Word::Label { label, scale: 1, offset: 0 }
+	}
+}
+
+#[derive(Default)]
+struct Emitter {
+	code: Vec<Word>,
+	labels: Vec<Option<i64>>,
+}
+
+impl Emitter {
+	fn new_label(&mut self) -> Label {
+		self.labels.push(None);
+		Label(self.labels.len() - 1)
+	}
+
+	fn bind(&mut self, label: Label) {
+		let value = self.code.len().try_into().expect("program length fits in i64");
+		self.bind_value(label, value);
+	}
+
+	fn bind_value(&mut self, label: Label, value: i64) {
+		let slot = &mut self.labels[label.0];
+		assert!(slot.is_none(), "label bound more than once");
+		*slot = Some(value);
+	}
+
+	fn emit(&mut self, words: &[Word]) {
+		self.code.extend_from_slice(words);
+	}
+
+	fn finish(self) -> Result<super::Ram, crate::Error> {
+		let labels = self.labels;
+		let ram: Result<Vec<_>, crate::Error> =
+			self.code.into_iter()
+			.map(|word| match word {
+				Word::Literal(value) => Ok(value),
+				Word::Label { label, scale, offset } => {
+					let value = labels[label.0].ok_or("unbound label")?;
+					Ok(scale * value + offset)
+				},
+			})
+			.collect();
+		Ok(super::Ram(ram?))
+	}
+}
+
+struct FunctionCompiler<'a> {
+	signatures: &'a std::collections::BTreeMap<&'a str, (Label, usize)>,
+	emitter: &'a mut Emitter,
+
+	// Map of variable name to its slot in the frame. Includes parameters.
+	variables: std::collections::BTreeMap<&'a str, i64>,
+
+	// Slot of the first temporary
+	temporaries_start: i64,
+	num_temporaries: i64,
+	max_temporaries: i64,
+
+	// Bound to the size of the frame once the whole function has been compiled
+	frame_size: Label,
+}
+
+impl<'a> FunctionCompiler<'a> {
+	fn compile(
+		function: &'a Function,
+		signatures: &'a std::collections::BTreeMap<&'a str, (Label, usize)>,
+		emitter: &'a mut Emitter,
+	) -> Result<(), crate::Error> {
+		let (label, _) = signatures[&*function.name];
+		emitter.bind(label);
+
+		let mut variables: std::collections::BTreeMap<&str, i64> = Default::default();
+		let mut next_slot = 2;
+		for param in &function.params {
+			if variables.insert(param, next_slot).is_some() {
+				return Err(format!("parameter {param:?} of function {:?} is declared more than once", function.name).into());
+			}
+			next_slot += 1;
+		}
+
+		// Locals are only usable after their `let`, but their slots are reserved up-front
+		// so that the temporaries can start at a fixed offset.
+		let num_locals = count_locals(&function.body);
+
+		let frame_size = emitter.new_label();
+
+		let mut compiler = FunctionCompiler {
+			signatures,
+			emitter,
+			variables,
+			temporaries_start: next_slot + num_locals,
+			num_temporaries: 0,
+			max_temporaries: 0,
+			frame_size,
+		};
+
+		compiler.compile_block(&function.body, next_slot)?;
+
+		// Implicit `return 0;`
+		compiler.emitter.emit(&[Word::Literal(21101), Word::Literal(0), Word::Literal(0), Word::Literal(1)]);
+		compiler.emitter.emit(&[Word::Literal(2105), Word::Literal(1), Word::Literal(0)]);
+
+		let frame_size = compiler.temporaries_start + compiler.max_temporaries;
+		compiler.emitter.bind_value(compiler.frame_size, frame_size);
+
+		Ok(())
+	}
+
+	fn compile_block(&mut self, block: &'a [Statement], mut next_local: i64) -> Result<(), crate::Error> {
+		// Variables declared in the block go out of scope at its end, and their slots are reused by the blocks after it
+		let variables = self.variables.clone();
+
+		for statement in block {
+			self.compile_statement(statement, &mut next_local)?;
+		}
+
+		self.variables = variables;
+
+		Ok(())
+	}
+
+	fn compile_statement(&mut self, statement: &'a Statement, next_local: &mut i64) -> Result<(), crate::Error> {
+		match statement {
+			Statement::Let(name, value) => {
+				// The variable is not in scope in its own initializer.
+				let temp = self.alloc_temporary();
+				self.compile_expr(value, temp)?;
+				self.free_temporary();
+
+				if self.variables.insert(name, *next_local).is_some() {
+					return Err(format!("variable {name:?} is declared more than once").into());
+				}
+				self.emit_copy(temp, *next_local);
+				*next_local += 1;
+			},
+
+			Statement::Assign(name, value) => {
+				let slot = self.variable(name)?;
+
+				// Evaluate into a temporary first, since the expression may itself read the variable.
+				let temp = self.alloc_temporary();
+				self.compile_expr(value, temp)?;
+				self.free_temporary();
+
+				self.emit_copy(temp, slot);
+			},
+
+			Statement::If(cond, then, r#else) => {
+				let else_label = self.emitter.new_label();
+				let end_label = self.emitter.new_label();
+
+				let temp = self.alloc_temporary();
+				self.compile_expr(cond, temp)?;
+				self.free_temporary();
+				self.emitter.emit(&[Word::Literal(1206), Word::Literal(temp), Word::label(else_label)]);
+
+				self.compile_block(then, *next_local)?;
+				self.emitter.emit(&[Word::Literal(1105), Word::Literal(1), Word::label(end_label)]);
+
+				self.emitter.bind(else_label);
+				self.compile_block(r#else, *next_local)?;
+
+				self.emitter.bind(end_label);
+			},
+
+			Statement::While(cond, body) => {
+				let start_label = self.emitter.new_label();
+				let end_label = self.emitter.new_label();
+
+				self.emitter.bind(start_label);
+
+				let temp = self.alloc_temporary();
+				self.compile_expr(cond, temp)?;
+				self.free_temporary();
+				self.emitter.emit(&[Word::Literal(1206), Word::Literal(temp), Word::label(end_label)]);
+
+				self.compile_block(body, *next_local)?;
+				self.emitter.emit(&[Word::Literal(1105), Word::Literal(1), Word::label(start_label)]);
+
+				self.emitter.bind(end_label);
+			},
+
+			Statement::Return(value) => {
+				match value {
+					Some(value) => {
+						let temp = self.alloc_temporary();
+						self.compile_expr(value, temp)?;
+						self.free_temporary();
+						self.emit_copy(temp, 1);
+					},
+
+					None => self.emitter.emit(&[Word::Literal(21101), Word::Literal(0), Word::Literal(0), Word::Literal(1)]),
+				}
+
+				self.emitter.emit(&[Word::Literal(2105), Word::Literal(1), Word::Literal(0)]);
+			},
+
+			Statement::Expr(value) => {
+				let temp = self.alloc_temporary();
+				self.compile_expr(value, temp)?;
+				self.free_temporary();
+			},
+		}
+
+		Ok(())
+	}
+
+	// Compiles the given expression such that its result is stored in the `dest` slot of the frame.
+	//
+	// `dest` must be a temporary.
+	fn compile_expr(&mut self, expr: &'a Expr, dest: i64) -> Result<(), crate::Error> {
+		match expr {
+			Expr::Number(value) =>
+				self.emitter.emit(&[Word::Literal(21101), Word::Literal(*value), Word::Literal(0), Word::Literal(dest)]),
+
+			Expr::Variable(name) => {
+				let slot = self.variable(name)?;
+				self.emit_copy(slot, dest);
+			},
+
+			Expr::Call(name, args) if name == "input" => {
+				if !args.is_empty() {
+					return Err(format!("function \"input\" takes 0 arguments but {} were given", args.len()).into());
+				}
+
+				self.emitter.emit(&[Word::Literal(203), Word::Literal(dest)]);
+			},
+
+			Expr::Call(name, args) if name == "output" => {
+				if args.len() != 1 {
+					return Err(format!("function \"output\" takes 1 argument but {} were given", args.len()).into());
+				}
+
+				self.compile_expr(&args[0], dest)?;
+				self.emitter.emit(&[Word::Literal(204), Word::Literal(dest)]);
+			},
+
+			Expr::Call(name, args) => {
+				let &(label, num_params) = self.signatures.get(&**name).ok_or_else(|| format!("function {name:?} is not defined"))?;
+				if args.len() != num_params {
+					return Err(format!("function {name:?} takes {num_params} arguments but {} were given", args.len()).into());
+				}
+
+				let mut temps = Vec::with_capacity(args.len());
+				for arg in args {
+					let temp = self.alloc_temporary();
+					self.compile_expr(arg, temp)?;
+					temps.push(temp);
+				}
+
+				let frame_size = self.frame_size;
+				let callee_slot = |slot| Word::Label { label: frame_size, scale: 1, offset: slot };
+
+				let mut callee_param_slot = 2;
+				for &temp in &temps {
+					self.emitter.emit(&[Word::Literal(22101), Word::Literal(0), Word::Literal(temp), callee_slot(callee_param_slot)]);
+					callee_param_slot += 1;
+				}
+
+				let return_label = self.emitter.new_label();
+				self.emitter.emit(&[Word::Literal(21101), Word::label(return_label), Word::Literal(0), callee_slot(0)]);
+				self.emitter.emit(&[Word::Literal(109), Word::label(self.frame_size)]);
+				self.emitter.emit(&[Word::Literal(1105), Word::Literal(1), Word::label(label)]);
+				self.emitter.bind(return_label);
+				self.emitter.emit(&[Word::Literal(109), Word::Label { label: self.frame_size, scale: -1, offset: 0 }]);
+				self.emitter.emit(&[Word::Literal(22101), Word::Literal(0), callee_slot(1), Word::Literal(dest)]);
+
+				for _ in temps {
+					self.free_temporary();
+				}
+			},
+
+			Expr::Unary(UnaryOp::Neg, value) => {
+				self.compile_expr(value, dest)?;
+				self.emitter.emit(&[Word::Literal(21202), Word::Literal(dest), Word::Literal(-1), Word::Literal(dest)]);
+			},
+
+			Expr::Unary(UnaryOp::Not, value) => {
+				self.compile_expr(value, dest)?;
+				self.emit_not(dest);
+			},
+
+			Expr::Binary(BinaryOp::And, left, right) => {
+				let end_label = self.emitter.new_label();
+
+				// If left is 0, then dest already holds the result.
+				self.compile_expr(left, dest)?;
+				self.emitter.emit(&[Word::Literal(1206), Word::Literal(dest), Word::label(end_label)]);
+
+				self.compile_expr(right, dest)?;
+				self.emit_not(dest);
+				self.emit_not(dest);
+
+				self.emitter.bind(end_label);
+			},
+
+			Expr::Binary(BinaryOp::Or, left, right) => {
+				let end_label = self.emitter.new_label();
+
+				self.compile_expr(left, dest)?;
+				self.emit_not(dest);
+				self.emit_not(dest);
+				self.emitter.emit(&[Word::Literal(1205), Word::Literal(dest), Word::label(end_label)]);
+
+				self.compile_expr(right, dest)?;
+				self.emit_not(dest);
+				self.emit_not(dest);
+
+				self.emitter.bind(end_label);
+			},
+
+			Expr::Binary(op, left, right) => {
+				self.compile_expr(left, dest)?;
+				let temp = self.alloc_temporary();
+				self.compile_expr(right, temp)?;
+				self.free_temporary();
+
+				let (left, right) = (Word::Literal(dest), Word::Literal(temp));
+				let dest_word = Word::Literal(dest);
+
+				match op {
+					BinaryOp::Add => self.emitter.emit(&[Word::Literal(22201), left, right, dest_word]),
+
+					BinaryOp::Sub => {
+						self.emitter.emit(&[Word::Literal(21202), right, Word::Literal(-1), right]);
+						self.emitter.emit(&[Word::Literal(22201), left, right, dest_word]);
+					},
+
+					BinaryOp::Mul => self.emitter.emit(&[Word::Literal(22202), left, right, dest_word]),
+
+					BinaryOp::Lt => self.emitter.emit(&[Word::Literal(22207), left, right, dest_word]),
+
+					BinaryOp::Gt => self.emitter.emit(&[Word::Literal(22207), right, left, dest_word]),
+
+					BinaryOp::Le => {
+						self.emitter.emit(&[Word::Literal(22207), right, left, dest_word]);
+						self.emit_not(dest);
+					},
+
+					BinaryOp::Ge => {
+						self.emitter.emit(&[Word::Literal(22207), left, right, dest_word]);
+						self.emit_not(dest);
+					},
+
+					BinaryOp::Eq => self.emitter.emit(&[Word::Literal(22208), left, right, dest_word]),
+
+					BinaryOp::Ne => {
+						self.emitter.emit(&[Word::Literal(22208), left, right, dest_word]);
+						self.emit_not(dest);
+					},
+
+					BinaryOp::And | BinaryOp::Or => unreachable!(),
+				}
+			},
+		}
+
+		Ok(())
+	}
+
+	fn variable(&self, name: &str) -> Result<i64, crate::Error> {
+		Ok(*self.variables.get(name).ok_or_else(|| format!("variable {name:?} is not defined"))?)
+	}
+
+	fn alloc_temporary(&mut self) -> i64 {
+		let slot = self.temporaries_start + self.num_temporaries;
+		self.num_temporaries += 1;
+		self.max_temporaries = std::cmp::max(self.max_temporaries, self.num_temporaries);
+		slot
+	}
+
+	fn free_temporary(&mut self) {
+		self.num_temporaries -= 1;
+	}
+
+	fn emit_copy(&mut self, from: i64, to: i64) {
+		self.emitter.emit(&[Word::Literal(22101), Word::Literal(0), Word::Literal(from), Word::Literal(to)]);
+	}
+
+	fn emit_not(&mut self, slot: i64) {
+		self.emitter.emit(&[Word::Literal(21208), Word::Literal(slot), Word::Literal(0), Word::Literal(slot)]);
+	}
+}
+
+// The number of slots needed for the variables of the block, including those of the blocks nested in it
+fn count_locals(block: &[Statement]) -> i64 {
+	let mut num_lets = 0;
+	let mut result = 0;
+
+	for statement in block {
+		let nested = match statement {
+			Statement::Let(_, _) => {
+				num_lets += 1;
+				0
+			},
+			Statement::If(_, then, r#else) => std::cmp::max(count_locals(then), count_locals(r#else)),
+			Statement::While(_, body) => count_locals(body),
+			Statement::Assign(_, _) | Statement::Return(_) | Statement::Expr(_) => 0,
+		};
+		result = std::cmp::max(result, num_lets + nested);
+	}
+
+	result
+}
+
+#[derive(Debug)]
+struct Function {
+	name: String,
+	params: Vec<String>,
+	body: Vec<Statement>,
+}
+
+#[derive(Debug)]
+enum Statement {
+	Let(String, Expr),
+	Assign(String, Expr),
+	If(Expr, Vec<Statement>, Vec<Statement>),
+	While(Expr, Vec<Statement>),
+	Return(Option<Expr>),
+	Expr(Expr),
+}
+
+#[derive(Debug)]
+enum Expr {
+	Number(i64),
+	Variable(String),
+	Call(String, Vec<Expr>),
+	Unary(UnaryOp, Box<Expr>),
+	Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum UnaryOp {
+	Neg,
+	Not,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum BinaryOp {
+	Or,
+	And,
+	Eq,
+	Ne,
+	Lt,
+	Le,
+	Gt,
+	Ge,
+	Add,
+	Sub,
+	Mul,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+	Ident(String),
+	Number(i64),
+	Punct(&'static str),
+	Eof,
+}
+
+impl std::fmt::Display for Token {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Token::Ident(name) => write!(f, "{name:?}"),
+			Token::Number(value) => write!(f, "{value}"),
+			Token::Punct(punct) => write!(f, "{punct:?}"),
+			Token::Eof => f.write_str("end of input"),
+		}
+	}
+}
+
+// Tokens, along with the line and column they start at.
+fn tokenize(source: &str) -> Result<Vec<(Token, usize, usize)>, crate::Error> {
+	const PUNCTS: &[&str] = &[
+		"||", "&&", "==", "!=", "<=", ">=",
+		"<", ">", "+", "-", "*", "!", "=", "(", ")", "{", "}", ",", ";",
+	];
+
+	let mut result = vec![];
+
+	for (line_num, line) in source.lines().enumerate() {
+		let line_num = line_num + 1;
+		let line = line.split("//").next().expect("str::split always has at least one part");
+
+		let mut rest = line;
+		loop {
+			rest = rest.trim_start();
+			if rest.is_empty() {
+				break;
+			}
+
+			let col = line.len() - rest.len() + 1;
+
+			if rest.starts_with(|c: char| c.is_ascii_digit()) {
+				let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+				let value = rest[..end].parse().map_err(|err| format!("line {line_num}, column {col}: malformed number: {err}"))?;
+				result.push((Token::Number(value), line_num, col));
+				rest = &rest[end..];
+			}
+			else if rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+				let end = rest.find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(rest.len());
+				result.push((Token::Ident(rest[..end].to_owned()), line_num, col));
+				rest = &rest[end..];
+			}
+			else if let Some(&punct) = PUNCTS.iter().find(|punct| rest.starts_with(*punct)) {
+				result.push((Token::Punct(punct), line_num, col));
+				rest = &rest[punct.len()..];
+			}
+			else {
+				let c = rest.chars().next().expect("rest is not empty");
+				return Err(format!("line {line_num}, column {col}: unexpected character {c:?}").into());
+			}
+		}
+	}
+
+	let line_num = source.lines().count() + 1;
+	result.push((Token::Eof, line_num, 1));
+
+	Ok(result)
+}
+
+struct Parser<'a> {
+	tokens: &'a [(Token, usize, usize)],
+	pos: usize,
+}
+
+impl Parser<'_> {
+	fn parse_program(&mut self) -> Result<Vec<Function>, crate::Error> {
+		let mut result = vec![];
+
+		while *self.peek() != Token::Eof {
+			self.expect_keyword("fn")?;
+			let name = self.expect_ident()?;
+
+			self.expect_punct("(")?;
+			let mut params = vec![];
+			if !self.eat_punct(")") {
+				loop {
+					params.push(self.expect_ident()?);
+					if self.eat_punct(")") {
+						break;
+					}
+					self.expect_punct(",")?;
+				}
+			}
+
+			let body = self.parse_block()?;
+
+			result.push(Function { name, params, body });
+		}
+
+		Ok(result)
+	}
+
+	fn parse_block(&mut self) -> Result<Vec<Statement>, crate::Error> {
+		self.expect_punct("{")?;
+
+		let mut result = vec![];
+		while !self.eat_punct("}") {
+			result.push(self.parse_statement()?);
+		}
+
+		Ok(result)
+	}
+
+	fn parse_statement(&mut self) -> Result<Statement, crate::Error> {
+		if self.eat_keyword("let") {
+			let name = self.expect_ident()?;
+			self.expect_punct("=")?;
+			let value = self.parse_expr()?;
+			self.expect_punct(";")?;
+			Ok(Statement::Let(name, value))
+		}
+		else if self.eat_keyword("if") {
+			let cond = self.parse_expr()?;
+			let then = self.parse_block()?;
+			let r#else =
+				if self.eat_keyword("else") {
+					if let Token::Ident(keyword) = self.peek() {
+						if keyword == "if" {
+							vec![self.parse_statement()?]
+						}
+						else {
+							self.parse_block()?
+						}
+					}
+					else {
+						self.parse_block()?
+					}
+				}
+				else {
+					vec![]
+				};
+			Ok(Statement::If(cond, then, r#else))
+		}
+		else if self.eat_keyword("while") {
+			let cond = self.parse_expr()?;
+			let body = self.parse_block()?;
+			Ok(Statement::While(cond, body))
+		}
+		else if self.eat_keyword("return") {
+			let value = if self.eat_punct(";") { None } else {
+				let value = self.parse_expr()?;
+				self.expect_punct(";")?;
+				Some(value)
+			};
+			Ok(Statement::Return(value))
+		}
+		else {
+			let value = self.parse_expr()?;
+			if self.eat_punct("=") {
+				let name = match value {
+					Expr::Variable(name) => name,
+					_ => return Err(self.error("left-hand side of assignment must be a variable")),
+				};
+				let value = self.parse_expr()?;
+				self.expect_punct(";")?;
+				Ok(Statement::Assign(name, value))
+			}
+			else {
+				self.expect_punct(";")?;
+				Ok(Statement::Expr(value))
+			}
+		}
+	}
+
+	fn parse_expr(&mut self) -> Result<Expr, crate::Error> {
+		self.parse_binary(0)
+	}
+
+	fn parse_binary(&mut self, level: usize) -> Result<Expr, crate::Error> {
+		const LEVELS: &[&[(&str, BinaryOp)]] = &[
+			&[("||", BinaryOp::Or)],
+			&[("&&", BinaryOp::And)],
+			&[("==", BinaryOp::Eq), ("!=", BinaryOp::Ne), ("<=", BinaryOp::Le), (">=", BinaryOp::Ge), ("<", BinaryOp::Lt), (">", BinaryOp::Gt)],
+			&[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+			&[("*", BinaryOp::Mul)],
+		];
+
+		let ops = match LEVELS.get(level) {
+			Some(ops) => ops,
+			None => return self.parse_unary(),
+		};
+
+		let mut result = self.parse_binary(level + 1)?;
+
+		'outer: loop {
+			for &(punct, op) in *ops {
+				if self.eat_punct(punct) {
+					let right = self.parse_binary(level + 1)?;
+					result = Expr::Binary(op, Box::new(result), Box::new(right));
+					continue 'outer;
+				}
+			}
+
+			break;
+		}
+
+		Ok(result)
+	}
+
+	fn parse_unary(&mut self) -> Result<Expr, crate::Error> {
+		if self.eat_punct("-") {
+			Ok(Expr::Unary(UnaryOp::Neg, Box::new(self.parse_unary()?)))
+		}
+		else if self.eat_punct("!") {
+			Ok(Expr::Unary(UnaryOp::Not, Box::new(self.parse_unary()?)))
+		}
+		else {
+			self.parse_primary()
+		}
+	}
+
+	fn parse_primary(&mut self) -> Result<Expr, crate::Error> {
+		match self.peek().clone() {
+			Token::Number(value) => {
+				self.pos += 1;
+				Ok(Expr::Number(value))
+			},
+
+			Token::Ident(_) => {
+				let name = self.expect_ident()?;
+				if self.eat_punct("(") {
+					let mut args = vec![];
+					if !self.eat_punct(")") {
+						loop {
+							args.push(self.parse_expr()?);
+							if self.eat_punct(")") {
+								break;
+							}
+							self.expect_punct(",")?;
+						}
+					}
+					Ok(Expr::Call(name, args))
+				}
+				else {
+					Ok(Expr::Variable(name))
+				}
+			},
+
+			Token::Punct("(") => {
+				self.pos += 1;
+				let result = self.parse_expr()?;
+				self.expect_punct(")")?;
+				Ok(result)
+			},
+
+			token => Err(self.error(&format!("expected expression but found {token}"))),
+		}
+	}
+
+	fn peek(&self) -> &Token {
+		&self.tokens[self.pos].0
+	}
+
+	fn eat_punct(&mut self, punct: &str) -> bool {
+		match self.peek() {
+			Token::Punct(actual) if *actual == punct => {
+				self.pos += 1;
+				true
+			},
+			_ => false,
+		}
+	}
+
+	fn expect_punct(&mut self, punct: &str) -> Result<(), crate::Error> {
+		if self.eat_punct(punct) {
+			Ok(())
+		}
+		else {
+			Err(self.error(&format!("expected {punct:?} but found {}", self.peek())))
+		}
+	}
+
+	fn eat_keyword(&mut self, keyword: &str) -> bool {
+		match self.peek() {
+			Token::Ident(actual) if actual == keyword => {
+				self.pos += 1;
+				true
+			},
+			_ => false,
+		}
+	}
+
+	fn expect_keyword(&mut self, keyword: &str) -> Result<(), crate::Error> {
+		if self.eat_keyword(keyword) {
+			Ok(())
+		}
+		else {
+			Err(self.error(&format!("expected {keyword:?} but found {}", self.peek())))
+		}
+	}
+
+	fn expect_ident(&mut self) -> Result<String, crate::Error> {
+		match self.peek() {
+			Token::Ident(name) if !matches!(&**name, "fn" | "let" | "if" | "else" | "while" | "return") => {
+				let name = name.clone();
+				self.pos += 1;
+				Ok(name)
+			},
+			token => Err(self.error(&format!("expected identifier but found {token}"))),
+		}
+	}
+
+	fn error(&self, message: &str) -> crate::Error {
+		let (_, line, col) = &self.tokens[self.pos];
+		format!("line {line}, column {col}: {message}").into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_compile() {
+		fn test(source: &str, input: &[i64], expected_output: &[i64]) {
+			let ram = super::compile(source).unwrap();
+			let mut computer = crate::intcode::Computer::new(ram);
+			let actual_output = computer.execute(input.iter().copied()).unwrap();
+			assert_eq!(expected_output, &*actual_output);
+		}
+
+		test(
+			"\
+				fn main() {
+					let a = input();
+					let b = input();
+					output(a + b * 2 - 1);
+					output(-(a - b));
+					output(a - b - 1);
+				}
+			",
+			&[3, 4],
+			&[10, 1, -2],
+		);
+
+		test(
+			"\
+				fn main() {
+					let a = input();
+					let b = input();
+					output(a < b);
+					output(a <= b);
+					output(a > b);
+					output(a >= b);
+					output(a == b);
+					output(a != b);
+					output(!a);
+					output(a && b);
+					output(a || b);
+					output(0 && b);
+					output(0 || 0);
+				}
+			",
+			&[3, 4],
+			&[1, 1, 0, 0, 0, 1, 0, 1, 1, 0, 0],
+		);
+
+		// Short-circuiting must not consume input
+		test(
+			"\
+				fn main() {
+					output(0 && input());
+					output(1 || input());
+					output(input());
+				}
+			",
+			&[7],
+			&[0, 1, 7],
+		);
+
+		test(
+			"\
+				fn main() {
+					let n = input();
+					let sum = 0;
+					while n > 0 {
+						if n == 2 {
+							sum = sum + 100;
+						}
+						else if n == 3 {
+							sum = sum + 1000;
+						}
+						else {
+							sum = sum + n;
+						}
+						n = n - 1;
+					}
+					output(sum);
+				}
+			",
+			&[5],
+			&[1110],
+		);
+
+		test(
+			"\
+				fn main() {
+					let n = input();
+					while n > 0 {
+						output(fact(n));
+						n = n - 1;
+					}
+				}
+
+				fn fact(n) {
+					if n <= 1 {
+						return 1;
+					}
+					return n * fact(n - 1);
+				}
+			",
+			&[5],
+			&[120, 24, 6, 2, 1],
+		);
+
+		test(
+			"\
+				fn main() {
+					output(fib(10));
+					output(sub(10, 3));
+					output(nothing());
+				}
+
+				fn fib(n) {
+					if n < 2 {
+						return n;
+					}
+					return fib(n - 1) + fib(n - 2);
+				}
+
+				// Arguments must be passed in order
+				fn sub(a, b) {
+					return a - b;
+				}
+
+				fn nothing() {
+				}
+			",
+			&[],
+			&[55, 7, 0],
+		);
+
+		// Variables are scoped to their blocks, and blocks that don't overlap can declare the same names
+		test(
+			"\
+				fn main() {
+					let n = input();
+					if n {
+						let x = 1;
+						output(x + n);
+					}
+					else {
+						let x = 2;
+						output(x + n);
+					}
+					while n < 3 {
+						let x = n * 10;
+						n = n + 1;
+						output(x);
+					}
+					let x = 7;
+					output(x);
+				}
+			",
+			&[0],
+			&[2, 0, 10, 20, 7],
+		);
+	}
+
+	#[test]
+	fn test_compile_error() {
+		fn test(source: &str, expected_error: &str) {
+			let err = match super::compile(source) {
+				Ok(_) => panic!("expected {source:?} to fail to compile"),
+				Err(err) => err,
+			};
+			assert_eq!(expected_error, err.0.to_string());
+		}
+
+		test("fn foo() {}", "function \"main\" is not defined");
+		test("fn main(a) {}", "function \"main\" must not have parameters");
+		test("fn main() { output(x); }", "variable \"x\" is not defined");
+		test("fn main() { let x = x; }", "variable \"x\" is not defined");
+		test("fn main() { let x = 1; let x = 2; }", "variable \"x\" is declared more than once");
+		test("fn main() { let x = 1; if x { let x = 2; } }", "variable \"x\" is declared more than once");
+		test("fn main() { if 0 { let x = 1; } output(x); }", "variable \"x\" is not defined");
+		test("fn main() { while 0 { let x = 1; } output(x); }", "variable \"x\" is not defined");
+		test("fn main() { foo(); }", "function \"foo\" is not defined");
+		test("fn main() { main(1); }", "function \"main\" takes 0 arguments but 1 were given");
+		test("fn main() {\n\tlet x = 1 +;\n}", "line 2, column 13: expected expression but found \";\"");
+		test("fn main() { let x = 1 / 2; }", "line 1, column 23: unexpected character '/'");
+	}
+}
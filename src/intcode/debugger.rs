@@ -0,0 +1,467 @@
+//! Runs a computer one instruction at a time, and stops when a breakpoint's condition holds.
+//!
+//! Conditions are expressions parsed from text, like `pc == 1234 && [rb+2] > 10` or `output == 10`. They're evaluated after every instruction.
+//!
+//! ```text
+//! expr := or
+//! or := and ("||" and)*
+//! and := cmp ("&&" cmp)*
+//! cmp := sum (("==" | "!=" | "<" | "<=" | ">" | ">=") sum)?
+//! sum := product (("+" | "-") product)*
+//! product := unary ("*" unary)*
+//! unary := ("-" | "!") unary | atom
+//! atom := number | "pc" | "rb" | "output" | "[" expr "]" | "(" expr ")"
+//! ```
+//!
+//! `pc` and `rb` are the pc and relative base after the instruction, `[a]` is the value at address `a`,
+//! and `output` is the value that the instruction output.
+//!
+//! An expression can be undefined, eg `output` after an instruction that didn't output anything, or `[a]` for a negative `a`.
+//! Comparisons and logical operators treat undefined operands as false, so such a breakpoint just doesn't hit.
+
+/// A computer with breakpoints
+pub struct Debugger {
+	/// The computer being debugged
+	pub computer: super::Computer,
+	breakpoints: Vec<Breakpoint>,
+}
+
+/// A breakpoint and its condition
+pub struct Breakpoint {
+	/// The condition's source text
+	pub source: String,
+
+	/// The number of times the breakpoint has hit
+	pub hits: usize,
+	condition: Expr,
+}
+
+/// The reason `Debugger::step` returned
+#[derive(Debug, PartialEq)]
+pub enum Event {
+	/// The program output a value
+	Output(i64),
+
+	/// Breakpoints hit
+	Break {
+		/// Indices of the breakpoints that hit
+		breakpoints: Vec<usize>,
+
+		/// The output of the instruction that triggered them, if any
+		output: Option<i64>,
+
+		/// Whether the instruction that triggered them halted the program. The program must not be stepped again if it did.
+		halted: bool,
+	},
+
+	/// The program halted
+	Halt,
+}
+
+impl Debugger {
+	/// Creates a debugger with no breakpoints for the given computer
+	pub fn new(computer: super::Computer) -> Self {
+		Debugger {
+			computer,
+			breakpoints: vec![],
+		}
+	}
+
+	/// Returns the index of the new breakpoint
+	pub fn add_breakpoint(&mut self, source: &str) -> Result<usize, crate::Error> {
+		let condition = parse(source)?;
+		self.breakpoints.push(Breakpoint {
+			source: source.trim().to_owned(),
+			hits: 0,
+			condition,
+		});
+		Ok(self.breakpoints.len() - 1)
+	}
+
+	/// All breakpoints, in the order they were added
+	pub fn breakpoints(&self) -> &[Breakpoint] {
+		&self.breakpoints
+	}
+
+	/// Like `Computer::step`, except it also returns when a breakpoint hits
+	pub fn step(&mut self, input: impl IntoIterator<Item = i64>) -> Result<Event, crate::Error> {
+		let mut input = input.into_iter();
+
+		loop {
+			let (output, halted) = match self.computer.step_instruction(&mut input)? {
+				super::Step::Continue => (None, false),
+				super::Step::Output(output) => (Some(output), false),
+				super::Step::Halt => (None, true),
+			};
+
+			let mut hit_breakpoints = vec![];
+			for (i, breakpoint) in self.breakpoints.iter_mut().enumerate() {
+				if is_true(breakpoint.condition.evaluate(&self.computer, output)) {
+					breakpoint.hits += 1;
+					hit_breakpoints.push(i);
+				}
+			}
+
+			if !hit_breakpoints.is_empty() {
+				return Ok(Event::Break { breakpoints: hit_breakpoints, output, halted });
+			}
+
+			if let Some(output) = output {
+				return Ok(Event::Output(output));
+			}
+
+			if halted {
+				return Ok(Event::Halt);
+			}
+		}
+	}
+}
+
+#[derive(Debug)]
+enum Expr {
+	Number(i64),
+	Pc,
+	RelativeBase,
+	Output,
+	Memory(Box<Expr>),
+	Neg(Box<Expr>),
+	Not(Box<Expr>),
+	BinOp(Box<Expr>, BinOp, Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum BinOp {
+	Or,
+	And,
+	Equals,
+	NotEquals,
+	LessThan,
+	LessThanOrEquals,
+	GreaterThan,
+	GreaterThanOrEquals,
+	Add,
+	Sub,
+	Mul,
+}
+
+impl Expr {
+	fn evaluate(&self, computer: &super::Computer, output: Option<i64>) -> Option<i64> {
+		match self {
+			Expr::Number(value) => Some(*value),
+			Expr::Pc => computer.pc().try_into().ok(),
+			Expr::RelativeBase => computer.relative_base().try_into().ok(),
+			Expr::Output => output,
+
+			Expr::Memory(address) => {
+				let address: usize = address.evaluate(computer, output)?.try_into().ok()?;
+				Some(computer.ram.0.get(address).copied().unwrap_or(0))
+			},
+
+			Expr::Neg(expr) => expr.evaluate(computer, output)?.checked_neg(),
+			Expr::Not(expr) => Some((!is_true(expr.evaluate(computer, output))).into()),
+
+			Expr::BinOp(left, BinOp::Or, right) =>
+				Some((is_true(left.evaluate(computer, output)) || is_true(right.evaluate(computer, output))).into()),
+
+			Expr::BinOp(left, BinOp::And, right) =>
+				Some((is_true(left.evaluate(computer, output)) && is_true(right.evaluate(computer, output))).into()),
+
+			Expr::BinOp(left, op, right) => {
+				let left = left.evaluate(computer, output);
+				let right = right.evaluate(computer, output);
+				let (left, right) = match (left, right) {
+					(Some(left), Some(right)) => (left, right),
+
+					// Comparisons with undefined operands are false rather than undefined, so that `!(output == 10)` hits
+					// after instructions that don't output anything
+					_ => return match op {
+						BinOp::Add | BinOp::Sub | BinOp::Mul => None,
+						_ => Some(0),
+					},
+				};
+
+				match op {
+					BinOp::Or | BinOp::And => unreachable!(),
+					BinOp::Equals => Some((left == right).into()),
+					BinOp::NotEquals => Some((left != right).into()),
+					BinOp::LessThan => Some((left < right).into()),
+					BinOp::LessThanOrEquals => Some((left <= right).into()),
+					BinOp::GreaterThan => Some((left > right).into()),
+					BinOp::GreaterThanOrEquals => Some((left >= right).into()),
+					BinOp::Add => left.checked_add(right),
+					BinOp::Sub => left.checked_sub(right),
+					BinOp::Mul => left.checked_mul(right),
+				}
+			},
+		}
+	}
+}
+
+fn is_true(value: Option<i64>) -> bool {
+	matches!(value, Some(value) if value != 0)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token<'a> {
+	Number(i64),
+	Ident(&'a str),
+	Symbol(&'static str),
+}
+
+impl std::fmt::Display for Token<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Token::Number(value) => write!(f, "{value}"),
+			Token::Ident(ident) => write!(f, "{ident}"),
+			Token::Symbol(symbol) => write!(f, "{symbol:?}"),
+		}
+	}
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token<'_>)>, crate::Error> {
+	const SYMBOLS: &[&str] = &["||", "&&", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "!", "[", "]", "(", ")"];
+
+	let mut result = vec![];
+
+	let mut chars = source.char_indices().peekable();
+	while let Some(&(start, c)) = chars.peek() {
+		if c.is_whitespace() {
+			chars.next();
+		}
+		else if c.is_ascii_digit() {
+			let mut end = start;
+			while let Some(&(i, c)) = chars.peek() {
+				if !c.is_ascii_digit() {
+					break;
+				}
+				end = i + 1;
+				chars.next();
+			}
+			let value = source[start..end].parse().map_err(|err| format!("column {}: {err}", start + 1))?;
+			result.push((start, Token::Number(value)));
+		}
+		else if c.is_ascii_alphabetic() {
+			let mut end = start;
+			while let Some(&(i, c)) = chars.peek() {
+				if !c.is_ascii_alphanumeric() {
+					break;
+				}
+				end = i + 1;
+				chars.next();
+			}
+			result.push((start, Token::Ident(&source[start..end])));
+		}
+		else if let Some(symbol) = SYMBOLS.iter().find(|symbol| source[start..].starts_with(**symbol)) {
+			result.push((start, Token::Symbol(symbol)));
+			for _ in 0..symbol.len() {
+				chars.next();
+			}
+		}
+		else {
+			return Err(format!("column {}: unexpected {c:?}", start + 1).into());
+		}
+	}
+
+	Ok(result)
+}
+
+fn parse(source: &str) -> Result<Expr, crate::Error> {
+	let tokens = tokenize(source)?;
+
+	let mut parser = Parser { source, tokens: &tokens, pos: 0 };
+	let expr = parser.or()?;
+	match parser.tokens.get(parser.pos) {
+		None => Ok(expr),
+		Some(&(column, token)) => Err(format!("column {}: unexpected {token}", column + 1).into()),
+	}
+}
+
+struct Parser<'a> {
+	source: &'a str,
+	tokens: &'a [(usize, Token<'a>)],
+	pos: usize,
+}
+
+impl Parser<'_> {
+	fn or(&mut self) -> Result<Expr, crate::Error> {
+		let mut left = self.and()?;
+		while self.eat("||") {
+			let right = self.and()?;
+			left = Expr::BinOp(Box::new(left), BinOp::Or, Box::new(right));
+		}
+		Ok(left)
+	}
+
+	fn and(&mut self) -> Result<Expr, crate::Error> {
+		let mut left = self.cmp()?;
+		while self.eat("&&") {
+			let right = self.cmp()?;
+			left = Expr::BinOp(Box::new(left), BinOp::And, Box::new(right));
+		}
+		Ok(left)
+	}
+
+	fn cmp(&mut self) -> Result<Expr, crate::Error> {
+		let left = self.sum()?;
+
+		let op =
+			if self.eat("==") { BinOp::Equals }
+			else if self.eat("!=") { BinOp::NotEquals }
+			else if self.eat("<=") { BinOp::LessThanOrEquals }
+			else if self.eat(">=") { BinOp::GreaterThanOrEquals }
+			else if self.eat("<") { BinOp::LessThan }
+			else if self.eat(">") { BinOp::GreaterThan }
+			else { return Ok(left); };
+
+		let right = self.sum()?;
+		Ok(Expr::BinOp(Box::new(left), op, Box::new(right)))
+	}
+
+	fn sum(&mut self) -> Result<Expr, crate::Error> {
+		let mut left = self.product()?;
+		loop {
+			let op =
+				if self.eat("+") { BinOp::Add }
+				else if self.eat("-") { BinOp::Sub }
+				else { return Ok(left); };
+			let right = self.product()?;
+			left = Expr::BinOp(Box::new(left), op, Box::new(right));
+		}
+	}
+
+	fn product(&mut self) -> Result<Expr, crate::Error> {
+		let mut left = self.unary()?;
+		while self.eat("*") {
+			let right = self.unary()?;
+			left = Expr::BinOp(Box::new(left), BinOp::Mul, Box::new(right));
+		}
+		Ok(left)
+	}
+
+	fn unary(&mut self) -> Result<Expr, crate::Error> {
+		if self.eat("-") {
+			Ok(Expr::Neg(Box::new(self.unary()?)))
+		}
+		else if self.eat("!") {
+			Ok(Expr::Not(Box::new(self.unary()?)))
+		}
+		else {
+			self.atom()
+		}
+	}
+
+	fn atom(&mut self) -> Result<Expr, crate::Error> {
+		let (column, token) = match self.tokens.get(self.pos) {
+			Some(&token) => token,
+			None => return Err(format!("column {}: unexpected end of expression", self.source.len() + 1).into()),
+		};
+		self.pos += 1;
+
+		match token {
+			Token::Number(value) => Ok(Expr::Number(value)),
+			Token::Ident("pc") => Ok(Expr::Pc),
+			Token::Ident("rb") => Ok(Expr::RelativeBase),
+			Token::Ident("output") => Ok(Expr::Output),
+			Token::Ident(ident) => Err(format!("column {}: unknown variable {ident:?}", column + 1).into()),
+
+			Token::Symbol("[") => {
+				let expr = self.or()?;
+				self.expect("]")?;
+				Ok(Expr::Memory(Box::new(expr)))
+			},
+
+			Token::Symbol("(") => {
+				let expr = self.or()?;
+				self.expect(")")?;
+				Ok(expr)
+			},
+
+			Token::Symbol(_) => Err(format!("column {}: unexpected {token}", column + 1).into()),
+		}
+	}
+
+	fn eat(&mut self, symbol: &str) -> bool {
+		match self.tokens.get(self.pos) {
+			Some((_, Token::Symbol(s))) if *s == symbol => {
+				self.pos += 1;
+				true
+			},
+			_ => false,
+		}
+	}
+
+	fn expect(&mut self, symbol: &str) -> Result<(), crate::Error> {
+		if self.eat(symbol) {
+			Ok(())
+		}
+		else {
+			let column = self.tokens.get(self.pos).map_or(self.source.len(), |&(column, _)| column);
+			Err(format!("column {}: expected {symbol:?}", column + 1).into())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_breakpoints() {
+		// Counts down from the input to 1, outputting each number, with the counter at [rb+2]
+		let program = "3,100,109,98,204,2,21101,-1,0,0,22201,0,2,2,1005,100,4,99";
+		let ram: crate::intcode::Ram = program.parse().unwrap();
+
+		let mut debugger = super::Debugger::new(crate::intcode::Computer::new(ram));
+		assert_eq!(debugger.add_breakpoint("pc == 14 && [rb+2] < 3").unwrap(), 0);
+		assert_eq!(debugger.add_breakpoint("output == 4").unwrap(), 1);
+
+		let mut events = vec![];
+		let mut input = Some(5);
+		loop {
+			let event = debugger.step(input.take()).unwrap();
+			if event == super::Event::Halt {
+				break;
+			}
+			events.push(event);
+		}
+
+		assert_eq!(events, [
+			super::Event::Output(5),
+			super::Event::Break { breakpoints: vec![1], output: Some(4), halted: false },
+			super::Event::Output(3),
+			super::Event::Break { breakpoints: vec![0], output: None, halted: false },
+			super::Event::Output(2),
+			super::Event::Break { breakpoints: vec![0], output: None, halted: false },
+			super::Event::Output(1),
+			super::Event::Break { breakpoints: vec![0], output: None, halted: false },
+		]);
+
+		let hits: Vec<_> = debugger.breakpoints().iter().map(|breakpoint| (&*breakpoint.source, breakpoint.hits)).collect();
+		assert_eq!(hits, [("pc == 14 && [rb+2] < 3", 3), ("output == 4", 1)]);
+	}
+
+	#[test]
+	fn test_break_on_halt() {
+		let ram: crate::intcode::Ram = "1101,1,1,5,99,0".parse().unwrap();
+
+		let mut debugger = super::Debugger::new(crate::intcode::Computer::new(ram));
+		debugger.add_breakpoint("pc >= 5").unwrap();
+
+		assert_eq!(debugger.step(None).unwrap(), super::Event::Break { breakpoints: vec![0], output: None, halted: true });
+		assert_eq!(debugger.computer.pc(), 5);
+	}
+
+	#[test]
+	fn test_parse_error() {
+		fn test(source: &str, expected_err: &str) {
+			let mut debugger = super::Debugger::new(crate::intcode::Computer::new("99".parse().unwrap()));
+			let actual_err = debugger.add_breakpoint(source).unwrap_err();
+			assert_eq!(expected_err, actual_err.0.to_string());
+		}
+
+		test("pc == ", "column 7: unexpected end of expression");
+		test("pc = 5", "column 4: unexpected '='");
+		test("[rb + 2 > 10", r#"column 13: expected "]""#);
+		test("foo > 1", r#"column 1: unknown variable "foo""#);
+		test("pc == 1 2", "column 9: unexpected 2");
+		test("[rb]]", r#"column 5: unexpected "]""#);
+	}
+}
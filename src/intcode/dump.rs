@@ -0,0 +1,266 @@
+//! Dumps and diffs of a computer's state, for working out which memory cells a program uses for what.
+//!
+//! Both can be rendered as text for reading, or as JSON for further processing.
+
+/// The radix that values are rendered in
+#[derive(Clone, Copy, Debug)]
+pub enum Radix {
+	/// Base 10
+	Decimal,
+
+	/// Base 16, with negative values rendered as a minus sign followed by the magnitude
+	Hexadecimal,
+}
+
+/// How a [`Diff`] is rendered
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+	/// As text, with [`Diff::to_text`]
+	Text,
+
+	/// As JSON, with [`Diff::to_json`]
+	Json,
+}
+
+impl std::str::FromStr for Format {
+	type Err = crate::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"text" => Format::Text,
+			"json" => Format::Json,
+			s => return Err(format!("invalid diff format {s:?}, expected text or json").into()),
+		})
+	}
+}
+
+const WORDS_PER_ROW: usize = 8;
+
+/// Renders the computer's RAM as a table of `WORDS_PER_ROW` words per row, with each row's words as ASCII in a gutter on the right.
+pub fn dump(computer: &super::Computer, radix: Radix) -> String {
+	let ram = &computer.ram.0;
+
+	let address_width = format!("{}", ram.len().saturating_sub(1)).len();
+	let value_width = ram.iter().map(|&value| format_value(value, radix).len()).max().unwrap_or(0);
+
+	let mut result = format!("pc = {}, relative base = {}\n", computer.pc(), computer.relative_base());
+
+	for (row_number, row) in ram.chunks(WORDS_PER_ROW).enumerate() {
+		let address = row_number * WORDS_PER_ROW;
+		result.push_str(&format!("{address:>address_width$}:"));
+
+		for &value in row {
+			result.push_str(&format!(" {:>value_width$}", format_value(value, radix)));
+		}
+
+		// Pad short rows so that the gutters line up
+		for _ in row.len()..WORDS_PER_ROW {
+			result.push_str(&format!(" {:>value_width$}", ""));
+		}
+
+		result.push_str("  |");
+		result.extend(row.iter().map(|&value| to_ascii(value)));
+		result.push_str("|\n");
+	}
+
+	result
+}
+
+/// Renders the computer's pc, relative base and RAM as a JSON object.
+pub fn dump_json(computer: &super::Computer) -> String {
+	#[derive(serde::Serialize)]
+	struct Dump<'a> {
+		pc: usize,
+		relative_base: isize,
+		ram: &'a [i64],
+	}
+
+	serde_json::to_string(&Dump {
+		pc: computer.pc(),
+		relative_base: computer.relative_base(),
+		ram: &computer.ram.0,
+	}).expect("dump is serializable")
+}
+
+/// The differences between two states of a computer
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Diff {
+	/// The change in pc, if it changed
+	pub pc: Option<Change<usize>>,
+
+	/// The change in relative base, if it changed
+	pub relative_base: Option<Change<isize>>,
+
+	/// The memory cells that changed, in order of address
+	pub cells: Vec<CellChange>,
+}
+
+/// A value before and after
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Change<T> {
+	/// The old value
+	pub before: T,
+
+	/// The new value
+	pub after: T,
+}
+
+/// A memory cell whose value changed
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct CellChange {
+	/// The address of the cell
+	pub address: usize,
+
+	/// The old value
+	pub before: i64,
+
+	/// The new value
+	pub after: i64,
+}
+
+/// Compares two states of a computer.
+///
+/// RAM grows on demand and its new cells are zero, so cells beyond the end of one computer's RAM are considered to be zero.
+pub fn diff(before: &super::Computer, after: &super::Computer) -> Diff {
+	fn change<T>(before: T, after: T) -> Option<Change<T>> where T: PartialEq {
+		if before == after {
+			None
+		}
+		else {
+			Some(Change { before, after })
+		}
+	}
+
+	let len = std::cmp::max(before.ram.0.len(), after.ram.0.len());
+	let cells =
+		(0..len)
+		.filter_map(|address| {
+			let before = before.ram.0.get(address).copied().unwrap_or(0);
+			let after = after.ram.0.get(address).copied().unwrap_or(0);
+			change(before, after).map(|Change { before, after }| CellChange { address, before, after })
+		})
+		.collect();
+
+	Diff {
+		pc: change(before.pc(), after.pc()),
+		relative_base: change(before.relative_base(), after.relative_base()),
+		cells,
+	}
+}
+
+impl Diff {
+	/// Whether nothing changed
+	pub fn is_empty(&self) -> bool {
+		self.pc.is_none() && self.relative_base.is_none() && self.cells.is_empty()
+	}
+
+	/// Renders the diff with one change per line, with memory cells also rendered as ASCII.
+	pub fn to_text(&self, radix: Radix) -> String {
+		let mut result = String::new();
+
+		if let Some(Change { before, after }) = &self.pc {
+			result.push_str(&format!("pc: {before} -> {after}\n"));
+		}
+
+		if let Some(Change { before, after }) = &self.relative_base {
+			result.push_str(&format!("relative base: {before} -> {after}\n"));
+		}
+
+		for &CellChange { address, before, after } in &self.cells {
+			result.push_str(&format!(
+				"[{address}]: {} -> {} ({:?} -> {:?})\n",
+				format_value(before, radix), format_value(after, radix),
+				to_ascii(before), to_ascii(after),
+			));
+		}
+
+		result
+	}
+
+	/// Renders the diff as a JSON object.
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).expect("diff is serializable")
+	}
+}
+
+fn format_value(value: i64, radix: Radix) -> String {
+	match radix {
+		Radix::Decimal => value.to_string(),
+		Radix::Hexadecimal if value < 0 => format!("-{:#x}", value.unsigned_abs()),
+		Radix::Hexadecimal => format!("{value:#x}"),
+	}
+}
+
+fn to_ascii(value: i64) -> char {
+	match u8::try_from(value) {
+		Ok(b) if b.is_ascii_graphic() || b == b' ' => b.into(),
+		_ => '.',
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_dump() {
+		let mut computer = crate::intcode::Computer::new("109,3,104,72,104,-1,99,0,0,0".parse().unwrap());
+		computer.execute(std::iter::empty()).unwrap();
+
+		assert_eq!(
+			super::dump(&computer, super::Radix::Decimal),
+			"\
+				pc = 7, relative base = 3\n\
+				0: 109   3 104  72 104  -1  99   0  |m.hHh.c.|\n\
+				8:   0   0                          |..|\n\
+			",
+		);
+
+		assert_eq!(
+			super::dump(&computer, super::Radix::Hexadecimal),
+			"\
+				pc = 7, relative base = 3\n\
+				0: 0x6d  0x3 0x68 0x48 0x68 -0x1 0x63  0x0  |m.hHh.c.|\n\
+				8:  0x0  0x0                                |..|\n\
+			",
+		);
+
+		assert_eq!(
+			super::dump_json(&computer),
+			r#"{"pc":7,"relative_base":3,"ram":[109,3,104,72,104,-1,99,0,0,0]}"#,
+		);
+	}
+
+	#[test]
+	fn test_diff() {
+		let before = crate::intcode::Computer::new("3,9,109,5,1001,9,1,10,99,0".parse().unwrap());
+
+		let mut after = before.clone();
+		after.execute(std::iter::once(65)).unwrap();
+
+		let diff = super::diff(&before, &after);
+		assert_eq!(diff, super::Diff {
+			pc: Some(super::Change { before: 0, after: 9 }),
+			relative_base: Some(super::Change { before: 0, after: 5 }),
+			cells: vec![
+				super::CellChange { address: 9, before: 0, after: 65 },
+				super::CellChange { address: 10, before: 0, after: 66 },
+			],
+		});
+
+		assert_eq!(
+			diff.to_text(super::Radix::Decimal),
+			"\
+				pc: 0 -> 9\n\
+				relative base: 0 -> 5\n\
+				[9]: 0 -> 65 ('.' -> 'A')\n\
+				[10]: 0 -> 66 ('.' -> 'B')\n\
+			",
+		);
+
+		assert_eq!(
+			diff.to_json(),
+			r#"{"pc":{"before":0,"after":9},"relative_base":{"before":0,"after":5},"cells":[{"address":9,"before":0,"after":65},{"address":10,"before":0,"after":66}]}"#,
+		);
+
+		assert!(super::diff(&after, &after.clone()).is_empty());
+	}
+}
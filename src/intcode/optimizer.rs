@@ -0,0 +1,438 @@
+//! A peephole optimizer for Intcode programs.
+//!
+//! The program is disassembled by following control flow from address 0, so that data is never mistaken for code.
+//! Then each instruction is simplified on its own:
+//!
+//! - `Add`, `Mul`, `LessThan` and `Equals` of two immediates are folded into `out <- constant + 0`
+//! - `x * 1`, `1 * x` and `0 + x` are normalized to `x + 0`, and `x * 0` to `0 + 0`
+//! - `x <- x + 0` is a no-op
+//! - Jumps that are never taken, or that jump to the next instruction, are no-ops
+//!
+//! Removing no-ops shifts everything after them, and folding changes the values of the cells of the folded instructions,
+//! so the optimized program only behaves the same as the original if:
+//!
+//! - every jump has an immediate target, so all code can be found and all jump targets are known
+//! - the relative base is never used, since it's impossible to know which addresses it's used to read or write
+//! - no instruction reads or writes code as data, which includes self-modifying programs
+//!
+//! Programs that don't meet these conditions are not optimized at all.
+
+/// The result of optimizing a program
+pub struct Optimized {
+	/// The optimized program
+	pub ram: super::Ram,
+
+	/// The number of instructions that were rewritten in place
+	pub instructions_folded: usize,
+
+	/// The number of no-op instructions that were removed
+	pub instructions_eliminated: usize,
+
+	/// The number of memory cells that the program shrank by
+	pub words_eliminated: usize,
+}
+
+/// Optimizes the given program. The program is returned unchanged if it can't be optimized without changing what it does.
+pub fn optimize(ram: &super::Ram) -> Optimized {
+	let unchanged = || Optimized {
+		ram: ram.clone(),
+		instructions_folded: 0,
+		instructions_eliminated: 0,
+		words_eliminated: 0,
+	};
+
+	let code = match disassemble(ram) {
+		Some(code) if !code.has_indirect_jumps && !code.uses_relative_base => code,
+		_ => return unchanged(),
+	};
+
+	let mut is_code = vec![false; ram.0.len()];
+	for (&start, &(_, len)) in &code.instructions {
+		for is_code in &mut is_code[start..(start + len)] {
+			*is_code = true;
+		}
+	}
+	for &start in &code.invalid {
+		if let Some(is_code) = is_code.get_mut(start) {
+			*is_code = true;
+		}
+	}
+	let is_code = |index: usize| is_code.get(index).copied().unwrap_or(false);
+
+	for (instruction, _) in code.instructions.values() {
+		let (ins, out) = parameters(instruction);
+
+		let writes_code = matches!(out, Some(super::ParameterOut::Position(index)) if is_code(index));
+		let reads_code = ins.iter().any(|r#in| matches!(r#in, super::ParameterIn::Position(index) if is_code(*index)));
+		if writes_code || reads_code {
+			return unchanged();
+		}
+	}
+
+	let mut folded: std::collections::BTreeMap<usize, (super::Instruction, usize)> = Default::default();
+	let mut deleted: std::collections::BTreeMap<usize, usize> = Default::default();
+	let mut instructions_folded = 0;
+
+	for (&start, &(instruction, len)) in &code.instructions {
+		match fold(instruction, start + len) {
+			Some(new_instruction) => {
+				if new_instruction != instruction {
+					instructions_folded += 1;
+				}
+				folded.insert(start, (new_instruction, len));
+			},
+
+			None => { deleted.insert(start, len); },
+		}
+	}
+
+	// Removing instructions can make jumps over them into jumps to the next instruction, which can then also be removed.
+	loop {
+		let mut changed = false;
+
+		for (&start, &(instruction, len)) in &folded {
+			if deleted.contains_key(&start) {
+				continue;
+			}
+
+			if let super::Instruction::JumpIfTrue(_, super::ParameterIn::Immediate(target)) | super::Instruction::JumpIfFalse(_, super::ParameterIn::Immediate(target)) = instruction {
+				if let Ok(target) = target.try_into() {
+					if relocate_address(target, &deleted) == relocate_address(start + len, &deleted) {
+						deleted.insert(start, len);
+						changed = true;
+					}
+				}
+			}
+		}
+
+		if !changed {
+			break;
+		}
+	}
+
+	let mut result = Vec::with_capacity(ram.0.len());
+	let mut index = 0;
+	while index < ram.0.len() {
+		if let Some(&len) = deleted.get(&index) {
+			index += len;
+		}
+		else if let Some(&(instruction, len)) = folded.get(&index) {
+			result.extend(relocate(instruction, &deleted).encode());
+			index += len;
+		}
+		else {
+			result.push(ram.0[index]);
+			index += 1;
+		}
+	}
+
+	Optimized {
+		ram: super::Ram(result),
+		instructions_folded,
+		instructions_eliminated: deleted.len(),
+		words_eliminated: deleted.values().sum(),
+	}
+}
+
+struct Code {
+	// Map of start address to the instruction and its length
+	instructions: std::collections::BTreeMap<usize, (super::Instruction, usize)>,
+
+	// Reachable addresses that don't hold valid instructions. Programs can still overwrite them with valid instructions
+	// before reaching them.
+	invalid: Vec<usize>,
+
+	has_indirect_jumps: bool,
+	uses_relative_base: bool,
+}
+
+// Returns `None` if the program has overlapping instructions, ie a jump into the middle of another instruction.
+fn disassemble(ram: &super::Ram) -> Option<Code> {
+	let mut scratch = ram.clone();
+
+	let mut instructions: std::collections::BTreeMap<usize, (super::Instruction, usize)> = Default::default();
+	let mut invalid = vec![];
+	let mut has_indirect_jumps = false;
+	let mut uses_relative_base = false;
+
+	let mut to_visit = vec![0];
+
+	while let Some(start) = to_visit.pop() {
+		if instructions.contains_key(&start) || invalid.contains(&start) {
+			continue;
+		}
+
+		let mut pc = start;
+		let instruction = match super::Instruction::parse(&mut scratch, &mut pc) {
+			Ok(instruction) if pc <= ram.0.len() => instruction,
+
+			_ => {
+				invalid.push(start);
+				continue;
+			},
+		};
+		instructions.insert(start, (instruction, pc - start));
+
+		let (ins, out) = parameters(&instruction);
+		uses_relative_base |=
+			matches!(instruction, super::Instruction::SetRelativeBase(_)) ||
+			ins.iter().any(|r#in| matches!(r#in, super::ParameterIn::Relative(_))) ||
+			matches!(out, Some(super::ParameterOut::Relative(_)));
+
+		match instruction {
+			super::Instruction::Halt => (),
+
+			super::Instruction::JumpIfTrue(cond, target) | super::Instruction::JumpIfFalse(cond, target) => {
+				match target {
+					super::ParameterIn::Immediate(target) => if let Ok(target) = target.try_into() {
+						to_visit.push(target);
+					},
+					_ => has_indirect_jumps = true,
+				}
+
+				let is_unconditional = match (instruction, cond) {
+					(super::Instruction::JumpIfTrue(_, _), super::ParameterIn::Immediate(cond)) => cond != 0,
+					(super::Instruction::JumpIfFalse(_, _), super::ParameterIn::Immediate(cond)) => cond == 0,
+					_ => false,
+				};
+				if !is_unconditional {
+					to_visit.push(pc);
+				}
+			},
+
+			_ => to_visit.push(pc),
+		}
+	}
+
+	let mut previous_end = 0;
+	for (&start, &(_, len)) in &instructions {
+		if start < previous_end {
+			return None;
+		}
+		previous_end = start + len;
+	}
+
+	Some(Code {
+		instructions,
+		invalid,
+		has_indirect_jumps,
+		uses_relative_base,
+	})
+}
+
+// Returns the simplified instruction, or `None` if it's a no-op.
+fn fold(instruction: super::Instruction, next_pc: usize) -> Option<super::Instruction> {
+	use super::{Instruction, ParameterIn::Immediate};
+
+	let instruction = match instruction {
+		Instruction::Add(Immediate(in1), Immediate(in2), out) if in2 != 0 =>
+			in1.checked_add(in2).map_or(instruction, |value| Instruction::Add(Immediate(value), Immediate(0), out)),
+
+		Instruction::Mul(Immediate(in1), Immediate(in2), out) =>
+			in1.checked_mul(in2).map_or(instruction, |value| Instruction::Add(Immediate(value), Immediate(0), out)),
+
+		Instruction::Mul(_, Immediate(0), out) | Instruction::Mul(Immediate(0), _, out) =>
+			Instruction::Add(Immediate(0), Immediate(0), out),
+
+		Instruction::Mul(r#in, Immediate(1), out) | Instruction::Mul(Immediate(1), r#in, out) | Instruction::Add(Immediate(0), r#in, out) =>
+			Instruction::Add(r#in, Immediate(0), out),
+
+		Instruction::LessThan(Immediate(in1), Immediate(in2), out) =>
+			Instruction::Add(Immediate(if in1 < in2 { 1 } else { 0 }), Immediate(0), out),
+
+		Instruction::Equals(Immediate(in1), Immediate(in2), out) =>
+			Instruction::Add(Immediate(if in1 == in2 { 1 } else { 0 }), Immediate(0), out),
+
+		instruction => instruction,
+	};
+
+	match instruction {
+		Instruction::Add(r#in, Immediate(0), out) if is_same_cell(r#in, out) => None,
+
+		Instruction::JumpIfTrue(Immediate(0), _) => None,
+
+		Instruction::JumpIfFalse(Immediate(cond), _) if cond != 0 => None,
+
+		Instruction::JumpIfTrue(_, Immediate(target)) | Instruction::JumpIfFalse(_, Immediate(target)) if usize::try_from(target) == Ok(next_pc) => None,
+
+		instruction => Some(instruction),
+	}
+}
+
+fn is_same_cell(r#in: super::ParameterIn, out: super::ParameterOut) -> bool {
+	match (r#in, out) {
+		(super::ParameterIn::Position(r#in), super::ParameterOut::Position(out)) => r#in == out,
+		(super::ParameterIn::Relative(r#in), super::ParameterOut::Relative(out)) => r#in == out,
+		_ => false,
+	}
+}
+
+fn parameters(instruction: &super::Instruction) -> (Vec<super::ParameterIn>, Option<super::ParameterOut>) {
+	match *instruction {
+		super::Instruction::Add(in1, in2, out) |
+		super::Instruction::Mul(in1, in2, out) |
+		super::Instruction::LessThan(in1, in2, out) |
+		super::Instruction::Equals(in1, in2, out) => (vec![in1, in2], Some(out)),
+		super::Instruction::Store(out) => (vec![], Some(out)),
+		super::Instruction::Output(r#in) | super::Instruction::SetRelativeBase(r#in) => (vec![r#in], None),
+		super::Instruction::JumpIfTrue(cond, r#in) | super::Instruction::JumpIfFalse(cond, r#in) => (vec![cond, r#in], None),
+		super::Instruction::Halt => (vec![], None),
+	}
+}
+
+fn relocate_address(index: usize, deleted: &std::collections::BTreeMap<usize, usize>) -> usize {
+	let num_deleted_before: usize =
+		deleted.range(..index)
+		.map(|(&start, &len)| std::cmp::min(len, index - start))
+		.sum();
+	index - num_deleted_before
+}
+
+fn relocate(instruction: super::Instruction, deleted: &std::collections::BTreeMap<usize, usize>) -> super::Instruction {
+	let r#in = |r#in| match r#in {
+		super::ParameterIn::Position(index) => super::ParameterIn::Position(relocate_address(index, deleted)),
+		r#in => r#in,
+	};
+
+	let out = |out| match out {
+		super::ParameterOut::Position(index) => super::ParameterOut::Position(relocate_address(index, deleted)),
+		out @ super::ParameterOut::Relative(_) => out,
+	};
+
+	let target = |target| match target {
+		super::ParameterIn::Immediate(target) => match usize::try_from(target) {
+			Ok(target) => super::ParameterIn::Immediate(relocate_address(target, deleted).try_into().expect("usize fits in i64")),
+			Err(_) => super::ParameterIn::Immediate(target),
+		},
+		target => r#in(target),
+	};
+
+	match instruction {
+		super::Instruction::Add(in1, in2, o) => super::Instruction::Add(r#in(in1), r#in(in2), out(o)),
+		super::Instruction::Mul(in1, in2, o) => super::Instruction::Mul(r#in(in1), r#in(in2), out(o)),
+		super::Instruction::Store(o) => super::Instruction::Store(out(o)),
+		super::Instruction::Output(in1) => super::Instruction::Output(r#in(in1)),
+		super::Instruction::JumpIfTrue(cond, t) => super::Instruction::JumpIfTrue(r#in(cond), target(t)),
+		super::Instruction::JumpIfFalse(cond, t) => super::Instruction::JumpIfFalse(r#in(cond), target(t)),
+		super::Instruction::LessThan(in1, in2, o) => super::Instruction::LessThan(r#in(in1), r#in(in2), out(o)),
+		super::Instruction::Equals(in1, in2, o) => super::Instruction::Equals(r#in(in1), r#in(in2), out(o)),
+		super::Instruction::SetRelativeBase(in1) => super::Instruction::SetRelativeBase(r#in(in1)),
+		super::Instruction::Halt => super::Instruction::Halt,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_optimize() {
+		fn test(
+			program: &str,
+			inputs: &[&[i64]],
+			expected_ram: &[i64],
+			expected_instructions_folded: usize,
+			expected_instructions_eliminated: usize,
+			expected_words_eliminated: usize,
+		) {
+			let ram: crate::intcode::Ram = program.parse().unwrap();
+
+			let actual = super::optimize(&ram);
+			assert_eq!(expected_ram, &*actual.ram.0);
+			assert_eq!(expected_instructions_folded, actual.instructions_folded);
+			assert_eq!(expected_instructions_eliminated, actual.instructions_eliminated);
+			assert_eq!(expected_words_eliminated, actual.words_eliminated);
+
+			for input in inputs {
+				let expected_output = crate::intcode::Computer::new(ram.clone()).execute(input.iter().copied()).unwrap();
+				let actual_output = crate::intcode::Computer::new(actual.ram.clone()).execute(input.iter().copied()).unwrap();
+				assert_eq!(expected_output, actual_output);
+			}
+		}
+
+		test(
+			"\
+				1101,2,3,34,\
+				1002,34,1,34,\
+				1105,0,99,\
+				1001,34,0,34,\
+				1106,0,18,\
+				1005,35,25,\
+				1001,34,0,34,\
+				4,34,\
+				2,34,34,34,\
+				4,34,\
+				99,\
+				0,1\
+			",
+			&[&[]],
+			&[
+				1101, 5, 0, 13,
+				4, 13,
+				2, 13, 13, 13,
+				4, 13,
+				99,
+				0, 1,
+			],
+			1,
+			6,
+			21,
+		);
+
+		// Uses the relative base, so not optimized at all
+		test(
+			"109,20,21101,2,3,0,22101,0,0,0,204,0,99",
+			&[&[]],
+			&[109, 20, 21101, 2, 3, 0, 22101, 0, 0, 0, 204, 0, 99],
+			0,
+			0,
+			0,
+		);
+
+		// Reads its own code as data, so not optimized at all
+		test("1102,2,3,7,4,1,99,0", &[&[]], &[1102, 2, 3, 7, 4, 1, 99, 0], 0, 0, 0);
+
+		// Self-modifying, so not optimized at all
+		test("1,9,10,3,2,3,11,0,99,30,40,50", &[&[]], &[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50], 0, 0, 0);
+		test("1102,33,3,4,33", &[], &[1102, 33, 3, 4, 33], 0, 0, 0);
+
+		// Jumps into the middle of another instruction
+		test("1105,1,2,4,0,99", &[], &[1105, 1, 2, 4, 0, 99], 0, 0, 0);
+
+		// The larger example from day 5
+		test(
+			"3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99",
+			&[&[3], &[8], &[77]],
+			&[3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0, 0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1001, 0, 20, 4, 20, 1105, 1, 46, 98, 99],
+			1,
+			0,
+			0,
+		);
+	}
+
+	#[test]
+	fn test_optimize_compiled() {
+		let ram = crate::intcode::compiler::compile("\
+			fn main() {
+				let n = input();
+				while n > 0 {
+					output(fact(n));
+					n = n - 1;
+				}
+			}
+
+			fn fact(n) {
+				if n <= 1 {
+					return 1;
+				}
+				return n * fact(n - 1);
+			}
+		").unwrap();
+
+		let optimized = super::optimize(&ram);
+
+		for n in 0..8 {
+			let expected_output = crate::intcode::Computer::new(ram.clone()).execute(std::iter::once(n)).unwrap();
+			let actual_output = crate::intcode::Computer::new(optimized.ram.clone()).execute(std::iter::once(n)).unwrap();
+			assert_eq!(expected_output, actual_output);
+		}
+	}
+}
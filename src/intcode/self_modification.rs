@@ -0,0 +1,160 @@
+//! Detects self-modifying code while a program runs.
+//!
+//! Two kinds of self-modification are reported:
+//!
+//! - An instruction writes to an address that was previously executed as part of an instruction.
+//! - An instruction is executed that includes an address that was previously written to.
+//!
+//! Each is reported once per combination of kind, writer and target, so that loops that patch the same code every iteration don't flood the report.
+
+/// The kind of a self-modification
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Kind {
+	/// An instruction wrote to an address that was previously executed
+	WriteToExecuted,
+
+	/// An instruction was executed that includes an address that was previously written to
+	ExecuteAfterWrite,
+}
+
+/// A self-modification
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelfModification {
+	/// The kind of self-modification
+	pub kind: Kind,
+
+	/// The pc of the instruction that did the write
+	pub writer_pc: usize,
+
+	/// The address range of the affected instruction
+	pub target: std::ops::Range<usize>,
+}
+
+/// Records self-modifications as a computer executes instructions and writes to memory.
+///
+/// The monitor remembers every address that was executed or written to, so its memory grows with the number of distinct addresses
+/// that the program touches.
+#[derive(Clone, Debug, Default)]
+pub struct Monitor {
+	// Map of executed address to the range of the last instruction it was executed as part of
+	executed: std::collections::HashMap<usize, std::ops::Range<usize>>,
+
+	// Map of written address to the pc of the last instruction that wrote it
+	written: std::collections::HashMap<usize, usize>,
+
+	reported: std::collections::HashSet<(Kind, usize, usize, usize)>,
+	self_modifications: Vec<SelfModification>,
+}
+
+impl Monitor {
+	/// Called when an instruction occupying the given address range is about to be executed
+	pub fn on_execute(&mut self, instruction: std::ops::Range<usize>) {
+		for address in instruction.clone() {
+			if let Some(&writer_pc) = self.written.get(&address) {
+				self.report(Kind::ExecuteAfterWrite, writer_pc, instruction.clone());
+			}
+
+			self.executed.insert(address, instruction.clone());
+		}
+	}
+
+	/// Called when the instruction at `writer_pc` has written to `address`
+	pub fn on_write(&mut self, writer_pc: usize, address: usize) {
+		self.written.insert(address, writer_pc);
+
+		if let Some(target) = self.executed.get(&address) {
+			let target = target.clone();
+			self.report(Kind::WriteToExecuted, writer_pc, target);
+		}
+	}
+
+	/// All self-modifications recorded so far, in the order they happened
+	pub fn self_modifications(&self) -> &[SelfModification] {
+		&self.self_modifications
+	}
+
+	/// The merged address ranges of all instructions that were modified
+	pub fn mutable_code(&self) -> Vec<std::ops::Range<usize>> {
+		let mut targets: Vec<_> = self.self_modifications.iter().map(|self_modification| self_modification.target.clone()).collect();
+		targets.sort_by_key(|target| (target.start, target.end));
+
+		let mut result: Vec<std::ops::Range<usize>> = vec![];
+		for target in targets {
+			match result.last_mut() {
+				Some(last) if target.start <= last.end => last.end = std::cmp::max(last.end, target.end),
+				_ => result.push(target),
+			}
+		}
+		result
+	}
+
+	fn report(&mut self, kind: Kind, writer_pc: usize, target: std::ops::Range<usize>) {
+		let key = (kind, writer_pc, target.start, target.end);
+		if self.reported.insert(key) {
+			self.self_modifications.push(SelfModification { kind, writer_pc, target });
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_monitor() {
+		fn test(program: &str, input: &[i64], expected: &[(super::Kind, usize, std::ops::Range<usize>)], expected_mutable_code: &[std::ops::Range<usize>]) {
+			let mut computer: crate::intcode::Computer = crate::intcode::Computer::new(program.parse().unwrap());
+			computer.monitor_self_modification();
+			computer.execute(input.iter().copied()).unwrap();
+
+			let monitor = computer.self_modification_monitor().unwrap();
+
+			let actual: Vec<_> =
+				monitor.self_modifications().iter()
+				.map(|self_modification| (self_modification.kind, self_modification.writer_pc, self_modification.target.clone()))
+				.collect();
+			assert_eq!(expected, actual);
+
+			assert_eq!(expected_mutable_code, monitor.mutable_code());
+		}
+
+		// Day 2 example: patches its own operands and opcodes
+		test(
+			"1,9,10,3,2,3,11,0,99,30,40,50",
+			&[],
+			&[
+				(super::Kind::WriteToExecuted, 0, 0..4),
+				(super::Kind::WriteToExecuted, 4, 0..4),
+			],
+			&[0..4],
+		);
+
+		// Patches the instruction after it, which then halts
+		test(
+			"1002,4,3,4,33",
+			&[],
+			&[(super::Kind::ExecuteAfterWrite, 0, 4..5)],
+			&[4..5],
+		);
+
+		// Stores its input into the operand of the next instruction
+		test(
+			"3,3,104,0,99",
+			&[42],
+			&[(super::Kind::ExecuteAfterWrite, 0, 2..4)],
+			&[2..4],
+		);
+
+		// Patches the operand of an instruction that already ran, which then runs again with it
+		test(
+			"104,7,1001,1,1,1,1007,1,9,14,1005,14,0,99,0",
+			&[],
+			&[
+				(super::Kind::WriteToExecuted, 2, 0..2),
+				(super::Kind::ExecuteAfterWrite, 2, 0..2),
+			],
+			&[0..2],
+		);
+
+		// Writes only to data
+		test("3,9,1001,9,1,10,4,10,99,0,0", &[5], &[], &[]);
+	}
+}
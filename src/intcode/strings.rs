@@ -0,0 +1,193 @@
+//! Extracts the strings embedded in an Intcode program, like `strings(1)`.
+//!
+//! Three layouts are recognized:
+//!
+//! - Runs of printable characters, one per cell, eg the prompts in day 17 and day 21.
+//! - A cell holding the length, followed by that many printable characters. Day 21's error messages are stored like this.
+//! - A cell holding the length, followed by that many characters that are each offset by the length plus their index,
+//!   ie `cell[i] = char[i] - len - i`. Day 25 stores its room names, descriptions and messages like this.
+//!
+//! Code and numbers are often printable too, so only strings that are long enough and mostly look like prose are reported.
+
+/// How a string is stored in memory
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layout {
+	/// A run of printable characters, one per cell
+	Printable,
+
+	/// A cell holding the length, followed by that many printable characters
+	LengthPrefixed,
+
+	/// A cell holding the length, followed by that many characters that are each offset by the length plus their index
+	LengthPrefixedOffset,
+}
+
+/// A string found in memory
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+	/// The address of the first character, or of the length for length-prefixed strings
+	pub address: usize,
+
+	/// How the string is stored
+	pub layout: Layout,
+
+	/// The decoded string
+	pub text: String,
+}
+
+/// Finds all strings of at least `min_len` characters in the given memory, in order of address.
+pub fn strings(ram: &super::Ram, min_len: usize) -> Vec<Entry> {
+	let ram = &ram.0;
+
+	let mut result = vec![];
+
+	let mut address = 0;
+	while address < ram.len() {
+		// A string can decode to prose under both length-prefixed layouts, so pick the one that looks more like prose.
+		let entry =
+			match (
+				length_prefixed(ram, address, min_len, Layout::LengthPrefixedOffset),
+				length_prefixed(ram, address, min_len, Layout::LengthPrefixed),
+			) {
+				(Some(offset), Some(plain)) => Some(if prose_score(&plain.0.text) >= prose_score(&offset.0.text) { plain } else { offset }),
+				(offset, plain) => offset.or(plain),
+			}
+			.or_else(|| printable_run(ram, address, min_len));
+
+		match entry {
+			Some((entry, len)) => {
+				result.push(entry);
+				address += len;
+			},
+
+			None => address += 1,
+		}
+	}
+
+	result
+}
+
+/// Renders the entries as a table of address and text, one per line, with the text escaped so that it stays on one line.
+pub fn table(entries: &[Entry]) -> String {
+	let address_width = entries.iter().map(|entry| entry.address.to_string().len()).max().unwrap_or(0);
+
+	let mut result = String::new();
+	for Entry { address, text, .. } in entries {
+		result.push_str(&format!("{address:>address_width$} {text:?}\n"));
+	}
+	result
+}
+
+// Returns the entry and the number of cells it occupies
+fn length_prefixed(ram: &[i64], address: usize, min_len: usize, layout: Layout) -> Option<(Entry, usize)> {
+	let len: usize = ram[address].try_into().ok()?;
+	if len < min_len || len >= ram.len() - address {
+		return None;
+	}
+
+	let text: Option<String> =
+		ram[(address + 1)..=(address + len)].iter()
+		.zip(0..)
+		.map(|(&value, i)| match layout {
+			Layout::LengthPrefixed => to_char(value),
+			Layout::LengthPrefixedOffset => to_char(value.checked_add(ram[address])?.checked_add(i)?),
+			Layout::Printable => unreachable!(),
+		})
+		.collect();
+	let text = text?;
+
+	if !is_prose(&text) {
+		return None;
+	}
+
+	Some((Entry { address, layout, text }, len + 1))
+}
+
+fn printable_run(ram: &[i64], address: usize, min_len: usize) -> Option<(Entry, usize)> {
+	let mut text = String::new();
+
+	for (end, &value) in ram.iter().enumerate().skip(address) {
+		// Stop at the start of a length-prefixed string, so that consecutive length-prefixed strings aren't mistaken for a single run
+		if end > address && length_prefixed(ram, end, min_len, Layout::LengthPrefixed).is_some() {
+			break;
+		}
+
+		match to_char(value) {
+			Some(c) => text.push(c),
+			None => break,
+		}
+	}
+
+	if text.len() < min_len || !is_prose(&text) {
+		return None;
+	}
+
+	let len = text.len();
+	Some((Entry { address, layout: Layout::Printable, text }, len))
+}
+
+fn to_char(value: i64) -> Option<char> {
+	let b: u8 = value.try_into().ok()?;
+	if b.is_ascii_graphic() || b == b' ' || b == b'\n' {
+		Some(b.into())
+	}
+	else {
+		None
+	}
+}
+
+fn is_prose(text: &str) -> bool {
+	prose_score(text).0 * 10 >= text.len() * 9
+}
+
+// The number of characters that are common in prose, then the number of lowercase vowels to break ties between strings of letters
+fn prose_score(text: &str) -> (usize, usize) {
+	(
+		text.chars().filter(|&c| c.is_ascii_alphanumeric() || " \n.,;:'\"!?-()".contains(c)).count(),
+		text.chars().filter(|&c| "aeiou".contains(c)).count(),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_strings() {
+		fn encode(s: &str, offset: bool) -> String {
+			let len = i64::try_from(s.len()).unwrap();
+			let mut result = len.to_string();
+			for (b, i) in s.bytes().zip(0..) {
+				let value = if offset { i64::from(b) - len - i } else { i64::from(b) };
+				result.push_str(&format!(",{value}"));
+			}
+			result
+		}
+
+		let printable: String = "Input instructions:\n".bytes().map(|b| format!(",{b}")).collect();
+
+		let ram: crate::intcode::Ram = format!(
+			"1101,1,2,3,0{printable},{},{},104,101,108,108,111,0,{}",
+			encode("Expected J or T", false),
+			encode("Hull Breach", true),
+			encode("You can't go that way.", true),
+		).parse().unwrap();
+
+		let entries = super::strings(&ram, 4);
+		assert_eq!(entries, [
+			super::Entry { address: 5, layout: super::Layout::Printable, text: "Input instructions:\n".to_owned() },
+			super::Entry { address: 25, layout: super::Layout::LengthPrefixed, text: "Expected J or T".to_owned() },
+			super::Entry { address: 41, layout: super::Layout::LengthPrefixedOffset, text: "Hull Breach".to_owned() },
+			super::Entry { address: 53, layout: super::Layout::Printable, text: "hello".to_owned() },
+			super::Entry { address: 59, layout: super::Layout::LengthPrefixedOffset, text: "You can't go that way.".to_owned() },
+		]);
+
+		assert_eq!(
+			super::table(&entries),
+			" 5 \"Input instructions:\\n\"\n\
+				25 \"Expected J or T\"\n\
+				41 \"Hull Breach\"\n\
+				53 \"hello\"\n\
+				59 \"You can't go that way.\"\n\
+			",
+		);
+	}
+}
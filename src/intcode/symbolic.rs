@@ -0,0 +1,477 @@
+//! Symbolic execution of Intcode programs.
+//!
+//! Selected memory cells, as well as every value read from the input, are treated as symbols. `Add` and `Mul` build linear expressions
+//! of these symbols, and `LessThan` and `Equals` build comparisons of them. Control flow must not depend on symbols, so this works for
+//! straight-line programs like day 2's.
+//!
+//! Values that cannot be represented, like the product of two symbolic expressions or the result of reading from a symbolic address,
+//! become `Value::Unknown`. This is fine as long as they don't end up affecting control flow or the values of interest.
+
+/// An unknown value
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Symbol {
+	/// The original value of a memory cell
+	Memory(usize),
+
+	/// The n'th value read from the input
+	Input(usize),
+}
+
+impl std::fmt::Display for Symbol {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Symbol::Memory(index) => write!(f, "[{index}]"),
+			Symbol::Input(index) => write!(f, "input{index}"),
+		}
+	}
+}
+
+/// `constant + sum(coefficient * symbol)`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Linear {
+	constant: i64,
+
+	// Coefficients are never 0
+	terms: std::collections::BTreeMap<Symbol, i64>,
+}
+
+impl Linear {
+	fn constant(constant: i64) -> Self {
+		Linear { constant, terms: Default::default() }
+	}
+
+	fn symbol(symbol: Symbol) -> Self {
+		Linear { constant: 0, terms: std::iter::once((symbol, 1)).collect() }
+	}
+
+	/// The value of the expression, if it has no symbols
+	pub fn as_constant(&self) -> Option<i64> {
+		if self.terms.is_empty() { Some(self.constant) } else { None }
+	}
+
+	fn checked_add(&self, other: &Self) -> Option<Self> {
+		let mut result = self.clone();
+		result.constant = result.constant.checked_add(other.constant)?;
+		for (&symbol, &coefficient) in &other.terms {
+			let entry = result.terms.entry(symbol).or_default();
+			*entry = entry.checked_add(coefficient)?;
+			if *entry == 0 {
+				result.terms.remove(&symbol);
+			}
+		}
+		Some(result)
+	}
+
+	fn checked_scale(&self, factor: i64) -> Option<Self> {
+		if factor == 0 {
+			return Some(Linear::constant(0));
+		}
+
+		let constant = self.constant.checked_mul(factor)?;
+		let terms: Option<_> =
+			self.terms.iter()
+			.map(|(&symbol, &coefficient)| Some((symbol, coefficient.checked_mul(factor)?)))
+			.collect();
+		Some(Linear { constant, terms: terms? })
+	}
+
+	/// Finds values for the symbols within the given domains such that this expression evaluates to `target`.
+	///
+	/// All but one of the symbols are enumerated, and the last one is solved for directly, so this is fast as long as
+	/// the domains of all but the largest one are small.
+	pub fn solve(
+		&self,
+		target: i64,
+		domains: &std::collections::BTreeMap<Symbol, std::ops::RangeInclusive<i64>>,
+	) -> Result<Option<std::collections::BTreeMap<Symbol, i64>>, crate::Error> {
+		for symbol in self.terms.keys() {
+			if !domains.contains_key(symbol) {
+				return Err(format!("no domain for symbol {symbol}").into());
+			}
+		}
+
+		// Symbols that don't affect the result can have any value
+		let mut result: std::collections::BTreeMap<_, _> =
+			domains.iter()
+			.filter(|(symbol, _)| !self.terms.contains_key(symbol))
+			.map(|(&symbol, domain)| (symbol, *domain.start()))
+			.collect();
+
+		let solved_for = match self.terms.keys().max_by_key(|symbol| domains[symbol].end().saturating_sub(*domains[symbol].start())) {
+			Some(&symbol) => symbol,
+			None => return Ok(if self.constant == target { Some(result) } else { None }),
+		};
+		let solved_for_coefficient = i128::from(self.terms[&solved_for]);
+		let solved_for_domain = &domains[&solved_for];
+
+		let enumerated: Vec<_> =
+			self.terms.iter()
+			.filter(|&(&symbol, _)| symbol != solved_for)
+			.map(|(&symbol, &coefficient)| (symbol, i128::from(coefficient), domains[&symbol].clone()))
+			.collect();
+		if enumerated.iter().any(|(_, _, domain)| domain.is_empty()) {
+			return Ok(None);
+		}
+
+		let mut values: Vec<_> = enumerated.iter().map(|(_, _, domain)| *domain.start()).collect();
+
+		loop {
+			let sum: i128 =
+				enumerated.iter().zip(&values)
+				.map(|((_, coefficient, _), &value)| coefficient * i128::from(value))
+				.sum();
+			let residual = i128::from(target) - i128::from(self.constant) - sum;
+			if residual % solved_for_coefficient == 0 {
+				if let Ok(value) = i64::try_from(residual / solved_for_coefficient) {
+					if solved_for_domain.contains(&value) {
+						result.insert(solved_for, value);
+						result.extend(enumerated.iter().zip(&values).map(|((symbol, _, _), &value)| (*symbol, value)));
+						return Ok(Some(result));
+					}
+				}
+			}
+
+			// Advance to the next combination of values of the enumerated symbols
+			let mut i = 0;
+			loop {
+				let (value, (_, _, domain)) = match values.get_mut(i).zip(enumerated.get(i)) {
+					Some(entry) => entry,
+					None => return Ok(None),
+				};
+
+				if *value < *domain.end() {
+					*value += 1;
+					break;
+				}
+
+				*value = *domain.start();
+				i += 1;
+			}
+		}
+	}
+}
+
+impl std::fmt::Display for Linear {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut first = true;
+
+		if self.constant != 0 || self.terms.is_empty() {
+			write!(f, "{}", self.constant)?;
+			first = false;
+		}
+
+		for (symbol, &coefficient) in &self.terms {
+			match (first, coefficient < 0) {
+				(true, false) => (),
+				(true, true) => f.write_str("-")?,
+				(false, false) => f.write_str(" + ")?,
+				(false, true) => f.write_str(" - ")?,
+			}
+			first = false;
+
+			if coefficient.unsigned_abs() != 1 {
+				write!(f, "{} * ", coefficient.unsigned_abs())?;
+			}
+
+			write!(f, "{symbol}")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// The value of a memory cell or output
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	/// A linear expression of symbols, which includes constants
+	Linear(Linear),
+
+	/// 1 if the first expression is less than the second, else 0
+	LessThan(Linear, Linear),
+
+	/// 1 if the two expressions are equal, else 0
+	Equals(Linear, Linear),
+
+	/// A value that cannot be represented
+	Unknown,
+}
+
+impl Value {
+	fn as_constant(&self) -> Option<i64> {
+		match self {
+			Value::Linear(value) => value.as_constant(),
+			_ => None,
+		}
+	}
+
+	/// Like `Linear::solve`. Fails if the value isn't linear.
+	pub fn solve(
+		&self,
+		target: i64,
+		domains: &std::collections::BTreeMap<Symbol, std::ops::RangeInclusive<i64>>,
+	) -> Result<Option<std::collections::BTreeMap<Symbol, i64>>, crate::Error> {
+		match self {
+			Value::Linear(value) => value.solve(target, domains),
+			value => Err(format!("cannot solve {value} = {target}").into()),
+		}
+	}
+}
+
+impl std::fmt::Display for Value {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Value::Linear(value) => write!(f, "{value}"),
+			Value::LessThan(in1, in2) => write!(f, "if {in1} < {in2} then 1 else 0"),
+			Value::Equals(in1, in2) => write!(f, "if {in1} == {in2} then 1 else 0"),
+			Value::Unknown => f.write_str("?"),
+		}
+	}
+}
+
+/// A computer whose memory holds symbolic values
+pub struct SymbolicComputer {
+	ram: Vec<Value>,
+	pc: usize,
+	relative_base: isize,
+	num_inputs: usize,
+}
+
+impl SymbolicComputer {
+	/// Creates a computer that starts executing the given program at address 0. Every memory cell starts out as a constant.
+	pub fn new(ram: &super::Ram) -> Self {
+		SymbolicComputer {
+			ram: ram.0.iter().map(|&value| Value::Linear(Linear::constant(value))).collect(),
+			pc: 0,
+			relative_base: 0,
+			num_inputs: 0,
+		}
+	}
+
+	/// Treats the current value of the given memory cell as the symbol `Symbol::Memory(index)`
+	pub fn symbolize(&mut self, index: usize) {
+		*self.get_mut(index) = Value::Linear(Linear::symbol(Symbol::Memory(index)));
+	}
+
+	/// Returns the value at the given address
+	pub fn get(&mut self, index: usize) -> Value {
+		self.get_mut(index).clone()
+	}
+
+	fn get_mut(&mut self, index: usize) -> &mut Value {
+		if index >= self.ram.len() {
+			self.ram.resize(index + 1, Value::Linear(Linear::constant(0)));
+		}
+
+		&mut self.ram[index]
+	}
+
+	/// Runs the program until it halts, and returns its outputs.
+	pub fn execute(&mut self) -> Result<Vec<Value>, crate::Error> {
+		let mut result = vec![];
+
+		loop {
+			let pc = self.pc;
+
+			let opcode = self.get(pc);
+			let opcode = opcode.as_constant().ok_or_else(|| format!("pc {pc}: opcode depends on symbolic value {opcode}"))?;
+			if opcode < 0 {
+				return Err(format!("SIGILL({opcode})").into());
+			}
+
+			let mut parameter_mode = opcode / 100;
+			let opcode = opcode % 100;
+
+			let num_parameters = match opcode {
+				1 | 2 | 7 | 8 => 3,
+				5 | 6 => 2,
+				3 | 4 | 9 => 1,
+				99 => 0,
+				opcode => return Err(format!("SIGILL({opcode})").into()),
+			};
+
+			let mut parameters = vec![];
+			for i in 0..num_parameters {
+				parameters.push((parameter_mode % 10, self.get(pc + 1 + i)));
+				parameter_mode /= 10;
+			}
+
+			self.pc = pc + 1 + num_parameters;
+
+			match opcode {
+				1 => {
+					let in1 = self.read(&parameters[0])?;
+					let in2 = self.read(&parameters[1])?;
+					let out = match (in1, in2) {
+						(Value::Linear(in1), Value::Linear(in2)) =>
+							Value::Linear(in1.checked_add(&in2).ok_or_else(|| format!("pc {pc}: SIGFPE: {in1} + {in2} overflows"))?),
+						_ => Value::Unknown,
+					};
+					*self.write(pc, &parameters[2])? = out;
+				},
+
+				2 => {
+					let in1 = self.read(&parameters[0])?;
+					let in2 = self.read(&parameters[1])?;
+					let out = match (in1, in2) {
+						(Value::Linear(in1), Value::Linear(in2)) => {
+							let product = match (in1.as_constant(), in2.as_constant()) {
+								(Some(factor), _) => Some(in2.checked_scale(factor).ok_or_else(|| format!("pc {pc}: SIGFPE: {in1} * {in2} overflows"))?),
+								(_, Some(factor)) => Some(in1.checked_scale(factor).ok_or_else(|| format!("pc {pc}: SIGFPE: {in1} * {in2} overflows"))?),
+								(None, None) => None,
+							};
+							product.map_or(Value::Unknown, Value::Linear)
+						},
+						_ => Value::Unknown,
+					};
+					*self.write(pc, &parameters[2])? = out;
+				},
+
+				3 => {
+					let input = Symbol::Input(self.num_inputs);
+					self.num_inputs += 1;
+					*self.write(pc, &parameters[0])? = Value::Linear(Linear::symbol(input));
+				},
+
+				4 => {
+					let output = self.read(&parameters[0])?;
+					result.push(output);
+				},
+
+				5 | 6 => {
+					let cond = self.read(&parameters[0])?;
+					let cond = cond.as_constant().ok_or_else(|| format!("pc {pc}: branch condition depends on symbolic value {cond}"))?;
+					if (cond != 0) == (opcode == 5) {
+						let target = self.read(&parameters[1])?;
+						let target = target.as_constant().ok_or_else(|| format!("pc {pc}: branch target depends on symbolic value {target}"))?;
+						self.pc = target.try_into()?;
+					}
+				},
+
+				7 | 8 => {
+					let in1 = self.read(&parameters[0])?;
+					let in2 = self.read(&parameters[1])?;
+					let out = match (in1.as_constant(), in2.as_constant(), in1, in2) {
+						(Some(in1), Some(in2), _, _) => {
+							let out = if opcode == 7 { in1 < in2 } else { in1 == in2 };
+							Value::Linear(Linear::constant(if out { 1 } else { 0 }))
+						},
+						(_, _, Value::Linear(in1), Value::Linear(in2)) if opcode == 7 => Value::LessThan(in1, in2),
+						(_, _, Value::Linear(in1), Value::Linear(in2)) if in1 == in2 => Value::Linear(Linear::constant(1)),
+						(_, _, Value::Linear(in1), Value::Linear(in2)) => Value::Equals(in1, in2),
+						_ => Value::Unknown,
+					};
+					*self.write(pc, &parameters[2])? = out;
+				},
+
+				9 => {
+					let offset = self.read(&parameters[0])?;
+					let offset = offset.as_constant().ok_or_else(|| format!("pc {pc}: relative base depends on symbolic value {offset}"))?;
+					let offset: isize = offset.try_into()?;
+					self.relative_base += offset;
+				},
+
+				99 => return Ok(result),
+
+				_ => unreachable!(),
+			}
+		}
+	}
+
+	fn read(&mut self, (mode, value): &(i64, Value)) -> Result<Value, crate::Error> {
+		let index = match (mode, value.as_constant()) {
+			(0, Some(index)) => index.try_into()?,
+			(1, _) => return Ok(value.clone()),
+			(2, Some(offset)) => {
+				let offset: isize = offset.try_into()?;
+				(self.relative_base + offset).try_into()?
+			},
+			(0 | 2, None) => return Ok(Value::Unknown),
+			(mode, _) => return Err(format!("invalid parameter mode {mode}").into()),
+		};
+		Ok(self.get(index))
+	}
+
+	fn write(&mut self, pc: usize, (mode, value): &(i64, Value)) -> Result<&mut Value, crate::Error> {
+		let index = match (mode, value.as_constant()) {
+			(0, Some(index)) => index.try_into()?,
+			(1, _) => return Err("SIGILL: output parameter has immediate mode".into()),
+			(2, Some(offset)) => {
+				let offset: isize = offset.try_into()?;
+				(self.relative_base + offset).try_into()?
+			},
+			(0 | 2, None) => return Err(format!("pc {pc}: write to symbolic address {value}").into()),
+			(mode, _) => return Err(format!("invalid parameter mode {mode}").into()),
+		};
+		Ok(self.get_mut(index))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_execute() {
+		fn test(program: &str, symbols: &[usize], cell: Option<usize>, expected: &str) {
+			let ram = program.parse().unwrap();
+			let mut computer = super::SymbolicComputer::new(&ram);
+			for &symbol in symbols {
+				computer.symbolize(symbol);
+			}
+
+			let actual = match computer.execute() {
+				Ok(output) => match cell {
+					Some(cell) => computer.get(cell).to_string(),
+					None => output.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+				},
+				Err(err) => err.0.to_string(),
+			};
+			assert_eq!(expected, actual);
+		}
+
+		test("1,9,10,3,2,3,11,0,99,30,40,50", &[], Some(0), "3500");
+		test("1,9,10,3,2,3,11,0,99,30,40,50", &[9], Some(0), "2000 + 50 * [9]");
+		test("1,9,10,3,2,3,11,0,99,30,40,50", &[9, 11], Some(0), "?");
+
+		// Reading from a symbolic address is fine as long as the value is overwritten before being used
+		test("1,1,2,3,1,1,2,3,2,3,13,0,99,7", &[1, 2], Some(0), "7 * [1] + 7 * [2]");
+
+		test("3,0,3,1,1,0,1,2,1002,2,-3,2,4,2,99", &[], None, "-3 * input0 - 3 * input1");
+
+		test("3,0,1007,0,5,1,4,1,1008,0,5,1,4,1,8,0,0,1,4,1,99", &[], None, "if input0 < 5 then 1 else 0, if input0 == 5 then 1 else 0, 1");
+
+		test("3,0,1006,0,5,99", &[], None, "pc 2: branch condition depends on symbolic value input0");
+		test("3,5,1,0,0,0,99", &[], None, "pc 2: write to symbolic address input0");
+	}
+
+	#[test]
+	fn test_solve() {
+		fn test(program: &str, symbols: &[usize], domains: &[(usize, std::ops::RangeInclusive<i64>)], target: i64, expected: Option<&[(usize, i64)]>) {
+			let ram = program.parse().unwrap();
+			let mut computer = super::SymbolicComputer::new(&ram);
+			for &symbol in symbols {
+				computer.symbolize(symbol);
+			}
+			let _ = computer.execute().unwrap();
+
+			let domains =
+				domains.iter()
+				.map(|(index, domain)| (super::Symbol::Memory(*index), domain.clone()))
+				.collect();
+			let actual = computer.get(0).solve(target, &domains).unwrap();
+			let expected = expected.map(|expected| expected.iter().map(|&(index, value)| (super::Symbol::Memory(index), value)).collect());
+			assert_eq!(expected, actual);
+		}
+
+		// [0] = 50 * [9] + 2000
+		test("1,9,10,3,2,3,11,0,99,30,40,50", &[9], &[(9, 0..=99)], 3500, Some(&[(9, 30)]));
+		test("1,9,10,3,2,3,11,0,99,30,40,50", &[9], &[(9, 0..=99)], 3501, None);
+		test("1,9,10,3,2,3,11,0,99,30,40,50", &[9], &[(9, 0..=29)], 3500, None);
+
+		// [0] = 7 * [1] + 7 * [2]
+		test("1,1,2,3,1,1,2,3,2,3,13,0,99,7", &[1, 2], &[(1, 0..=99), (2, 3..=99)], 70, Some(&[(1, 7), (2, 3)]));
+		test("1,1,2,3,1,1,2,3,2,3,13,0,99,7", &[1, 2], &[(1, 0..=5), (2, 0..=99)], 70, Some(&[(1, 0), (2, 10)]));
+		test("1,1,2,3,1,1,2,3,2,3,13,0,99,7", &[1, 2], &[(1, 0..=5), (2, 0..=99)], 71, None);
+
+		// [0] is constant, so [5] can be anything
+		test("1101,1,2,0,99,0", &[], &[(5, 3..=4)], 3, Some(&[(5, 3)]));
+	}
+}
@@ -0,0 +1,220 @@
+//! Records every input consumed and output produced by a computer, so that a session can be saved and replayed later as a regression test.
+//!
+//! Each event is tagged with the number of instructions the computer had executed before the instruction that did the I/O,
+//! so replaying checks not just what the program inputs and outputs but also when.
+//!
+//! The text format has one event per line:
+//!
+//! ```text
+//! <instruction number> in <value>
+//! <instruction number> out <value>
+//! <instruction number> halt
+//! ```
+
+/// An input, output or halt
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event<W = i64> {
+	/// The program read a value from the input
+	Input {
+		/// The number of instructions executed before this one
+		instruction: u64,
+
+		/// The value that was read
+		value: W,
+	},
+
+	/// The program output a value
+	Output {
+		/// The number of instructions executed before this one
+		instruction: u64,
+
+		/// The value that was output
+		value: W,
+	},
+
+	/// The program halted
+	Halt {
+		/// The number of instructions executed before this one
+		instruction: u64,
+	},
+}
+
+impl<W> Event<W> {
+	fn instruction(&self) -> u64 {
+		match self {
+			Event::Input { instruction, .. } |
+			Event::Output { instruction, .. } |
+			Event::Halt { instruction } => *instruction,
+		}
+	}
+}
+
+impl<W> std::fmt::Display for Event<W> where W: std::fmt::Display {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Event::Input { instruction, value } => write!(f, "{instruction} in {value}"),
+			Event::Output { instruction, value } => write!(f, "{instruction} out {value}"),
+			Event::Halt { instruction } => write!(f, "{instruction} halt"),
+		}
+	}
+}
+
+/// The events of a session, in the order they happened
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transcript<W = i64> {
+	/// The events
+	pub events: Vec<Event<W>>,
+}
+
+// Not derived, since that would require `W: Default`
+impl<W> Default for Transcript<W> {
+	fn default() -> Self {
+		Transcript { events: vec![] }
+	}
+}
+
+impl<W> std::fmt::Display for Transcript<W> where W: std::fmt::Display {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for event in &self.events {
+			writeln!(f, "{event}")?;
+		}
+		Ok(())
+	}
+}
+
+impl<W> std::str::FromStr for Transcript<W> where W: super::Word {
+	type Err = crate::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut events = vec![];
+
+		for (line_number, line) in s.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() {
+				continue;
+			}
+
+			let event: Result<_, crate::Error> = (|| {
+				let mut parts = line.split_whitespace();
+				let instruction = parts.next().ok_or("missing instruction number")?.parse()?;
+				let event = match (parts.next(), parts.next()) {
+					(Some("in"), Some(value)) => Event::Input { instruction, value: W::parse(value)? },
+					(Some("out"), Some(value)) => Event::Output { instruction, value: W::parse(value)? },
+					(Some("halt"), None) => Event::Halt { instruction },
+					_ => return Err("expected `in <value>`, `out <value>` or `halt`".into()),
+				};
+				if parts.next().is_some() {
+					return Err("unexpected trailing text".into());
+				}
+				Ok(event)
+			})();
+
+			events.push(event.map_err(|err| format!("line {}: {}", line_number + 1, err.0))?);
+		}
+
+		Ok(Transcript { events })
+	}
+}
+
+/// Runs the program with the inputs from the transcript, and fails as soon as any input, output or halt happens at a different
+/// instruction or with a different value than it did in the transcript.
+///
+/// If the transcript doesn't end with the program halting, eg because an interactive session was quit, the replay stops after the last event.
+pub fn replay<W>(ram: super::Ram<W>, expected: &Transcript<W>) -> Result<(), crate::Error> where W: super::Word + std::fmt::Display {
+	let mut computer = super::Computer::new(ram);
+	computer.record_transcript();
+
+	let mut input = expected.events.iter().filter_map(|event| match event {
+		Event::Input { value, .. } => Some(value.clone()),
+		_ => None,
+	});
+
+	loop {
+		let num_events = computer.transcript().map_or(0, |transcript| transcript.events.len());
+		let next_expected = match expected.events.get(num_events) {
+			Some(next_expected) => next_expected,
+			None => return Ok(()),
+		};
+
+		let diverged = |actual: &dyn std::fmt::Display| -> crate::Error {
+			format!("replay diverged at event {}: expected `{next_expected}` but got {actual}", num_events + 1).into()
+		};
+
+		if computer.instructions_executed() > next_expected.instruction() {
+			return Err(diverged(&format_args!("no event by instruction {}", computer.instructions_executed())));
+		}
+
+		let step = computer.step_instruction(&mut input);
+		let actual = computer.transcript().and_then(|transcript| transcript.events.get(num_events));
+		match (step, actual) {
+			(_, Some(actual)) if actual != next_expected => return Err(diverged(&format_args!("`{actual}`"))),
+			(Err(err), _) => return Err(diverged(&format_args!("error {:?}", err.0.to_string()))),
+			_ => (),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	// Outputs each input plus one until it reads 0, then halts
+	const PROGRAM: &str = "3,15,1006,15,14,101,1,15,15,4,15,1105,1,0,99,0";
+
+	#[test]
+	fn test_record() {
+		let mut computer: crate::intcode::Computer = crate::intcode::Computer::new(PROGRAM.parse().unwrap());
+		computer.record_transcript();
+		assert_eq!(computer.execute([5, 7, 0]).unwrap(), [6, 8]);
+
+		let transcript = computer.transcript().unwrap();
+		assert_eq!(
+			transcript.to_string(),
+			"\
+				0 in 5\n\
+				3 out 6\n\
+				5 in 7\n\
+				8 out 8\n\
+				10 in 0\n\
+				12 halt\n\
+			",
+		);
+
+		assert_eq!(&transcript.to_string().parse::<super::Transcript>().unwrap(), transcript);
+	}
+
+	#[test]
+	fn test_replay() {
+		fn test(program: &str, transcript: &str, expected: Result<(), &str>) {
+			let transcript: super::Transcript = transcript.parse().unwrap();
+			let actual = super::replay(program.parse().unwrap(), &transcript);
+			match expected {
+				Ok(()) => actual.unwrap(),
+				Err(expected_err) => assert_eq!(expected_err, actual.unwrap_err().0.to_string()),
+			}
+		}
+
+		test(PROGRAM, "0 in 5\n3 out 6\n5 in 7\n8 out 8\n10 in 0\n12 halt\n", Ok(()));
+
+		// A session that was quit before the program halted
+		test(PROGRAM, "0 in 5\n3 out 6\n", Ok(()));
+
+		test(PROGRAM, "0 in 5\n3 out 6\n5 in 7\n8 out 9\n", Err("replay diverged at event 4: expected `8 out 9` but got `8 out 8`"));
+		test(PROGRAM, "0 in 5\n3 out 6\n5 in 7\n7 out 8\n", Err("replay diverged at event 4: expected `7 out 8` but got no event by instruction 8"));
+		test(PROGRAM, "0 in 5\n3 out 6\n5 in 7\n8 out 8\n10 in 0\n11 halt\n", Err("replay diverged at event 6: expected `11 halt` but got no event by instruction 12"));
+		test(PROGRAM, "0 in 5\n3 out 6\n5 out 7\n", Err(r#"replay diverged at event 3: expected `5 out 7` but got error "EOF""#));
+
+		// The program changed to add 2 instead of 1
+		test(&PROGRAM.replace("101,1,", "101,2,"), "0 in 5\n3 out 6\n", Err("replay diverged at event 2: expected `3 out 6` but got `3 out 7`"));
+	}
+
+	#[test]
+	fn test_parse_error() {
+		fn test(transcript: &str, expected_err: &str) {
+			let actual_err = transcript.parse::<super::Transcript>().unwrap_err();
+			assert_eq!(expected_err, actual_err.0.to_string());
+		}
+
+		test("0 in 5\n3 output 6\n", "line 2: expected `in <value>`, `out <value>` or `halt`");
+		test("0 in 5\nx halt\n", "line 2: invalid digit found in string");
+		test("0 halt 5\n", "line 1: expected `in <value>`, `out <value>` or `halt`");
+	}
+}
@@ -0,0 +1,764 @@
+//! Solutions to Advent of Code 2019, and the Intcode computer and utilities they're built on.
+//!
+//! The [`intcode`] module is a standalone Intcode VM along with tools for working with Intcode programs.
+//! Each day's puzzle is solved by a [`Solution`], and [`DAYS`] has all of them.
+//! [`run`] runs a day's solution, either against the input in this crate's `inputs` directory or against another [`Input`].
+
+#![deny(missing_docs, rust_2018_idioms, warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(
+	clippy::default_trait_access,
+	clippy::let_and_return,
+	clippy::let_underscore_drop,
+	clippy::missing_errors_doc,
+	clippy::module_name_repetitions,
+	clippy::must_use_candidate,
+	clippy::naive_bytecount,
+	clippy::too_many_arguments,
+	clippy::too_many_lines,
+	clippy::type_complexity,
+	clippy::unreadable_literal,
+)]
+
+pub mod answers;
+
+pub mod grid;
+
+pub mod intcode;
+
+pub mod ocr;
+
+pub mod timing;
+
+pub mod vis;
+
+mod day1;
+mod day2;
+mod day3;
+mod day4;
+mod day5;
+mod day6;
+mod day7;
+mod day8;
+mod day9;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
+
+/// The solution of a day's puzzle.
+///
+/// The input is parsed once, and then each part solves the puzzle for the parsed input.
+pub trait Solution {
+	/// The parsed input
+	type Input;
+
+	/// The answer of the first part
+	type Part1: Into<Value>;
+
+	/// The answer of the second part
+	type Part2: Into<Value>;
+
+	/// Whether the puzzle has a second part. Only the last day's doesn't.
+	const HAS_PART2: bool = true;
+
+	/// Parses the input
+	fn parse(input: Box<dyn std::io::BufRead>) -> Result<Self::Input, Error>;
+
+	/// Solves the first part
+	fn part1(input: &Self::Input) -> Result<Self::Part1, Error>;
+
+	/// Solves the second part
+	fn part2(input: &Self::Input) -> Result<Self::Part2, Error>;
+}
+
+/// A day's [`Solution`], as registered in [`DAYS`]
+pub struct Day {
+	/// The day's number
+	pub day: u32,
+
+	run: fn(u32, &Context) -> Report,
+}
+
+impl Day {
+	const fn new<S>(day: u32) -> Self where S: Solution {
+		Day {
+			day,
+			run: run_solution::<S>,
+		}
+	}
+
+	/// Runs the solution, and returns its answers. If it fails, the report has the answers of the parts before the failure.
+	pub fn run(&self, context: &Context) -> Report {
+		(self.run)(self.day, context)
+	}
+}
+
+impl std::fmt::Debug for Day {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("Day").field("day", &self.day).finish_non_exhaustive()
+	}
+}
+
+/// The solutions of every day, in order
+pub static DAYS: &[Day] = &[
+	Day::new::<day1::Solution>(1),
+	Day::new::<day2::Solution>(2),
+	Day::new::<day3::Solution>(3),
+	Day::new::<day4::Solution>(4),
+	Day::new::<day5::Solution>(5),
+	Day::new::<day6::Solution>(6),
+	Day::new::<day7::Solution>(7),
+	Day::new::<day8::Solution>(8),
+	Day::new::<day9::Solution>(9),
+	Day::new::<day10::Solution>(10),
+	Day::new::<day11::Solution>(11),
+	Day::new::<day12::Solution>(12),
+	Day::new::<day13::Solution>(13),
+	Day::new::<day14::Solution>(14),
+	Day::new::<day15::Solution>(15),
+	Day::new::<day16::Solution>(16),
+	Day::new::<day17::Solution>(17),
+	Day::new::<day18::Solution>(18),
+	Day::new::<day19::Solution>(19),
+	Day::new::<day20::Solution>(20),
+	Day::new::<day21::Solution>(21),
+	Day::new::<day22::Solution>(22),
+	Day::new::<day23::Solution>(23),
+	Day::new::<day24::Solution>(24),
+	Day::new::<day25::Solution>(25),
+];
+
+/// Finds the solution of the given day
+pub fn day(day: u32) -> Result<&'static Day, Error> {
+	DAYS.iter().find(|d| d.day == day).ok_or_else(|| format!("there is no day {day}").into())
+}
+
+/// Runs the solution of the given day, and returns its answers.
+pub fn run(day: u32, context: &Context) -> Result<Report, Error> {
+	Ok(self::day(day)?.run(context))
+}
+
+fn run_solution<S>(day: u32, context: &Context) -> Report where S: Solution {
+	fn solve<T>(part: Part, input_name: &str, f: impl FnOnce() -> Result<T, Error>) -> Result<Answer, Error> where T: Into<Value> {
+		let started = std::time::Instant::now();
+		let value = f()?.into();
+		let time = started.elapsed();
+		Ok(Answer { part, input: input_name.to_owned(), value, time })
+	}
+
+	let started = std::time::Instant::now();
+	let parsed =
+		context.open_input(&format!("day{day}"))
+		.and_then(|(input_name, input)| match S::parse(input) {
+			Ok(input) => Ok((input_name, input)),
+			Err(err) => Err(err.in_input(&input_name)),
+		});
+	let mut report = Report {
+		parse_time: started.elapsed(),
+		answers: vec![],
+		error: None,
+	};
+
+	let (input_name, input) = match parsed {
+		Ok(parsed) => parsed,
+		Err(err) => {
+			report.error = Some((None, err));
+			return report;
+		},
+	};
+
+	let parts: [(Part, &dyn Fn() -> Result<Answer, Error>); 2] = [
+		(Part::A, &|| solve(Part::A, &input_name, || S::part1(&input))),
+		(Part::B, &|| solve(Part::B, &input_name, || S::part2(&input))),
+	];
+	for (part, solve) in parts {
+		if !context.runs(part) || (part == Part::B && !S::HAS_PART2) {
+			continue;
+		}
+
+		let _vis = vis::Scope::new(context.vis.as_ref(), &format!("day{day}{part}"));
+
+		match solve() {
+			Ok(answer) => report.answers.push(answer),
+			Err(err) => {
+				report.error = Some((Some(part), err));
+				break;
+			},
+		}
+	}
+
+	report
+}
+
+/// Selects which parts of a day to run, and where its input comes from.
+#[derive(Debug, Default)]
+pub struct Context {
+	/// The part to run, or `None` to run both
+	pub part: Option<Part>,
+
+	/// Replaces the day's input file, or `None` to use the file in this crate's `inputs` directory
+	pub input: Option<Input>,
+
+	/// Visualizes the days that support it with these options, or `None` to not visualize them
+	pub vis: Option<vis::Options>,
+}
+
+impl Context {
+	/// Whether the given part should be run
+	pub fn runs(&self, part: Part) -> bool {
+		self.part.map_or(true, |p| p == part)
+	}
+
+	/// Opens the file with the given name in this crate's `inputs` directory, or `self.input` instead if it's set.
+	/// Returns the name of the input that was opened along with it.
+	pub fn open_input(&self, filename: &str) -> Result<(String, Box<dyn std::io::BufRead>), Error> {
+		Ok(match &self.input {
+			None => (filename.to_owned(), open_input(filename)?),
+			Some(Input::Path(path)) => {
+				let file = std::fs::File::open(path).map_err(|err| format!("could not open {}: {err}", path.display()))?;
+				(path.display().to_string(), Box::new(std::io::BufReader::new(file)))
+			},
+			Some(Input::Bytes(bytes)) => ("-".to_owned(), Box::new(std::io::Cursor::new(bytes.clone()))),
+		})
+	}
+}
+
+/// The result of running a day
+#[derive(Debug)]
+pub struct Report {
+	/// The time spent opening and parsing the input
+	pub parse_time: std::time::Duration,
+
+	/// The answers of the parts that were run
+	pub answers: Vec<Answer>,
+
+	/// The error that stopped the run, if any, along with the part that failed, or `None` if opening or parsing the input failed
+	pub error: Option<(Option<Part>, Error)>,
+}
+
+/// The answer of one part of a day
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+	/// The part that this is the answer of
+	pub part: Part,
+
+	/// The name of the input that this is the answer for. This is the file name for inputs in this crate's `inputs` directory,
+	/// the path for `Input::Path`, and `-` for `Input::Bytes`.
+	pub input: String,
+
+	/// The answer
+	pub value: Value,
+
+	/// The time taken to compute the answer
+	pub time: std::time::Duration,
+}
+
+/// The value of an answer
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	/// A number
+	Number(i128),
+
+	/// Text, eg a password
+	Text(String),
+
+	/// A URL that computes the answer
+	Url(String),
+
+	/// An image of the answer, for letters drawn in pixels
+	Image {
+		/// The pixels
+		image: Image,
+
+		/// The letters in the image, if [`ocr`] recognizes them
+		text: Option<String>,
+	},
+}
+
+impl Value {
+	/// The name of the kind of value, like `number`
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Value::Number(_) => "number",
+			Value::Text(_) => "text",
+			Value::Url(_) => "url",
+			Value::Image { .. } => "image",
+		}
+	}
+
+	/// An image of letters, along with the letters if they can be recognized
+	pub fn from_image(image: Image) -> Self {
+		let text = ocr::recognize(&image);
+		Value::Image { image, text }
+	}
+}
+
+// Images are displayed as their letters, or as block art if they weren't recognized, with each pixel two characters wide so that they're roughly square.
+impl std::fmt::Display for Value {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Value::Number(n) => n.fmt(f),
+			Value::Text(s) | Value::Url(s) => f.write_str(s),
+			Value::Image { text: Some(text), .. } => f.write_str(text),
+			Value::Image { image, text: None } => {
+				for (y, row) in image.rows().enumerate() {
+					if y > 0 {
+						f.write_str("\n")?;
+					}
+
+					for &pixel in row {
+						f.write_str(if pixel { "\u{2588}\u{2588}" } else { "  " })?;
+					}
+				}
+
+				Ok(())
+			},
+		}
+	}
+}
+
+macro_rules! value_from_number {
+	($($ty:ty)*) => {
+		$(
+			impl From<$ty> for Value {
+				fn from(n: $ty) -> Self {
+					Value::Number(n.into())
+				}
+			}
+		)*
+	};
+}
+
+value_from_number! { i32 i64 u32 u64 }
+
+impl From<usize> for Value {
+	fn from(n: usize) -> Self {
+		Value::Number(std::convert::TryInto::try_into(n).expect("usize fits in i128"))
+	}
+}
+
+impl From<String> for Value {
+	fn from(s: String) -> Self {
+		Value::Text(s)
+	}
+}
+
+impl From<Image> for Value {
+	fn from(image: Image) -> Self {
+		Value::from_image(image)
+	}
+}
+
+impl From<std::convert::Infallible> for Value {
+	fn from(never: std::convert::Infallible) -> Self {
+		match never {}
+	}
+}
+
+/// A black and white image
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+	width: usize,
+	pixels: Vec<bool>,
+}
+
+impl Image {
+	/// Creates an image from the given rows of pixels, where `true` is white. Every row must have the same width.
+	pub fn new(rows: impl IntoIterator<Item = impl IntoIterator<Item = bool>>) -> Result<Self, Error> {
+		let mut width = None;
+		let mut pixels = vec![];
+
+		for row in rows {
+			let len = pixels.len();
+			pixels.extend(row);
+			let row_width = pixels.len() - len;
+			if *width.get_or_insert(row_width) != row_width {
+				return Err("image rows have different widths".into());
+			}
+		}
+
+		Ok(Image { width: width.unwrap_or_default(), pixels })
+	}
+
+	/// The width of the image
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	/// The height of the image
+	pub fn height(&self) -> usize {
+		if self.width == 0 { 0 } else { self.pixels.len() / self.width }
+	}
+
+	/// The rows of pixels, from top to bottom
+	pub fn rows(&self) -> impl Iterator<Item = &[bool]> + '_ {
+		self.pixels.chunks(self.width.max(1))
+	}
+}
+
+/// One of the two parts of a day's puzzle
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Part {
+	/// The first part
+	A,
+
+	/// The second part
+	B,
+}
+
+impl std::fmt::Display for Part {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Part::A => f.write_str("a"),
+			Part::B => f.write_str("b"),
+		}
+	}
+}
+
+impl std::str::FromStr for Part {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"a" | "1" => Ok(Part::A),
+			"b" | "2" => Ok(Part::B),
+			s => Err(format!("invalid part {s:?}, expected a or b").into()),
+		}
+	}
+}
+
+/// An input that replaces a day's input file
+#[derive(Debug)]
+pub enum Input {
+	/// The file at the given path
+	Path(std::path::PathBuf),
+
+	/// The given contents, eg everything that was read from stdin. They don't have to be text, like a binary Intcode program.
+	Bytes(Vec<u8>),
+}
+
+/// Opens the file with the given name in this crate's `inputs` directory.
+pub fn open_input(filename: &str) -> Result<Box<dyn std::io::BufRead>, Error> {
+	let mut path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).to_owned();
+	path.push("inputs");
+	path.push(filename);
+	let file = std::fs::File::open(&path).map_err(|err| format!("could not open {}: {err}", path.display()))?;
+	Ok(Box::new(std::io::BufReader::new(file)))
+}
+
+/// The error type of everything in this crate.
+///
+/// It can be created from anything that can be converted into a `Box<dyn std::error::Error + Send + Sync>`, including strings, and captures a backtrace
+/// when it's created.
+pub struct Error(Box<dyn std::error::Error + Send + Sync>, backtrace::Backtrace);
+
+impl Error {
+	/// The backtrace of where the error was created
+	pub fn backtrace(&self) -> &backtrace::Backtrace {
+		&self.1
+	}
+
+	/// Locates this error at the given byte range of the given text, for an error in parsing that range of the text.
+	///
+	/// A [`ParseError`] is moved from the range into the whole text, so its span stays on the same offending text.
+	/// Any other error becomes a [`ParseError`] whose span is the whole range.
+	#[must_use]
+	pub fn at(mut self, text: &str, span: std::ops::Range<usize>) -> Self {
+		if let Some(err) = self.0.downcast_mut::<ParseError>() {
+			err.text = text.to_owned();
+			err.span = (err.span.start + span.start)..(err.span.end + span.start);
+		}
+		else {
+			let message = self.0.to_string();
+			self.0 = Box::new(ParseError::new(text, span, message));
+		}
+
+		self
+	}
+
+	/// Sets the line number of a [`ParseError`]. The error must already have been located at the whole line.
+	fn at_line(mut self, line: usize) -> Self {
+		if let Some(err) = self.0.downcast_mut::<ParseError>() {
+			err.line = Some(line);
+		}
+
+		self
+	}
+
+	/// Sets the name of the input of a [`ParseError`].
+	fn in_input(mut self, input: &str) -> Self {
+		if let Some(err) = self.0.downcast_mut::<ParseError>() {
+			err.input = Some(input.to_owned());
+		}
+
+		self
+	}
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.0.fmt(f)
+	}
+}
+
+impl std::fmt::Debug for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{}", self.0)?;
+
+		let mut source = self.0.source();
+		while let Some(err) = source {
+			writeln!(f, "caused by: {err}")?;
+			source = err.source();
+		}
+
+		writeln!(f)?;
+
+		writeln!(f, "{:?}", self.1)?;
+
+		Ok(())
+	}
+}
+
+impl<E> From<E> for Error where E: Into<Box<dyn std::error::Error + Send + Sync>> {
+	fn from(err: E) -> Self {
+		Error(err.into(), Default::default())
+	}
+}
+
+/// An error in parsing an input, along with where in the input it happened.
+///
+/// It's displayed with the offending text underlined:
+///
+/// ```text
+/// day3:2:14: malformed step: invalid digit found in string
+///   |
+/// 2 | U7,R6,D4,L4,Rx
+///   |              ^
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+	/// The name of the input, as in [`Answer::input`], if it's known
+	pub input: Option<String>,
+
+	/// The 1-based line number of [`ParseError::text`] in the input, if it's known
+	pub line: Option<usize>,
+
+	/// The text that was being parsed, usually the whole line
+	pub text: String,
+
+	/// The byte range of the offending text in [`ParseError::text`]
+	pub span: std::ops::Range<usize>,
+
+	/// What was wrong with the offending text
+	pub message: String,
+}
+
+impl ParseError {
+	/// An error in parsing the given byte range of the given text. Use [`Error::at`] to locate it in a larger text.
+	pub fn new(text: &str, span: std::ops::Range<usize>, message: impl Into<String>) -> Self {
+		ParseError {
+			input: None,
+			line: None,
+			text: text.to_owned(),
+			span,
+			message: message.into(),
+		}
+	}
+
+	/// The 1-based column of the start of the offending text, in characters
+	pub fn column(&self) -> usize {
+		self.text.get(..self.span.start).map_or(self.span.start, |prefix| prefix.chars().count()) + 1
+	}
+}
+
+impl std::fmt::Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if let Some(input) = &self.input {
+			write!(f, "{input}:")?;
+		}
+		if let Some(line) = self.line {
+			write!(f, "{line}:{}:", self.column())?;
+		}
+		if self.input.is_some() || self.line.is_some() {
+			f.write_str(" ")?;
+		}
+		writeln!(f, "{}", self.message)?;
+
+		let line = self.line.map_or_else(String::new, |line| line.to_string());
+		let width = line.len();
+		let underline_len = self.text.get(self.span.clone()).map_or(0, |s| s.chars().count()).max(1);
+
+		writeln!(f, "{:width$} |", "")?;
+		writeln!(f, "{line} | {}", self.text)?;
+		write!(f, "{:width$} | {:indent$}{:^<underline_len$}", "", "", "", indent = self.column() - 1)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+/// An iterator that parses each line of a reader as a `T`, with trailing whitespace removed.
+///
+/// Errors in parsing a line are located at that line as [`ParseError`]s.
+pub struct Lines<T> {
+	inner: Box<dyn std::io::BufRead>,
+	buf: String,
+	line: usize,
+	_ty: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> Lines<T> {
+	/// Parses the lines of the given reader
+	pub fn new(inner: impl std::io::BufRead + 'static) -> Self {
+		Lines {
+			inner: Box::new(inner),
+			buf: String::new(),
+			line: 0,
+			_ty: Default::default(),
+		}
+	}
+}
+
+impl<T> Iterator for Lines<T> where T: std::str::FromStr, <T as std::str::FromStr>::Err: Into<Error> {
+	type Item = Result<T, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		use std::io::BufRead;
+
+		self.buf.clear();
+
+		let read = match self.inner.read_line(&mut self.buf) {
+			Ok(read) => read,
+			Err(err) => return Some(Err(err.into())),
+		};
+		if read == 0 {
+			return None;
+		}
+
+		self.line += 1;
+
+		let buf = self.buf.trim_end();
+
+		let value: T = match buf.parse() {
+			Ok(value) => value,
+			Err(err) => return Some(Err(err.into().at(buf, 0..buf.len()).at_line(self.line))),
+		};
+
+		Some(Ok(value))
+	}
+}
+
+/// Reads the whole of the given reader into a string.
+pub fn read_to_string(mut input: impl std::io::BufRead) -> Result<String, Error> {
+	let mut s = String::new();
+	let _ = input.read_to_string(&mut s)?;
+	Ok(s)
+}
+
+/// Reads the given reader as a grid of bytes, one row per line.
+///
+/// Unlike [`Lines`], only the line terminator is removed from each row, so trailing spaces are kept.
+/// Rows are not required to have the same length.
+pub fn read_grid(input: impl std::io::BufRead) -> Result<Vec<Vec<u8>>, Error> {
+	input.split(b'\n')
+		.map(|row| {
+			let mut row = row?;
+			if row.last() == Some(&b'\r') {
+				let _ = row.pop();
+			}
+			Ok(row)
+		})
+		.collect()
+}
+
+/// A comma-separated list of values, eg `1,2,3`. Whitespace around each value is ignored.
+///
+/// It can be parsed from one line with [`Lines`], or from a whole input with [`read_to_string`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommaSeparated<T>(pub Vec<T>);
+
+impl<T> std::str::FromStr for CommaSeparated<T> where T: std::str::FromStr, <T as std::str::FromStr>::Err: Into<Error> {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.trim().is_empty() {
+			return Ok(CommaSeparated(vec![]));
+		}
+
+		let mut values = vec![];
+
+		// Byte offset of the current token
+		let mut start = 0;
+
+		for token in s.split(',') {
+			let value = token.trim_start();
+			let value_start = start + (token.len() - value.len());
+			let value = value.trim_end();
+
+			let value = value.parse().map_err(|err: T::Err| err.into().at(s, value_start..(value_start + value.len())))?;
+			values.push(value);
+
+			start += token.len() + 1;
+		}
+
+		Ok(CommaSeparated(values))
+	}
+}
+
+// Parses the given example the same way that a day's input file is parsed
+#[cfg(test)]
+fn parse_str<S>(s: &str) -> Result<S::Input, Error> where S: Solution {
+	S::parse(Box::new(std::io::Cursor::new(s.to_owned())))
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_readers() {
+		let lines: Result<Vec<super::CommaSeparated<i64>>, _> = super::Lines::new(&b"1,2, 3\r\n\n-4\n"[..]).collect();
+		assert_eq!(lines.unwrap(), [super::CommaSeparated(vec![1, 2, 3]), super::CommaSeparated(vec![]), super::CommaSeparated(vec![-4])]);
+
+		assert_eq!(
+			super::read_to_string(&b"1,x"[..]).unwrap().parse::<super::CommaSeparated<i64>>().unwrap_err().to_string(),
+			"invalid digit found in string\n |\n | 1,x\n |   ^",
+		);
+
+		assert_eq!(super::read_grid(&b"#.# \r\n.#\n"[..]).unwrap(), [&b"#.# "[..], &b".#"[..]]);
+
+		assert_eq!(super::read_to_string(std::io::Cursor::new("a\nb\n")).unwrap(), "a\nb\n");
+	}
+
+	#[test]
+	fn test_parse_error() {
+		let err = super::Lines::<super::CommaSeparated<i64>>::new(&b"1,2\n3, x4 ,5\n"[..]).nth(1).unwrap().unwrap_err().in_input("day2");
+		let parse_err = err.0.downcast_ref::<super::ParseError>().unwrap();
+		assert_eq!(*parse_err, super::ParseError {
+			input: Some("day2".to_owned()),
+			line: Some(2),
+			text: "3, x4 ,5".to_owned(),
+			span: 3..5,
+			message: "invalid digit found in string".to_owned(),
+		});
+		assert_eq!(parse_err.column(), 4);
+		assert_eq!(err.to_string(), "\
+day2:2:4: invalid digit found in string
+  |
+2 | 3, x4 ,5
+  |    ^^");
+
+		let err = super::Error::from(super::ParseError::new("", 0..0, "file is empty"));
+		assert_eq!(err.to_string(), "file is empty\n |\n | \n | ^");
+	}
+}
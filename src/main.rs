@@ -1,149 +1,667 @@
 #![deny(rust_2018_idioms, warnings)]
 #![deny(clippy::all, clippy::pedantic)]
-#![allow(
-	clippy::default_trait_access,
-	clippy::let_and_return,
-	clippy::let_underscore_drop,
-	clippy::naive_bytecount,
-	clippy::too_many_arguments,
-	clippy::too_many_lines,
-	clippy::type_complexity,
-	clippy::unreadable_literal,
-)]
-
-mod intcode;
-
-macro_rules! main {
-	($($mod:ident ,)*) => {
-		main! {
-			@inner
-			{}
-			{}
-			[$($mod,)*]
-		}
+
+const USAGE: &str = "\
+usage: aoc2019 [--day <days>] [--part <part>] [--input <path>] [--verify | --record] [--answers <path>]
+               [--time] [--repeat <n>] [--time-json <path>] [--format <format>] [--vis] [--fps <n>]
+               [--vis-out <dir>] [--vis-format <format>] [--vis-record <dir>]
+               [--intcode-diff <format>] [--transcript <path>] [--strings]
+
+    --day <days>      The days to run, as a comma-separated list of days and ranges, eg `7` or `1-5,9`. Defaults to all days.
+    --part <part>     The part to run, `a` or `b`. Defaults to both.
+    --input <path>    Read the day's input from this file instead of the inputs directory, or from stdin if it's `-`.
+                      Requires exactly one day.
+    --verify          Compare each answer to the answers manifest, and fail if any don't match.
+    --record          Save each answer to the answers manifest.
+    --answers <path>  The answers manifest. Defaults to `inputs/answers.json`.
+    --time            Print a table of the time taken to parse the input and to run each part of each day.
+                      Days are run one at a time when timing them, and in parallel otherwise.
+    --repeat <n>      Run each day this many times and report the minimum and median times. Implies --time.
+                      Answers are only printed, verified and recorded for the first run.
+    --time-json <path>
+                      Also write the times to this file as JSON.
+    --format <format> `text` to print answers for humans, or `json` to print a JSON record of each answer on its own line.
+                      In JSON mode, a day that errors is also printed as a record,
+                      and everything besides records is printed to stderr. Defaults to `text`.
+    --vis             Visualize the days that support it in the terminal while they're solved. Days 8, 11, 13, 15, 17 and 24
+                      render their grids, day 21 prints the springdroid's output, and day 25 is played interactively.
+                      Days are run one at a time when visualizing them in the terminal.
+    --fps <n>         The maximum frame rate of visualizations, or 0 for no limit. Defaults to 100. Implies --vis.
+                      For animated images, this is the frame rate of the animation instead.
+    --vis-out <dir>   Write the grids of visualizations to image files in this directory instead of the terminal,
+                      eg `day13b.gif`. Implies --vis.
+    --vis-format <format>
+                      The format of the image files. `ppm` or `png` write an image of every frame, `gif` writes
+                      an animation of all the frames, and `svg` writes an image of the final frame. Defaults to `gif`.
+                      Implies --vis-out, which defaults to the `vis` directory.
+    --vis-record <dir>
+                      Also record what's shown in the terminal as asciicast files in this directory, eg `day13b.cast`,
+                      which can be replayed with `asciinema play`. Implies --vis, and can't be combined with --vis-out.
+    --intcode-diff <format>
+                      While playing day 25 in the terminal, also print which memory cells each command changed,
+                      as `text` or `json`. Implies --vis, and can't be combined with --vis-out.
+    --transcript <path>
+                      Save the Intcode I/O of playing day 25 in the terminal to this file, so that it can be replayed
+                      as a regression test. Implies --vis, and can't be combined with --vis-out.
+    --strings         Print the room names, descriptions, items and messages embedded in day 25's program before playing it
+                      in the terminal. Implies --vis, and can't be combined with --vis-out.
+
+A day that errors or panics doesn't stop the other days from running. The run ends with a summary of the days that passed,
+failed verification or errored, along with the backtraces of the errors, and fails if any day failed or errored.
+";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+	Text,
+	Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mode {
+	Print,
+	Verify,
+	Record,
+}
+
+struct Args {
+	days: Vec<u32>,
+	context: aoc2019::Context,
+	mode: Mode,
+	answers: std::path::PathBuf,
+	time: bool,
+	repeat: usize,
+	time_json: Option<std::path::PathBuf>,
+	format: Format,
+}
+
+fn main() -> Result<(), aoc2019::Error> {
+	let Args { days, context, mode, answers: manifest_path, time, repeat, time_json, format } = match parse_args(std::env::args().skip(1)) {
+		Ok(Some(args)) => args,
+
+		Ok(None) => {
+			print!("{USAGE}");
+			return Ok(());
+		},
+
+		Err(err) => {
+			eprintln!("{err}");
+			eprintln!();
+			eprint!("{USAGE}");
+			std::process::exit(2);
+		},
 	};
 
-	(@inner { $($mods:tt)* } { $($calls:tt)* } []) => {
-		$($mods)*
+	let mut manifest = match mode {
+		Mode::Print => aoc2019::answers::Manifest::default(),
+		Mode::Verify | Mode::Record => aoc2019::answers::Manifest::load(&manifest_path)?,
+	};
+
+	let num_days = days.len();
+	let in_terminal = matches!(context.vis, Some(aoc2019::vis::Options { target: aoc2019::vis::Target::Terminal, .. }));
+	let outcomes = run_days(&days, &context, repeat, time || in_terminal);
+
+	let mut num_passed = 0;
+	let mut num_failed = 0;
+	let mut num_unknown = 0;
+
+	let mut failed_days = vec![];
+	let mut errored_days = vec![];
+
+	let mut samples = aoc2019::timing::Samples::default();
 
-		fn main() -> Result<(), Error> {
-			$($calls)*
+	for (day, outcome) in days.into_iter().zip(outcomes) {
+		let (answers, error) = match outcome {
+			Outcome::Ran { report, repeats } => {
+				samples.add(day, &report);
+				for repeat in &repeats {
+					samples.add(day, repeat);
+				}
 
-			Ok(())
+				(report.answers, report.error.map(|(part, err)| DayError::Error(part, err)))
+			},
+
+			Outcome::Panicked(panic) => (vec![], Some(DayError::Panic(panic))),
+		};
+
+		let mut failures = vec![];
+
+		for answer in &answers {
+			let check = match mode {
+				Mode::Print => None,
+
+				Mode::Verify => {
+					let status = manifest.check(answer);
+					match &status {
+						aoc2019::answers::Status::Pass => num_passed += 1,
+						aoc2019::answers::Status::Fail { expected } => {
+							num_failed += 1;
+							let value = answer.value.to_string();
+							failures.push(format!("{day}{} was {value:?}, expected {expected:?}", answer.part));
+						},
+						aoc2019::answers::Status::Unknown => num_unknown += 1,
+					}
+					Some(Check::Verified(status))
+				},
+
+				Mode::Record => Some(Check::Recorded { previous: manifest.record(answer) }),
+			};
+
+			match format {
+				Format::Text => print_answer(day, answer, check.as_ref()),
+				Format::Json => println!("{}", answer_record(day, answer, check.as_ref())),
+			}
+		}
+
+		if let Some(error) = error {
+			match format {
+				Format::Text => println!("{day}{}: error: {}", error.part().map_or_else(String::new, |part| part.to_string()), error.message()),
+
+				Format::Json => println!("{}", serde_json::json!({
+					"day": day,
+					"part": error.part().map(|part| part.to_string()),
+					"error": error.message(),
+					"backtrace": error.backtrace(),
+				})),
+			}
+
+			errored_days.push((day, error));
+		}
+		else if !failures.is_empty() {
+			failed_days.push((day, failures));
 		}
+	}
+
+	// Only records are written to stdout in JSON mode, so everything else goes to stderr.
+	let mut summary: Box<dyn std::io::Write> = match format {
+		Format::Text => Box::new(std::io::stdout()),
+		Format::Json => Box::new(std::io::stderr()),
 	};
 
-	(@inner { $($mods:tt)* } { $($calls:tt)* } [$first:ident , $($rest:ident ,)*]) => {
-		main! {
-			@inner
-			{ $($mods)* mod $first; }
-			{ $($calls)* $first::run()?; }
-			[$($rest ,)*]
+	if time {
+		writeln!(summary)?;
+		write!(summary, "{samples}")?;
+	}
+
+	if let Some(path) = time_json {
+		std::fs::write(&path, samples.to_json()).map_err(|err| format!("could not write {}: {err}", path.display()))?;
+	}
+
+	if mode == Mode::Record {
+		manifest.save(&manifest_path)?;
+	}
+
+	let verified = if mode == Mode::Verify { Some((num_passed, num_failed, num_unknown)) } else { None };
+	write_summary(&mut summary, num_days, verified, &failed_days, &errored_days)?;
+
+	if !failed_days.is_empty() || !errored_days.is_empty() {
+		std::process::exit(1);
+	}
+
+	Ok(())
+}
+
+// Writes how many answers passed verification if they were verified, how many days passed, failed or errored,
+// and the failed answers and errors of the days that didn't pass
+fn write_summary(
+	summary: &mut dyn std::io::Write,
+	num_days: usize,
+	verified: Option<(usize, usize, usize)>,
+	failed_days: &[(u32, Vec<String>)],
+	errored_days: &[(u32, DayError)],
+) -> std::io::Result<()> {
+	writeln!(summary)?;
+
+	if let Some((num_passed, num_failed, num_unknown)) = verified {
+		writeln!(summary, "answers: {num_passed} passed, {num_failed} failed, {num_unknown} unknown")?;
+	}
+
+	writeln!(
+		summary,
+		"days: {} passed, {} failed, {} errored",
+		num_days - failed_days.len() - errored_days.len(),
+		failed_days.len(),
+		errored_days.len(),
+	)?;
+
+	for (day, failures) in failed_days {
+		writeln!(summary)?;
+		writeln!(summary, "day {day} failed:")?;
+		for failure in failures {
+			writeln!(summary, "    {failure}")?;
+		}
+	}
+
+	for (day, error) in errored_days {
+		writeln!(summary)?;
+		match error.part() {
+			Some(part) => writeln!(summary, "day {day} errored in part {part}: {}", error.message())?,
+			None => writeln!(summary, "day {day} errored: {}", error.message())?,
 		}
+		write!(summary, "{}", error.backtrace())?;
 	}
+
+	Ok(())
+}
+
+// The result of running a day, along with the reports of the repeated runs for timing it
+enum Outcome {
+	Ran {
+		report: aoc2019::Report,
+		repeats: Vec<aoc2019::Report>,
+	},
+
+	Panicked(Panic),
 }
 
-main! {
-	day25,
-	day24,
-	day23,
-	day22,
-	day21,
-	day20,
-	day19,
-	day18,
-	day17,
-	day16,
-	day15,
-	day14,
-	day13,
-	day12,
-	day11,
-	day10,
-	day9,
-	day8,
-	day7,
-	day6,
-	day5,
-	day4,
-	day3,
-	day2,
-	day1,
+struct Panic {
+	message: String,
+	backtrace: Option<backtrace::Backtrace>,
 }
 
-fn read_input_lines<T>(filename: &str) -> Result<impl Iterator<Item = Result<T, Error>>, Error> where T: std::str::FromStr, <T as std::str::FromStr>::Err: Into<Error> {
-	let mut path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).to_owned();
-	path.push("inputs");
-	path.push(filename);
-	let inner = std::io::BufReader::new(std::fs::File::open(path)?);
-	Ok(Lines::new(inner))
+// Why a day didn't finish
+enum DayError {
+	Error(Option<aoc2019::Part>, aoc2019::Error),
+	Panic(Panic),
 }
 
-struct Error(Box<dyn std::error::Error>, backtrace::Backtrace);
+impl DayError {
+	fn part(&self) -> Option<aoc2019::Part> {
+		match self {
+			DayError::Error(part, _) => *part,
+			DayError::Panic(_) => None,
+		}
+	}
+
+	fn message(&self) -> String {
+		match self {
+			DayError::Error(_, err) => err.to_string(),
+			DayError::Panic(panic) => format!("panicked at {}", panic.message),
+		}
+	}
+
+	fn backtrace(&self) -> String {
+		match self {
+			DayError::Error(_, err) => format!("{:?}", err.backtrace()),
+			DayError::Panic(Panic { backtrace: Some(backtrace), .. }) => format!("{backtrace:?}"),
+			DayError::Panic(Panic { backtrace: None, .. }) => "no backtrace".to_owned(),
+		}
+	}
+}
 
-impl std::fmt::Debug for Error {
-	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		writeln!(f, "{}", self.0)?;
+// Runs the given days on the rayon thread pool, with each day's panics caught and reported as its outcome.
+//
+// When timing or visualizing in the terminal, the days are run one at a time instead so that they don't compete with each other for the CPU or the terminal.
+fn run_days(days: &[u32], context: &aoc2019::Context, repeat: usize, sequential: bool) -> Vec<Outcome> {
+	use rayon::prelude::*;
 
-		let mut source = self.0.source();
-		while let Some(err) = source {
-			writeln!(f, "caused by: {err}")?;
-			source = err.source();
+	// Panics outside of the days, like while printing their results, go to the default hook as usual
+	let previous_hook = std::panic::take_hook();
+	let panics = catch_panics();
+
+	let run_day = |&day: &u32| {
+		let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| -> Result<_, aoc2019::Error> {
+			let report = aoc2019::run(day, context)?;
+			let repeats = (1..repeat).map(|_| aoc2019::run(day, context)).collect::<Result<_, _>>()?;
+			Ok((report, repeats))
+		}));
+
+		match result {
+			Ok(Ok((report, repeats))) => Outcome::Ran { report, repeats },
+
+			// The days were validated when parsing arguments
+			Ok(Err(err)) => unreachable!("{err}"),
+
+			Err(payload) => Outcome::Panicked(panics.take(&*payload)),
 		}
+	};
+
+	let outcomes =
+		if sequential {
+			days.iter().map(run_day).collect()
+		}
+		else {
+			days.par_iter().map(run_day).collect()
+		};
+
+	std::panic::set_hook(previous_hook);
 
-		writeln!(f)?;
+	outcomes
+}
+
+// Panics that were caught by the panic hook, so that they can be reported with their backtraces when their days finish.
+//
+// A panic in a rayon job is caught on the thread that the job ran on and then resumed on the thread that's waiting for it,
+// so a panic is matched by its thread if possible and otherwise by its message.
+#[derive(Clone, Default)]
+struct Panics(std::sync::Arc<std::sync::Mutex<Vec<(std::thread::ThreadId, String, Panic)>>>);
 
-		writeln!(f, "{:?}", self.1)?;
+fn catch_panics() -> Panics {
+	let panics = Panics::default();
 
-		Ok(())
+	let hook_panics = panics.clone();
+	std::panic::set_hook(Box::new(move |info| {
+		let payload = panic_payload(info.payload());
+		let message = match info.location() {
+			Some(location) => format!("{payload:?}, {location}"),
+			None => format!("{payload:?}"),
+		};
+		let panic = Panic { message, backtrace: Some(backtrace::Backtrace::new()) };
+		hook_panics.0.lock().unwrap().push((std::thread::current().id(), payload, panic));
+	}));
+
+	panics
+}
+
+impl Panics {
+	fn take(&self, payload: &(dyn std::any::Any + Send)) -> Panic {
+		let payload = panic_payload(payload);
+
+		let mut panics = self.0.lock().unwrap();
+		let thread_id = std::thread::current().id();
+		let i =
+			panics.iter().position(|(panic_thread_id, panic_payload, _)| *panic_thread_id == thread_id && *panic_payload == payload)
+			.or_else(|| panics.iter().position(|(_, panic_payload, _)| *panic_payload == payload));
+		match i {
+			Some(i) => panics.remove(i).2,
+			None => Panic { message: format!("{payload:?}"), backtrace: None },
+		}
 	}
 }
 
-impl<E> From<E> for Error where E: Into<Box<dyn std::error::Error>> {
-	fn from(err: E) -> Self {
-		Error(err.into(), Default::default())
+fn panic_payload(payload: &(dyn std::any::Any + Send)) -> String {
+	if let Some(s) = payload.downcast_ref::<&str>() {
+		(*s).to_owned()
+	}
+	else if let Some(s) = payload.downcast_ref::<String>() {
+		s.clone()
+	}
+	else {
+		"Box<dyn Any>".to_owned()
 	}
 }
 
-struct Lines<T> {
-	inner: std::io::BufReader<std::fs::File>,
-	buf: String,
-	_ty: std::marker::PhantomData<fn() -> T>,
+// How an answer compares to the answers manifest
+enum Check {
+	Verified(aoc2019::answers::Status),
+	Recorded { previous: Option<String> },
 }
 
-impl<T> Lines<T> {
-	fn new(inner: std::io::BufReader<std::fs::File>) -> Self {
-		Lines {
-			inner,
-			buf: String::new(),
-			_ty: Default::default(),
+// Multi-line answers like images are printed indented on the lines after the header
+fn print_answer(day: u32, answer: &aoc2019::Answer, check: Option<&Check>) {
+	let value = answer.value.to_string();
+
+	let status = match check {
+		None => String::new(),
+		Some(Check::Verified(aoc2019::answers::Status::Pass)) => " (pass)".to_owned(),
+		Some(Check::Verified(aoc2019::answers::Status::Fail { expected })) => format!(" (FAIL, expected {expected:?})"),
+		Some(Check::Verified(aoc2019::answers::Status::Unknown)) => " (unknown)".to_owned(),
+		Some(Check::Recorded { previous: Some(previous) }) if *previous == value => " (unchanged)".to_owned(),
+		Some(Check::Recorded { previous: Some(previous) }) => format!(" (recorded, was {previous:?})"),
+		Some(Check::Recorded { previous: None }) => " (recorded)".to_owned(),
+	};
+
+	if value.contains('\n') {
+		println!("{day}{}:{status}", answer.part);
+		for line in value.lines() {
+			println!("    {line}");
 		}
 	}
+	else {
+		println!("{day}{}: {value}{status}", answer.part);
+	}
+}
+
+// Image answers have their pixels as one string of 0s and 1s per row, along with the decoded text if it could be recognized.
+fn answer_record(day: u32, answer: &aoc2019::Answer, check: Option<&Check>) -> serde_json::Value {
+	let value = answer.value.to_string();
+
+	let mut record = serde_json::json!({
+		"day": day,
+		"part": answer.part.to_string(),
+		"input": answer.input,
+		"answer": value,
+		"type": answer.value.kind(),
+		"time_ns": u64::try_from(answer.time.as_nanos()).unwrap_or(u64::MAX),
+		"error": null,
+	});
+
+	if let aoc2019::Value::Image { image, text } = &answer.value {
+		let pixels: Vec<String> = image.rows().map(|row| row.iter().map(|&pixel| if pixel { '1' } else { '0' }).collect()).collect();
+		record["image"] = serde_json::json!({
+			"width": image.width(),
+			"height": image.height(),
+			"pixels": pixels,
+			"text": text,
+		});
+	}
+
+	match check {
+		None => (),
+
+		Some(Check::Verified(status)) => {
+			record["check"] = match status {
+				aoc2019::answers::Status::Pass => "pass",
+				aoc2019::answers::Status::Fail { .. } => "fail",
+				aoc2019::answers::Status::Unknown => "unknown",
+			}.into();
+			if let aoc2019::answers::Status::Fail { expected } = status {
+				record["expected"] = expected.clone().into();
+			}
+		},
+
+		Some(Check::Recorded { previous }) => {
+			record["check"] = match previous {
+				Some(previous) if *previous == value => "unchanged",
+				_ => "recorded",
+			}.into();
+			if let Some(previous) = previous {
+				record["previous"] = previous.clone().into();
+			}
+		},
+	}
+
+	record
 }
 
-impl<T> Iterator for Lines<T> where T: std::str::FromStr, <T as std::str::FromStr>::Err: Into<Error> {
-	type Item = Result<T, Error>;
+// Returns `None` if help was requested
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Args>, aoc2019::Error> {
+	let mut days = None;
+	let mut context = aoc2019::Context::default();
+	let mut mode = Mode::Print;
+	let mut answers = None;
+	let mut time = false;
+	let mut repeat = 1;
+	let mut time_json = None;
+	let mut format = Format::Text;
+	let mut vis_out: Option<std::path::PathBuf> = None;
+	let mut vis_format = None;
+
+	let mut args = args.into_iter();
+	while let Some(arg) = args.next() {
+		let mut value = || args.next().ok_or_else(|| format!("{arg} requires a value"));
+
+		match &*arg {
+			"--day" => days = Some(parse_days(&value()?)?),
+
+			"--part" => context.part = Some(value()?.parse()?),
+
+			"--input" => context.input = Some(match &*value()? {
+				"-" => {
+					let mut bytes = vec![];
+					std::io::Read::read_to_end(&mut std::io::stdin(), &mut bytes)?;
+					aoc2019::Input::Bytes(bytes)
+				},
+
+				path => aoc2019::Input::Path(path.into()),
+			}),
+
+			"--verify" | "--record" if mode != Mode::Print => return Err("only one of --verify and --record can be specified".into()),
+			"--verify" => mode = Mode::Verify,
+			"--record" => mode = Mode::Record,
+
+			"--answers" => answers = Some(value()?.into()),
+
+			"--time" => time = true,
+
+			"--repeat" => {
+				let value = value()?;
+				repeat = match value.parse() {
+					Ok(0) | Err(_) => return Err(format!("invalid number of runs {value:?}").into()),
+					Ok(repeat) => repeat,
+				};
+				time = true;
+			},
+
+			"--time-json" => time_json = Some(value()?.into()),
+
+			"--format" => format = match &*value()? {
+				"text" => Format::Text,
+				"json" => Format::Json,
+				value => return Err(format!("invalid format {value:?}, expected text or json").into()),
+			},
+
+			"--vis" => if context.vis.is_none() {
+				context.vis = Some(aoc2019::vis::Options::default());
+			},
 
-	fn next(&mut self) -> Option<Self::Item> {
-		use std::io::BufRead;
+			"--fps" => {
+				let value = value()?;
+				let frame_rate = value.parse().map_err(|err| format!("invalid frame rate {value:?}: {err}"))?;
+				context.vis.get_or_insert_with(aoc2019::vis::Options::default).frame_rate = frame_rate;
+			},
 
-		self.buf.clear();
+			"--vis-out" => vis_out = Some(value()?.into()),
 
-		let read = match self.inner.read_line(&mut self.buf) {
-			Ok(read) => read,
-			Err(err) => return Some(Err(err.into())),
+			"--vis-format" => vis_format = Some(value()?.parse()?),
+
+			"--vis-record" => context.vis.get_or_insert_with(aoc2019::vis::Options::default).record = Some(value()?.into()),
+
+			"--intcode-diff" => context.vis.get_or_insert_with(aoc2019::vis::Options::default).intcode_diff = Some(value()?.parse()?),
+
+			"--transcript" => context.vis.get_or_insert_with(aoc2019::vis::Options::default).transcript = Some(value()?.into()),
+
+			"--strings" => context.vis.get_or_insert_with(aoc2019::vis::Options::default).strings = true,
+
+			"-h" | "--help" => return Ok(None),
+
+			arg => return Err(format!("unexpected argument {arg:?}").into()),
+		}
+	}
+
+	let days = days.unwrap_or_else(|| aoc2019::DAYS.iter().map(|day| day.day).collect());
+
+	if context.input.is_some() && days.len() != 1 {
+		return Err("--input requires exactly one day".into());
+	}
+
+	let answers = answers.unwrap_or_else(aoc2019::answers::Manifest::default_path);
+
+	if vis_out.is_some() || vis_format.is_some() {
+		if matches!(context.vis, Some(aoc2019::vis::Options { record: Some(_), .. })) {
+			return Err("--vis-record records the terminal, so it can't be combined with --vis-out or --vis-format".into());
+		}
+
+		if matches!(context.vis, Some(aoc2019::vis::Options { intcode_diff: Some(_), .. } | aoc2019::vis::Options { transcript: Some(_), .. } | aoc2019::vis::Options { strings: true, .. })) {
+			return Err("--intcode-diff, --transcript and --strings are for playing day 25 in the terminal, so they can't be combined with --vis-out or --vis-format".into());
+		}
+
+		context.vis.get_or_insert_with(aoc2019::vis::Options::default).target = aoc2019::vis::Target::Images {
+			dir: vis_out.unwrap_or_else(|| "vis".into()),
+			format: vis_format.unwrap_or(aoc2019::vis::headless::Format::Gif),
+		};
+	}
+
+	Ok(Some(Args { days, context, mode, answers, time, repeat, time_json, format }))
+}
+
+fn parse_days(s: &str) -> Result<Vec<u32>, aoc2019::Error> {
+	let mut result = vec![];
+
+	for range in s.split(',') {
+		let (start, end) = match range.split_once('-') {
+			Some((start, end)) => (start, end),
+			None => (range, range),
 		};
-		if read == 0 {
-			return None;
+
+		let start: u32 = start.trim().parse().map_err(|err| format!("invalid day {start:?}: {err}"))?;
+		let end: u32 = end.trim().parse().map_err(|err| format!("invalid day {end:?}: {err}"))?;
+
+		for day in [start, end] {
+			let _ = aoc2019::day(day)?;
+		}
+
+		if start > end {
+			return Err(format!("invalid range {range:?}").into());
 		}
 
-		let buf = self.buf.trim_end();
+		result.extend(start..=end);
+	}
+
+	Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_parse_days() {
+		fn test(s: &str, expected: Result<&[u32], &str>) {
+			let actual = super::parse_days(s);
+			match (expected, actual) {
+				(Ok(expected), Ok(actual)) => assert_eq!(expected, actual),
+				(Err(expected_err), Err(actual_err)) => assert_eq!(expected_err, actual_err.to_string()),
+				(expected, actual) => panic!("expected {expected:?} but got {actual:?}"),
+			}
+		}
 
-		let value: T = match buf.parse() {
-			Ok(value) => value,
-			Err(err) => return Some(Err(err.into())),
+		test("7", Ok(&[7]));
+		test("1-5,9", Ok(&[1, 2, 3, 4, 5, 9]));
+		test("25,1-2", Ok(&[25, 1, 2]));
+		test("5-3", Err(r#"invalid range "5-3""#));
+		test("0", Err("there is no day 0"));
+		test("1-26", Err("there is no day 26"));
+		test("x", Err(r#"invalid day "x": invalid digit found in string"#));
+	}
+
+	#[test]
+	fn test_answer_record() {
+		let image = aoc2019::Image::new(vec![vec![true, false], vec![false, true]]).unwrap();
+		let answer = aoc2019::Answer {
+			part: aoc2019::Part::B,
+			input: "day8".to_owned(),
+			value: aoc2019::Value::from_image(image),
+			time: std::time::Duration::from_micros(5),
 		};
+		let check = super::Check::Verified(aoc2019::answers::Status::Fail { expected: "x".to_owned() });
+
+		assert_eq!(super::answer_record(8, &answer, Some(&check)), serde_json::json!({
+			"day": 8,
+			"part": "b",
+			"input": "day8",
+			"answer": "\u{2588}\u{2588}  \n  \u{2588}\u{2588}",
+			"type": "image",
+			"image": {
+				"width": 2,
+				"height": 2,
+				"pixels": ["10", "01"],
+				"text": null,
+			},
+			"time_ns": 5000,
+			"check": "fail",
+			"expected": "x",
+			"error": null,
+		}));
+
+		// An image of letters has its text too
+		let image = aoc2019::Image::new(["#...", "#...", "#...", "#...", "#...", "####"].iter().map(|row| row.bytes().map(|b| b == b'#'))).unwrap();
+		let answer = aoc2019::Answer { value: aoc2019::Value::from_image(image), ..answer };
+		let record = super::answer_record(8, &answer, None);
+		assert_eq!(record["answer"], "L");
+		assert_eq!(record["image"]["text"], "L");
+		assert_eq!(record["image"]["pixels"][5], "1111");
 
-		Some(Ok(value))
+		assert_eq!(
+			aoc2019::Image::new(vec![vec![true, false], vec![false]]).unwrap_err().to_string(),
+			"image rows have different widths",
+		);
 	}
 }
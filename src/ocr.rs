@@ -0,0 +1,179 @@
+//! Recognizes the block letters that some days draw their answers in.
+//!
+//! Advent of Code draws letters in one of two fonts: a small one that's 6 pixels high, with each letter in a 5 pixel wide cell,
+//! and a large one that's 10 pixels high, with each letter in an 8 pixel wide cell. Most letters leave the last columns
+//! of their cell blank to separate them from the next one.
+//!
+//! ```text
+//!   ##  ###   ##  ####
+//!  #  # #  # #  # #
+//!  #  # ###  #    ###
+//!  #### #  # #    #
+//!  #  # #  # #  # #
+//!  #  # ###   ##  ####
+//! ```
+
+/// Recognizes the letters in the image, or returns `None` if any of them isn't a letter of either font.
+///
+/// The image can have blank rows and columns around the letters.
+pub fn recognize(image: &crate::Image) -> Option<String> {
+	let rows: Vec<&[bool]> = image.rows().collect();
+
+	let top = rows.iter().position(|row| row.contains(&true))?;
+	let bottom = rows.iter().rposition(|row| row.contains(&true))? + 1;
+	let rows = &rows[top..bottom];
+
+	let font = FONTS.iter().find(|font| font.height == rows.len())?;
+
+	let left = (0..image.width()).find(|&x| rows.iter().any(|row| row[x]))?;
+	let right = (0..image.width()).rev().find(|&x| rows.iter().any(|row| row[x]))? + 1;
+
+	// Some letters start with a blank column, like I, so the first letter's cell can start before the first column that isn't blank
+	(0..font.cell_width)
+		.filter_map(|blank| left.checked_sub(blank))
+		.find_map(|start| font.recognize(rows, start..right))
+}
+
+struct Font {
+	height: usize,
+	cell_width: usize,
+	letters: &'static [(char, &'static [&'static str])],
+}
+
+impl Font {
+	// Recognizes the letters in the cells that cover the given columns
+	fn recognize(&self, rows: &[&[bool]], cols: std::ops::Range<usize>) -> Option<String> {
+		cols.step_by(self.cell_width)
+		.map(|x| {
+			let &(letter, _) = self.letters.iter().find(|(_, glyph)| self.matches(glyph, rows, x))?;
+			Some(letter)
+		})
+		.collect()
+	}
+
+	// Whether the cell starting at the given column has the given glyph. Columns past the glyph and past the image are blank.
+	fn matches(&self, glyph: &[&str], rows: &[&[bool]], x: usize) -> bool {
+		rows.iter().zip(glyph).all(|(row, glyph_row)| {
+			(0..self.cell_width).all(|i| row.get(x + i).copied().unwrap_or(false) == (glyph_row.as_bytes().get(i) == Some(&b'#')))
+		})
+	}
+}
+
+const FONTS: [Font; 2] = [
+	Font {
+		height: 6,
+		cell_width: 5,
+		letters: &[
+			('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+			('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+			('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+			('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+			('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+			('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+			('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+			('I', &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+			('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+			('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+			('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+			('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+			('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+			('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+			('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+			('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+			('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+			('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+		],
+	},
+
+	Font {
+		height: 10,
+		cell_width: 8,
+		letters: &[
+			('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+			('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+			('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+			('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+			('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+			('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+			('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+			('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+			('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+			('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+			('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+			('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+			('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+			('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+			('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+		],
+	},
+];
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_recognize() {
+		fn test(rows: &[&str], expected: Option<&str>) {
+			let image = crate::Image::new(rows.iter().map(|row| row.bytes().map(|b| b == b'#'))).unwrap();
+			let actual = super::recognize(&image);
+			assert_eq!(expected, actual.as_deref());
+		}
+
+		test(
+			&[
+				"#..#.#...##..#.####.####.",
+				"#.#..#...##..#.#....#....",
+				"##....#.#.####.###..###..",
+				"#.#....#..#..#.#....#....",
+				"#.#....#..#..#.#....#....",
+				"#..#...#..#..#.#....####.",
+			],
+			Some("KYHFE"),
+		);
+
+		// Blank rows and columns around the letters, and a first letter that starts with a blank column
+		test(
+			&[
+				"................",
+				"...###.#....###.",
+				"....#..#....#..#",
+				"....#..#....#..#",
+				"....#..#....###.",
+				"....#..#....#...",
+				"...###.####.#...",
+				"................",
+			],
+			Some("ILP"),
+		);
+
+		test(
+			&[
+				"#....#..######..",
+				"#....#..#.......",
+				".#..#...#.......",
+				".#..#...#.......",
+				"..##....#####...",
+				"..##....#.......",
+				".#..#...#.......",
+				".#..#...#.......",
+				"#....#..#.......",
+				"#....#..######..",
+			],
+			Some("XE"),
+		);
+
+		// Not letters
+		test(&["#.#", ".#.", "#.#"], None);
+		test(&["....", "...."], None);
+		test(
+			&[
+				"####.####.",
+				"#....#..#.",
+				"###..#..#.",
+				"#....#..#.",
+				"#....#..#.",
+				"####.####.",
+			],
+			None,
+		);
+	}
+}
@@ -0,0 +1,217 @@
+//! Timings of repeated runs of days, summarized as a table or as JSON.
+//!
+//! Each run of a day is split into phases: opening and parsing the input, and each part.
+//! The table shows the minimum and median of each phase over all runs:
+//!
+//! ```text
+//! day                 parse                part a                part b                 total
+//!   3       12.3ms / 12.6ms       1.07µs / 1.42µs       3.25ms / 3.33ms       15.6ms / 15.9ms
+//!   4       30.1µs / 37.6µs       18.7ms / 19.3ms       17.5ms / 18.8ms       36.5ms / 37.5ms
+//! ```
+
+/// A phase of a run of a day
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Phase {
+	/// Opening and parsing the input
+	Parse,
+
+	/// One of the parts
+	Part(crate::Part),
+
+	/// The whole run
+	Total,
+}
+
+impl std::fmt::Display for Phase {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Phase::Parse => f.write_str("parse"),
+			Phase::Part(part) => write!(f, "part {part}"),
+			Phase::Total => f.write_str("total"),
+		}
+	}
+}
+
+/// The timings of every run of every day
+#[derive(Debug, Default)]
+pub struct Samples(std::collections::BTreeMap<(u32, Phase), Vec<std::time::Duration>>);
+
+/// The summary of the timings of one phase of one day
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+	/// The number of runs
+	pub runs: usize,
+
+	/// The fastest run
+	pub min: std::time::Duration,
+
+	/// The median run
+	pub median: std::time::Duration,
+}
+
+#[derive(serde::Serialize)]
+struct Record {
+	day: u32,
+	phase: String,
+	runs: usize,
+	min_ns: u64,
+	median_ns: u64,
+}
+
+impl Samples {
+	/// Adds the timings of one run of the given day
+	pub fn add(&mut self, day: u32, report: &crate::Report) {
+		let mut total = report.parse_time;
+		self.0.entry((day, Phase::Parse)).or_default().push(report.parse_time);
+
+		for answer in &report.answers {
+			total += answer.time;
+			self.0.entry((day, Phase::Part(answer.part))).or_default().push(answer.time);
+		}
+
+		self.0.entry((day, Phase::Total)).or_default().push(total);
+	}
+
+	/// Summarizes the timings of the given phase of the given day, if it was run
+	pub fn stats(&self, day: u32, phase: Phase) -> Option<Stats> {
+		let mut samples = self.0.get(&(day, phase))?.clone();
+		samples.sort_unstable();
+
+		let runs = samples.len();
+		let min = *samples.first()?;
+		let median =
+			if runs % 2 == 0 {
+				(samples[runs / 2 - 1] + samples[runs / 2]) / 2
+			}
+			else {
+				samples[runs / 2]
+			};
+
+		Some(Stats { runs, min, median })
+	}
+
+	/// Renders the summaries of every phase of every day as a JSON array
+	pub fn to_json(&self) -> String {
+		let records: Vec<_> =
+			self.0.keys()
+			.filter_map(|&(day, phase)| {
+				let Stats { runs, min, median } = self.stats(day, phase)?;
+				Some(Record {
+					day,
+					phase: match phase {
+						Phase::Part(part) => part.to_string(),
+						phase => phase.to_string(),
+					},
+					runs,
+					min_ns: std::convert::TryInto::try_into(min.as_nanos()).unwrap_or(u64::MAX),
+					median_ns: std::convert::TryInto::try_into(median.as_nanos()).unwrap_or(u64::MAX),
+				})
+			})
+			.collect();
+		let mut s = serde_json::to_string_pretty(&records).expect("records are always serializable");
+		s.push('\n');
+		s
+	}
+}
+
+// Renders the table of the minimum and median of each phase of each day
+impl std::fmt::Display for Samples {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		const PHASES: [Phase; 4] = [Phase::Parse, Phase::Part(crate::Part::A), Phase::Part(crate::Part::B), Phase::Total];
+
+		write!(f, "day")?;
+		for phase in PHASES {
+			write!(f, " {:>21}", phase.to_string())?;
+		}
+		writeln!(f)?;
+
+		let mut days: Vec<_> = self.0.keys().map(|&(day, _)| day).collect();
+		days.dedup();
+
+		for day in days {
+			write!(f, "{day:>3}")?;
+			for phase in PHASES {
+				match self.stats(day, phase) {
+					Some(Stats { min, median, .. }) => write!(f, " {:>21}", format!("{} / {}", Duration(min), Duration(median)))?,
+					None => write!(f, " {:>21}", "-")?,
+				}
+			}
+			writeln!(f)?;
+		}
+
+		Ok(())
+	}
+}
+
+// Formats a duration with three significant digits in the largest unit that keeps it above 1
+struct Duration(std::time::Duration);
+
+impl std::fmt::Display for Duration {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		#[allow(clippy::cast_precision_loss)]
+		let nanos = self.0.as_nanos() as f64;
+
+		let (value, unit) =
+			if nanos < 1e3 { (nanos, "ns") }
+			else if nanos < 1e6 { (nanos / 1e3, "µs") }
+			else if nanos < 1e9 { (nanos / 1e6, "ms") }
+			else { (nanos / 1e9, "s") };
+
+		let precision =
+			if value < 10. { 2 }
+			else if value < 100. { 1 }
+			else { 0 };
+
+		write!(f, "{value:.precision$}{unit}")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_samples() {
+		fn report(parse_time: u64, part_times: &[(crate::Part, u64)]) -> crate::Report {
+			crate::Report {
+				parse_time: std::time::Duration::from_micros(parse_time),
+				answers: part_times.iter().map(|&(part, time)| crate::Answer {
+					part,
+					input: "day1".to_owned(),
+					value: crate::Value::Number(0),
+					time: std::time::Duration::from_micros(time),
+				}).collect(),
+				error: None,
+			}
+		}
+
+		let mut samples: super::Samples = Default::default();
+		samples.add(1, &report(30, &[(crate::Part::A, 5), (crate::Part::B, 1500)]));
+		samples.add(1, &report(10, &[(crate::Part::A, 7), (crate::Part::B, 1200)]));
+		samples.add(1, &report(20, &[(crate::Part::A, 6), (crate::Part::B, 1100)]));
+		samples.add(1, &report(40, &[(crate::Part::A, 4), (crate::Part::B, 1000)]));
+		samples.add(7, &report(2_000_000, &[(crate::Part::B, 123)]));
+
+		assert_eq!(
+			samples.stats(1, super::Phase::Parse),
+			Some(super::Stats { runs: 4, min: std::time::Duration::from_micros(10), median: std::time::Duration::from_micros(25) }),
+		);
+		assert_eq!(
+			samples.stats(1, super::Phase::Total),
+			Some(super::Stats { runs: 4, min: std::time::Duration::from_micros(1044), median: std::time::Duration::from_nanos(1_171_500) }),
+		);
+		assert_eq!(samples.stats(7, super::Phase::Part(crate::Part::A)), None);
+
+		assert_eq!(
+			samples.to_string(),
+			"\
+day                 parse                part a                part b                 total
+  1       10.0µs / 25.0µs       4.00µs / 5.50µs       1.00ms / 1.15ms       1.04ms / 1.17ms
+  7         2.00s / 2.00s                     -         123µs / 123µs         2.00s / 2.00s
+",
+		);
+
+		let json: serde_json::Value = serde_json::from_str(&samples.to_json()).unwrap();
+		assert_eq!(json[0], serde_json::json!({ "day": 1, "phase": "parse", "runs": 4, "min_ns": 10_000, "median_ns": 25_000 }));
+		assert_eq!(json[2], serde_json::json!({ "day": 1, "phase": "b", "runs": 4, "min_ns": 1_000_000, "median_ns": 1_150_000 }));
+		assert_eq!(json.as_array().unwrap().len(), 7);
+	}
+}
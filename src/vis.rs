@@ -0,0 +1,247 @@
+//! Visualizations of days that are rendered while they're being solved.
+//!
+//! A day that can be visualized gets a [`Renderer`] from [`renderer`] and sends it the cells of its grid as they change,
+//! along with a status line for anything that isn't part of the grid, like a score. Every call to [`Renderer::frame`] shows the changes
+//! since the previous frame, and waits long enough to keep to the frame rate in the [`Options`].
+//!
+//! Visualization is enabled for a run of a day by setting [`crate::Context::vis`]. When it's not enabled, [`renderer`] returns
+//! a renderer that does nothing, so days don't need to check for it themselves.
+//!
+//! Grids are drawn in the terminal by default, or can be written to image files by setting [`Options::target`] instead.
+//! What's drawn in the terminal can also be recorded by setting [`Options::record`].
+
+pub mod asciicast;
+pub mod headless;
+pub mod terminal;
+
+/// Options for visualizing days
+#[derive(Clone, Debug)]
+pub struct Options {
+	/// The maximum number of frames to render per second, or 0 to render frames as fast as possible.
+	///
+	/// When writing an animated image, this is the frame rate of the animation instead.
+	pub frame_rate: u32,
+
+	/// Where to render grids
+	pub target: Target,
+
+	/// Also records everything that's written to the terminal as asciicast files in this directory, eg `day13b.cast`.
+	///
+	/// This includes grids rendered to the terminal, and the text of days that print text or are played interactively.
+	pub record: Option<std::path::PathBuf>,
+
+	/// When day 25 is played in the terminal, also prints which memory cells each command changed, in this format
+	pub intcode_diff: Option<crate::intcode::dump::Format>,
+
+	/// When day 25 is played in the terminal, saves the session to this path as a transcript that can be replayed as a regression test
+	pub transcript: Option<std::path::PathBuf>,
+
+	/// When day 25 is played in the terminal, first prints the room names, descriptions, items and messages embedded in the program
+	pub strings: bool,
+}
+
+impl Default for Options {
+	fn default() -> Self {
+		Options {
+			frame_rate: 100,
+			target: Target::Terminal,
+			record: None,
+			intcode_diff: None,
+			transcript: None,
+			strings: false,
+		}
+	}
+}
+
+/// Where to render grids
+#[derive(Clone, Debug)]
+pub enum Target {
+	/// Draw grids in the terminal
+	Terminal,
+
+	/// Write grids to image files in a directory, without using the terminal
+	Images {
+		/// The directory to write the images to. It's created if it doesn't exist.
+		dir: std::path::PathBuf,
+
+		/// The format of the images
+		format: headless::Format,
+	},
+}
+
+/// A color, as its red, green and blue components
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Color(pub u8, pub u8, pub u8);
+
+/// What a cell of a grid looks like
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cell {
+	/// The text that a terminal shows for the cell. All cells of a grid should have glyphs of the same number of characters.
+	pub glyph: &'static str,
+
+	/// The color of the glyph
+	pub color: Color,
+}
+
+/// Renders a grid of cells that changes over time, along with a line of status text.
+///
+/// Coordinates can be negative, and the grid grows to fit every cell that's set.
+pub trait Renderer {
+	/// Sets the cell at the given coordinates
+	fn set(&mut self, pos: (i64, i64), cell: Cell) -> Result<(), crate::Error>;
+
+	/// Sets the status line
+	fn status(&mut self, text: &str) -> Result<(), crate::Error>;
+
+	/// Shows everything that was set since the previous frame, and waits until it's time for the next frame
+	fn frame(&mut self) -> Result<(), crate::Error>;
+
+	/// Finishes rendering
+	fn finish(&mut self) -> Result<(), crate::Error>;
+}
+
+/// A renderer that does nothing
+#[derive(Debug)]
+pub struct Null;
+
+impl Renderer for Null {
+	fn set(&mut self, _pos: (i64, i64), _cell: Cell) -> Result<(), crate::Error> {
+		Ok(())
+	}
+
+	fn status(&mut self, _text: &str) -> Result<(), crate::Error> {
+		Ok(())
+	}
+
+	fn frame(&mut self) -> Result<(), crate::Error> {
+		Ok(())
+	}
+
+	fn finish(&mut self) -> Result<(), crate::Error> {
+		Ok(())
+	}
+}
+
+// The visualization of the part that's being run
+struct Current {
+	options: Options,
+
+	// Names the files that the part writes, eg `day13b`
+	name: String,
+
+	// The number of files that the part has started writing so far
+	files: usize,
+}
+
+impl Current {
+	// The path of the next file that the part writes to the given directory, without its extension.
+	// Every renderer and output of the part gets its own files.
+	fn next_path(&mut self, dir: &std::path::Path) -> std::path::PathBuf {
+		self.files += 1;
+		if self.files == 1 {
+			dir.join(&self.name)
+		}
+		else {
+			dir.join(format!("{}-{}", self.name, self.files))
+		}
+	}
+
+	fn output(&mut self) -> terminal::Output {
+		let recorder = self.options.record.clone().map(|dir| asciicast::Recorder::new(self.next_path(&dir)));
+		terminal::Output::new(recorder)
+	}
+}
+
+thread_local! {
+	// Set by `crate::run` for the duration of running a part, so that the part can be visualized without passing the options through its solution
+	static CURRENT: std::cell::RefCell<Option<Current>> = std::cell::RefCell::new(None);
+}
+
+/// Whether the part that's being run is being visualized in the terminal.
+///
+/// Days that print text or are played interactively instead of rendering a grid only do so when this is true,
+/// and print it to [`output`].
+pub fn in_terminal() -> bool {
+	CURRENT.with(|current| matches!(&*current.borrow(), Some(Current { options: Options { target: Target::Terminal, .. }, .. })))
+}
+
+/// The options that the part that's being run is visualized with, or `None` if visualization isn't enabled
+pub fn options() -> Option<Options> {
+	CURRENT.with(|current| current.borrow().as_ref().map(|current| current.options.clone()))
+}
+
+/// A renderer for the part that's being run, or [`Null`] if visualization isn't enabled
+pub fn renderer() -> Box<dyn Renderer> {
+	CURRENT.with(|current| {
+		let mut current = current.borrow_mut();
+		let current = match &mut *current {
+			Some(current) => current,
+			None => return Box::new(Null) as Box<dyn Renderer>,
+		};
+
+		match current.options.target.clone() {
+			Target::Terminal => {
+				let output = current.output();
+				Box::new(terminal::Terminal::new(&current.options, output))
+			},
+
+			Target::Images { dir, format } => {
+				let path = current.next_path(&dir);
+				Box::new(headless::Headless::new(&current.options, path, format))
+			},
+		}
+	})
+}
+
+/// Writes text to the terminal for the part that's being run, and records it if recording is enabled
+pub fn output() -> terminal::Output {
+	CURRENT.with(|current| match &mut *current.borrow_mut() {
+		Some(current) => current.output(),
+		None => terminal::Output::new(None),
+	})
+}
+
+// Enables visualization with the given options on the current thread until it's dropped
+pub(crate) struct Scope(Option<Current>);
+
+impl Scope {
+	pub(crate) fn new(options: Option<&Options>, name: &str) -> Self {
+		let current = options.map(|options| Current { options: options.clone(), name: name.to_owned(), files: 0 });
+		Scope(CURRENT.with(|previous| previous.replace(current)))
+	}
+}
+
+impl Drop for Scope {
+	fn drop(&mut self) {
+		let previous = self.0.take();
+		CURRENT.with(|current| *current.borrow_mut() = previous);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_scope() {
+		assert!(!super::in_terminal());
+
+		{
+			let _scope = super::Scope::new(Some(&super::Options { frame_rate: 0, ..Default::default() }), "day13b");
+			assert!(super::in_terminal());
+
+			{
+				let _scope = super::Scope::new(None, "day15a");
+				assert!(!super::in_terminal());
+			}
+
+			{
+				let target = super::Target::Images { dir: "vis".into(), format: super::headless::Format::Gif };
+				let _scope = super::Scope::new(Some(&super::Options { frame_rate: 0, target, ..Default::default() }), "day15a");
+				assert!(!super::in_terminal());
+			}
+
+			assert!(super::in_terminal());
+		}
+
+		assert!(!super::in_terminal());
+	}
+}
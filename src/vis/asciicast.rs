@@ -0,0 +1,149 @@
+//! Records what's written to the terminal as an [asciicast v2](https://docs.asciinema.org/manual/asciicast/v2/) file,
+//! so that a visualization can be replayed with `asciinema play` without running the day again.
+
+/// A recording of everything written to the terminal, with the time it was written at
+#[derive(Debug)]
+pub struct Recorder {
+	path: std::path::PathBuf,
+	started: std::time::Instant,
+	timestamp: u64,
+
+	// The size of the terminal that's needed to replay the recording
+	size: (usize, usize),
+
+	events: Vec<(f64, String)>,
+
+	// Output that hasn't been recorded as an event yet
+	pending: Vec<u8>,
+
+	// The number of events when the recording was last saved
+	saved: Option<usize>,
+}
+
+impl Recorder {
+	/// A recording that will be saved to the given path after adding the `.cast` extension
+	pub fn new(path: std::path::PathBuf) -> Self {
+		let mut path = path.into_os_string();
+		path.push(".cast");
+
+		Recorder {
+			path: path.into(),
+			started: std::time::Instant::now(),
+			timestamp: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |timestamp| timestamp.as_secs()),
+
+			size: (80, 24),
+
+			events: vec![],
+			pending: vec![],
+
+			saved: None,
+		}
+	}
+
+	/// Grows the size of the terminal that the recording is replayed in to fit the given number of columns and rows
+	pub fn fit(&mut self, cols: usize, rows: usize) {
+		self.size = (self.size.0.max(cols), self.size.1.max(rows));
+	}
+
+	/// Adds output to the recording. It's recorded as an event on the next flush.
+	pub fn write(&mut self, bytes: &[u8]) {
+		self.pending.extend_from_slice(bytes);
+	}
+
+	/// Records the output since the previous flush as an event at the current time
+	pub fn flush(&mut self) {
+		// A character that's split across writes is recorded along with the rest of it
+		let len = match std::str::from_utf8(&self.pending) {
+			Ok(s) => s.len(),
+			Err(err) if err.error_len().is_none() => err.valid_up_to(),
+			Err(_) => self.pending.len(),
+		};
+		if len == 0 {
+			return;
+		}
+
+		let data = String::from_utf8_lossy(&self.pending[..len]).into_owned();
+		self.pending.drain(..len);
+		self.events.push((self.started.elapsed().as_secs_f64(), data));
+	}
+
+	/// Writes the recording to its file, replacing the previous save
+	pub fn save(&mut self) -> Result<(), crate::Error> {
+		use std::io::Write;
+
+		#[derive(serde::Serialize)]
+		struct Header {
+			version: u32,
+			width: usize,
+			height: usize,
+			timestamp: u64,
+		}
+
+		self.flush();
+
+		if self.saved == Some(self.events.len()) {
+			return Ok(());
+		}
+
+		if let Some(dir) = self.path.parent() {
+			std::fs::create_dir_all(dir).map_err(|err| format!("could not create {}: {err}", dir.display()))?;
+		}
+
+		let file = std::fs::File::create(&self.path).map_err(|err| format!("could not create {}: {err}", self.path.display()))?;
+		let mut out = std::io::BufWriter::new(file);
+
+		let (width, height) = self.size;
+		serde_json::to_writer(&mut out, &Header { version: 2, width, height, timestamp: self.timestamp })?;
+		writeln!(out)?;
+
+		for (time, data) in &self.events {
+			serde_json::to_writer(&mut out, &(time, "o", data))?;
+			writeln!(out)?;
+		}
+
+		out.flush()?;
+
+		self.saved = Some(self.events.len());
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_recorder() {
+		let path = std::env::temp_dir().join(format!("aoc2019-test-recorder-{}", std::process::id()));
+
+		let mut recorder = super::Recorder::new(path.clone());
+		recorder.fit(132, 10);
+		recorder.fit(100, 30);
+
+		recorder.write(b"\x1B[2Jscore: ");
+		recorder.write("\u{2B24}".as_bytes().split_at(1).0);
+		recorder.flush();
+		recorder.write("\u{2B24}".as_bytes().split_at(1).1);
+		recorder.write(b"\n");
+		recorder.flush();
+		recorder.flush();
+		recorder.save().unwrap();
+
+		let mut path = path.into_os_string();
+		path.push(".cast");
+		let recording = std::fs::read_to_string(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		let mut lines = recording.lines().map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap());
+
+		let header = lines.next().unwrap();
+		assert_eq!(header["version"], 2);
+		assert_eq!(header["width"], 132);
+		assert_eq!(header["height"], 30);
+
+		let events: Vec<_> = lines.map(|event| (event[1].as_str().unwrap().to_owned(), event[2].as_str().unwrap().to_owned())).collect();
+		assert_eq!(events, [
+			("o".to_owned(), "\x1B[2Jscore: ".to_owned()),
+			("o".to_owned(), "\u{2B24}\n".to_owned()),
+		]);
+	}
+}
@@ -0,0 +1,593 @@
+//! Renders to image files, without a terminal.
+//!
+//! The size of the images isn't known until every cell has been set, so the changes in each frame are kept until rendering finishes.
+//! Then the frames are written as a numbered sequence of PPM or PNG images, or as one animated GIF, or the final frame is written as an SVG.
+//!
+//! Each cell is drawn as a square of its color on a black background. Glyphs aren't drawn, and neither is the status line,
+//! except as the title of an SVG.
+
+/// The format of the images that a [`Headless`] renderer writes
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+	/// A binary PPM image of every frame, eg `day13b-00001.ppm`
+	Ppm,
+
+	/// A PNG image of every frame, eg `day13b-00001.png`
+	Png,
+
+	/// One animated GIF of all the frames, eg `day13b.gif`
+	Gif,
+
+	/// One SVG image of the final frame, eg `day8b.svg`
+	Svg,
+}
+
+impl std::str::FromStr for Format {
+	type Err = crate::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"ppm" => Format::Ppm,
+			"png" => Format::Png,
+			"gif" => Format::Gif,
+			"svg" => Format::Svg,
+			s => return Err(format!("invalid image format {s:?}, expected ppm, png, gif or svg").into()),
+		})
+	}
+}
+
+// The width and height in pixels of a cell in PPM, PNG and GIF images
+const CELL_SIZE: usize = 4;
+
+const BACKGROUND: super::Color = super::Color(0, 0, 0);
+
+/// A renderer that writes image files
+pub struct Headless {
+	// The path of the images without their extension, eg `vis/day13b`
+	path: std::path::PathBuf,
+	format: Format,
+	frame_rate: u32,
+
+	cells: std::collections::BTreeMap<(i64, i64), super::Color>,
+	status: String,
+
+	// The cells that changed in each frame so far, and in the frame that hasn't been shown yet
+	frames: Vec<Vec<((i64, i64), super::Color)>>,
+	changes: Vec<((i64, i64), super::Color)>,
+}
+
+impl Headless {
+	/// A renderer that writes images of the given format to the given path, after adding a frame number if needed and the format's extension
+	pub fn new(options: &super::Options, path: std::path::PathBuf, format: Format) -> Self {
+		Headless {
+			path,
+			format,
+			frame_rate: options.frame_rate,
+
+			cells: Default::default(),
+			status: String::new(),
+
+			frames: vec![],
+			changes: vec![],
+		}
+	}
+
+	fn create(&self, suffix: &str) -> Result<std::io::BufWriter<std::fs::File>, crate::Error> {
+		let mut path = self.path.clone().into_os_string();
+		path.push(suffix);
+		let path = std::path::PathBuf::from(path);
+
+		let file = std::fs::File::create(&path).map_err(|err| format!("could not create {}: {err}", path.display()))?;
+		Ok(std::io::BufWriter::new(file))
+	}
+}
+
+impl super::Renderer for Headless {
+	fn set(&mut self, pos: (i64, i64), cell: super::Cell) -> Result<(), crate::Error> {
+		if self.cells.insert(pos, cell.color) != Some(cell.color) {
+			self.changes.push((pos, cell.color));
+		}
+
+		Ok(())
+	}
+
+	fn status(&mut self, text: &str) -> Result<(), crate::Error> {
+		self.status = text.to_owned();
+		Ok(())
+	}
+
+	fn frame(&mut self) -> Result<(), crate::Error> {
+		// Frames where no cell changed would be identical to the previous one
+		if !self.changes.is_empty() {
+			self.frames.push(std::mem::take(&mut self.changes));
+		}
+
+		Ok(())
+	}
+
+	fn finish(&mut self) -> Result<(), crate::Error> {
+		use std::io::Write;
+
+		self.frame()?;
+
+		let frames = std::mem::take(&mut self.frames);
+
+		let bounds = match bounds(self.cells.keys()) {
+			Some(bounds) => bounds,
+			None => return Ok(()),
+		};
+
+		if let Some(dir) = self.path.parent() {
+			std::fs::create_dir_all(dir).map_err(|err| format!("could not create {}: {err}", dir.display()))?;
+		}
+
+		match self.format {
+			Format::Ppm | Format::Png => {
+				let mut canvas = Canvas::new(bounds, BACKGROUND)?;
+
+				for (i, frame) in frames.iter().enumerate() {
+					for &(pos, color) in frame {
+						canvas.set(pos, color);
+					}
+
+					let (extension, write): (_, fn(&mut _, &_) -> _) = match self.format {
+						Format::Ppm => ("ppm", write_ppm),
+						_ => ("png", write_png),
+					};
+
+					let mut out = self.create(&format!("-{:05}.{extension}", i + 1))?;
+					write(&mut out, &canvas)?;
+					out.flush()?;
+				}
+			},
+
+			Format::Gif => {
+				let mut out = self.create(".gif")?;
+				write_gif(&mut out, bounds, &frames, self.frame_rate)?;
+				out.flush()?;
+			},
+
+			Format::Svg => {
+				let mut out = self.create(".svg")?;
+				write_svg(&mut out, bounds, &self.cells, &self.status)?;
+				out.flush()?;
+			},
+		}
+
+		Ok(())
+	}
+}
+
+// The top left and bottom right of the given cells
+fn bounds<'a>(cells: impl IntoIterator<Item = &'a (i64, i64)>) -> Option<((i64, i64), (i64, i64))> {
+	cells.into_iter().fold(None, |bounds, &(x, y)| match bounds {
+		Some(((min_x, min_y), (max_x, max_y))) => Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))),
+		None => Some(((x, y), (x, y))),
+	})
+}
+
+// The cells within some bounds, stored row by row
+struct Canvas<T> {
+	min: (i64, i64),
+	width: usize,
+	height: usize,
+	cells: Vec<T>,
+}
+
+impl<T> Canvas<T> where T: Copy {
+	fn new(((min_x, min_y), (max_x, max_y)): ((i64, i64), (i64, i64)), background: T) -> Result<Self, crate::Error> {
+		let width: usize = (max_x - min_x + 1).try_into()?;
+		let height: usize = (max_y - min_y + 1).try_into()?;
+		Ok(Canvas { min: (min_x, min_y), width, height, cells: vec![background; width * height] })
+	}
+
+	// The column and row of the cell at the given coordinates
+	#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+	fn offset(&self, (x, y): (i64, i64)) -> (usize, usize) {
+		((x - self.min.0) as usize, (y - self.min.1) as usize)
+	}
+
+	fn set(&mut self, pos: (i64, i64), value: T) {
+		let (col, row) = self.offset(pos);
+		self.cells[row * self.width + col] = value;
+	}
+
+	// The pixels of the given columns and rows of cells, row by row, with each cell scaled up to `CELL_SIZE` pixels
+	fn pixels(&self, cols: std::ops::Range<usize>, rows: std::ops::Range<usize>) -> impl Iterator<Item = T> + '_ {
+		rows.flat_map(|row| std::iter::repeat(row).take(CELL_SIZE))
+		.flat_map(move |row| {
+			let row = &self.cells[(row * self.width)..((row + 1) * self.width)];
+			cols.clone().flat_map(move |col| std::iter::repeat(row[col]).take(CELL_SIZE))
+		})
+	}
+}
+
+fn write_ppm(out: &mut impl std::io::Write, canvas: &Canvas<super::Color>) -> Result<(), crate::Error> {
+	write!(out, "P6\n{} {}\n255\n", canvas.width * CELL_SIZE, canvas.height * CELL_SIZE)?;
+
+	for super::Color(r, g, b) in canvas.pixels(0..canvas.width, 0..canvas.height) {
+		out.write_all(&[r, g, b])?;
+	}
+
+	Ok(())
+}
+
+fn write_png(out: &mut impl std::io::Write, canvas: &Canvas<super::Color>) -> Result<(), crate::Error> {
+	fn write_chunk(out: &mut impl std::io::Write, kind: [u8; 4], data: &[u8]) -> Result<(), crate::Error> {
+		out.write_all(&u32::try_from(data.len())?.to_be_bytes())?;
+		out.write_all(&kind)?;
+		out.write_all(data)?;
+		out.write_all(&crc32(kind.iter().chain(data)).to_be_bytes())?;
+		Ok(())
+	}
+
+	let width = canvas.width * CELL_SIZE;
+	let height = canvas.height * CELL_SIZE;
+
+	out.write_all(b"\x89PNG\r\n\x1A\n")?;
+
+	let mut header = vec![];
+	header.extend_from_slice(&u32::try_from(width)?.to_be_bytes());
+	header.extend_from_slice(&u32::try_from(height)?.to_be_bytes());
+	// 8-bit RGB, with the default compression and filter methods and no interlacing
+	header.extend_from_slice(&[8, 2, 0, 0, 0]);
+	write_chunk(out, *b"IHDR", &header)?;
+
+	// Every row starts with its filter type, which is always 0 (none)
+	let pixels: Vec<_> = canvas.pixels(0..canvas.width, 0..canvas.height).flat_map(|super::Color(r, g, b)| [r, g, b]).collect();
+	let mut data = Vec::with_capacity((1 + width * 3) * height);
+	for row in pixels.chunks(width * 3) {
+		data.push(0);
+		data.extend_from_slice(row);
+	}
+	write_chunk(out, *b"IDAT", &miniz_oxide::deflate::compress_to_vec_zlib(&data, 6))?;
+
+	write_chunk(out, *b"IEND", &[])?;
+
+	Ok(())
+}
+
+// The CRC-32 that PNG chunks end with
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+	let crc = bytes.into_iter().fold(0xFFFF_FFFF_u32, |mut crc, &b| {
+		crc ^= u32::from(b);
+		for _ in 0..8 {
+			crc = if crc & 1 == 0 { crc >> 1 } else { (crc >> 1) ^ 0xEDB8_8320 };
+		}
+		crc
+	});
+	!crc
+}
+
+fn write_gif(
+	out: &mut impl std::io::Write,
+	bounds: ((i64, i64), (i64, i64)),
+	frames: &[Vec<((i64, i64), super::Color)>],
+	frame_rate: u32,
+) -> Result<(), crate::Error> {
+	// Every color gets an index in the color table, with the background first
+	let mut palette = vec![BACKGROUND];
+	let frames: Vec<Vec<_>> =
+		frames.iter()
+		.map(|frame| frame.iter().map(|&(pos, color)| {
+			let index = match palette.iter().position(|&c| c == color) {
+				Some(index) => index,
+				None => {
+					palette.push(color);
+					palette.len() - 1
+				},
+			};
+			Ok((pos, u8::try_from(index).map_err(|_| "too many colors for a GIF")?))
+		}).collect::<Result<_, crate::Error>>())
+		.collect::<Result<_, _>>()?;
+
+	// The color table has 2^bits entries
+	let mut bits = 1;
+	while 1 << bits < palette.len() {
+		bits += 1;
+	}
+	palette.resize(1 << bits, BACKGROUND);
+
+	let mut canvas = Canvas::new(bounds, 0_u8)?;
+	let width = u16::try_from(canvas.width * CELL_SIZE)?;
+	let height = u16::try_from(canvas.height * CELL_SIZE)?;
+
+	out.write_all(b"GIF89a")?;
+	out.write_all(&width.to_le_bytes())?;
+	out.write_all(&height.to_le_bytes())?;
+	// A global color table, with the background color at index 0 and no pixel aspect ratio
+	out.write_all(&[0x80 | (bits - 1), 0, 0])?;
+	for super::Color(r, g, b) in palette {
+		out.write_all(&[r, g, b])?;
+	}
+
+	// Loop forever
+	out.write_all(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+	// The delay between frames is in hundredths of a second. Viewers slow down frames that are shorter than 2.
+	let delay = if frame_rate == 0 { 2 } else { u16::try_from(100 / frame_rate)?.max(2) };
+
+	for (i, frame) in frames.iter().enumerate() {
+		for &(pos, index) in frame {
+			canvas.set(pos, index);
+		}
+
+		// The first frame covers the whole image, and the others only cover the cells that changed in them,
+		// leaving the rest of the previous frame in place.
+		let (cols, rows) =
+			if i == 0 {
+				(0..canvas.width, 0..canvas.height)
+			}
+			else {
+				let ((min_col, min_row), (end_col, end_row)) = frame.iter().fold(((usize::MAX, usize::MAX), (0, 0)), |((min_col, min_row), (end_col, end_row)), &(pos, _)| {
+					let (col, row) = canvas.offset(pos);
+					((min_col.min(col), min_row.min(row)), (end_col.max(col + 1), end_row.max(row + 1)))
+				});
+				(min_col..end_col, min_row..end_row)
+			};
+
+		// Graphic control extension, to not dispose of the frame and then wait for the delay
+		out.write_all(&[0x21, 0xF9, 0x04, 0x04])?;
+		out.write_all(&delay.to_le_bytes())?;
+		out.write_all(&[0x00, 0x00])?;
+
+		// Image descriptor, without a local color table
+		out.write_all(&[0x2C])?;
+		for value in [cols.start * CELL_SIZE, rows.start * CELL_SIZE, cols.len() * CELL_SIZE, rows.len() * CELL_SIZE] {
+			out.write_all(&u16::try_from(value)?.to_le_bytes())?;
+		}
+		out.write_all(&[0x00])?;
+
+		let min_code_size = bits.max(2);
+		let data = lzw(min_code_size, canvas.pixels(cols, rows));
+		out.write_all(&[min_code_size])?;
+		for block in data.chunks(255) {
+			out.write_all(&[u8::try_from(block.len())?])?;
+			out.write_all(block)?;
+		}
+		out.write_all(&[0x00])?;
+	}
+
+	out.write_all(&[0x3B])?;
+
+	Ok(())
+}
+
+// Compresses the color indices of a GIF image with variable-length LZW codes, packed starting from the least significant bit
+fn lzw(min_code_size: u8, indices: impl IntoIterator<Item = u8>) -> Vec<u8> {
+	struct Bits {
+		out: Vec<u8>,
+		buf: u32,
+		len: u8,
+	}
+
+	impl Bits {
+		fn write(&mut self, code: u16, code_size: u8) {
+			self.buf |= u32::from(code) << self.len;
+			self.len += code_size;
+			while self.len >= 8 {
+				self.out.push(self.buf.to_le_bytes()[0]);
+				self.buf >>= 8;
+				self.len -= 8;
+			}
+		}
+	}
+
+	const MAX_CODE_SIZE: u8 = 12;
+
+	let clear = 1_u16 << min_code_size;
+	let end = clear + 1;
+
+	// The code of each string in the table besides single indices, as the code of its prefix and its last index
+	let mut codes: std::collections::HashMap<(u16, u8), u16> = Default::default();
+	let mut next_code = end + 1;
+	let mut code_size = min_code_size + 1;
+
+	let mut bits = Bits { out: vec![], buf: 0, len: 0 };
+	bits.write(clear, code_size);
+
+	let mut indices = indices.into_iter();
+	if let Some(first) = indices.next() {
+		let mut prefix = u16::from(first);
+
+		for index in indices {
+			if let Some(&code) = codes.get(&(prefix, index)) {
+				prefix = code;
+				continue;
+			}
+
+			bits.write(prefix, code_size);
+
+			if next_code < 1 << MAX_CODE_SIZE {
+				codes.insert((prefix, index), next_code);
+				next_code += 1;
+				if next_code > 1 << code_size && code_size < MAX_CODE_SIZE {
+					code_size += 1;
+				}
+			}
+			else {
+				// The table is full, so start over
+				bits.write(clear, code_size);
+				codes.clear();
+				next_code = end + 1;
+				code_size = min_code_size + 1;
+			}
+
+			prefix = u16::from(index);
+		}
+
+		bits.write(prefix, code_size);
+
+		// The decoder adds a code to its table after reading the last one too, which can make the end code one bit longer
+		if next_code < 1 << MAX_CODE_SIZE && next_code + 1 > 1 << code_size && code_size < MAX_CODE_SIZE {
+			code_size += 1;
+		}
+	}
+
+	bits.write(end, code_size);
+	if bits.len > 0 {
+		bits.out.push(bits.buf.to_le_bytes()[0]);
+	}
+
+	bits.out
+}
+
+fn write_svg(
+	out: &mut impl std::io::Write,
+	((min_x, min_y), (max_x, max_y)): ((i64, i64), (i64, i64)),
+	cells: &std::collections::BTreeMap<(i64, i64), super::Color>,
+	status: &str,
+) -> Result<(), crate::Error> {
+	let width = max_x - min_x + 1;
+	let height = max_y - min_y + 1;
+
+	// Cells are one unit wide, and scaled up to ten pixels
+	writeln!(
+		out,
+		r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{min_x} {min_y} {width} {height}" shape-rendering="crispEdges">"#,
+		width * 10,
+		height * 10,
+	)?;
+
+	if !status.is_empty() {
+		let status = status.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+		writeln!(out, "<title>{status}</title>")?;
+	}
+
+	let super::Color(r, g, b) = BACKGROUND;
+	writeln!(out, r##"<rect x="{min_x}" y="{min_y}" width="{width}" height="{height}" fill="#{r:02x}{g:02x}{b:02x}"/>"##)?;
+
+	for (&(x, y), &super::Color(r, g, b)) in cells {
+		if super::Color(r, g, b) != BACKGROUND {
+			writeln!(out, r##"<rect x="{x}" y="{y}" width="1" height="1" fill="#{r:02x}{g:02x}{b:02x}"/>"##)?;
+		}
+	}
+
+	writeln!(out, "</svg>")?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn test_crc32() {
+		assert_eq!(super::crc32(b"IEND"), 0xAE42_6082);
+		assert_eq!(super::crc32(b"123456789"), 0xCBF4_3926);
+	}
+
+	#[test]
+	fn test_lzw() {
+		// Decodes the output of `super::lzw` the way a GIF viewer does
+		fn decode(min_code_size: u8, data: &[u8]) -> Vec<u8> {
+			let clear = 1_usize << min_code_size;
+			let end = clear + 1;
+
+			let mut table: Vec<Vec<u8>> = vec![];
+			let mut code_size = min_code_size + 1;
+			let mut previous: Option<usize> = None;
+			let mut result = vec![];
+
+			let mut buf = 0_u32;
+			let mut len = 0;
+			let mut data = data.iter();
+
+			loop {
+				while len < code_size {
+					buf |= u32::from(*data.next().expect("data ended before the end code")) << len;
+					len += 8;
+				}
+				let code = (buf & ((1 << code_size) - 1)) as usize;
+				buf >>= code_size;
+				len -= code_size;
+
+				if code == clear {
+					table = (0..clear).map(|i| vec![u8::try_from(i).unwrap()]).collect();
+					table.push(vec![]);
+					table.push(vec![]);
+					code_size = min_code_size + 1;
+					previous = None;
+					continue;
+				}
+
+				if code == end {
+					break;
+				}
+
+				let entry = match (table.get(code), previous) {
+					(Some(entry), _) => entry.clone(),
+					(None, Some(previous)) if code == table.len() => {
+						let mut entry = table[previous].clone();
+						entry.push(table[previous][0]);
+						entry
+					},
+					_ => panic!("invalid code {code}"),
+				};
+				result.extend_from_slice(&entry);
+
+				if let Some(previous) = previous {
+					if table.len() < 4096 {
+						let mut new_entry = table[previous].clone();
+						new_entry.push(entry[0]);
+						table.push(new_entry);
+						if table.len() == 1 << code_size && code_size < 12 {
+							code_size += 1;
+						}
+					}
+				}
+
+				previous = Some(code);
+			}
+
+			result
+		}
+
+		fn test(min_code_size: u8, indices: &[u8]) {
+			let data = super::lzw(min_code_size, indices.iter().copied());
+			assert_eq!(indices, decode(min_code_size, &data));
+		}
+
+		test(2, &[]);
+		test(2, &[0]);
+		test(2, &[1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 3, 3, 0, 0, 0, 1]);
+		test(4, &(0..16).cycle().take(1000).collect::<Vec<_>>());
+
+		// Enough codes to fill the table and start over, more than once
+		let mut noise = 1_u32;
+		let indices: Vec<_> = std::iter::repeat_with(|| { noise = noise.wrapping_mul(1_103_515_245).wrapping_add(12345); (noise >> 16).to_le_bytes()[0] }).take(30000).collect();
+		test(8, &indices);
+
+		// Every length of a run of the same index, to reach every code size boundary
+		for len in 0..300 {
+			test(2, &vec![3; len]);
+		}
+	}
+
+	#[test]
+	fn test_png() {
+		let mut canvas = super::Canvas::new(((-1, 0), (0, 1)), super::BACKGROUND).unwrap();
+		canvas.set((0, 1), super::super::Color(255, 0, 0));
+
+		let mut png = vec![];
+		super::write_png(&mut png, &canvas).unwrap();
+
+		assert_eq!(&png[..8], b"\x89PNG\r\n\x1A\n");
+		assert_eq!(&png[12..16], b"IHDR");
+		assert_eq!(&png[16..24], &[0, 0, 0, 8, 0, 0, 0, 8]);
+
+		let idat_len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+		assert_eq!(&png[37..41], b"IDAT");
+		let data = miniz_oxide::inflate::decompress_to_vec_zlib(&png[41..(41 + idat_len)]).unwrap();
+
+		// Each row is a filter type followed by 8 pixels, and the red cell is the bottom right quarter
+		let red_pixels: Vec<_> =
+			data.chunks(1 + 8 * 3)
+			.enumerate()
+			.flat_map(|(y, row)| row[1..].chunks(3).enumerate().filter(|(_, pixel)| *pixel == [255, 0, 0]).map(move |(x, _)| (x, y)))
+			.collect();
+		let expected_red_pixels: Vec<_> = (4..8).flat_map(|y| (4..8).map(move |x| (x, y))).collect();
+		assert_eq!(expected_red_pixels, red_pixels);
+
+		assert_eq!(&png[(png.len() - 12)..], b"\x00\x00\x00\x00IEND\xAE\x42\x60\x82");
+	}
+}
@@ -0,0 +1,272 @@
+//! Renders to a terminal, using ANSI escape sequences.
+//!
+//! The grid is drawn on the terminal's alternate screen, with the status line above it. Cells are drawn as they're set,
+//! except when a cell outside the grid so far makes it grow, in which case the whole screen is redrawn on the next frame.
+//! When rendering finishes, the terminal switches back to its main screen and the final frame is printed there.
+//!
+//! Everything is written through an [`Output`], which also records it when [`super::Options::record`] is set.
+
+/// Writes to stdout, and records what's written as an asciicast if recording is enabled
+pub struct Output {
+	stdout: std::io::BufWriter<std::io::Stdout>,
+	recorder: Option<super::asciicast::Recorder>,
+}
+
+impl Output {
+	/// Output that's recorded by the given recorder, if any
+	pub fn new(recorder: Option<super::asciicast::Recorder>) -> Self {
+		Output {
+			stdout: std::io::BufWriter::new(std::io::stdout()),
+			recorder,
+		}
+	}
+
+	/// Records text that the terminal has already shown without it being written, like what the user typed
+	pub fn echo(&mut self, text: &str) {
+		if let Some(recorder) = &mut self.recorder {
+			recorder.write(text.as_bytes());
+			recorder.flush();
+		}
+	}
+
+	/// Grows the size of the terminal that the recording is replayed in to fit the given number of columns and rows
+	pub fn fit(&mut self, cols: usize, rows: usize) {
+		if let Some(recorder) = &mut self.recorder {
+			recorder.fit(cols, rows);
+		}
+	}
+
+	/// Flushes stdout and saves the recording so far
+	pub fn save(&mut self) -> Result<(), crate::Error> {
+		use std::io::Write;
+
+		self.flush()?;
+
+		if let Some(recorder) = &mut self.recorder {
+			recorder.save()?;
+		}
+
+		Ok(())
+	}
+}
+
+impl std::io::Write for Output {
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		let written = self.stdout.write(buf)?;
+		if let Some(recorder) = &mut self.recorder {
+			recorder.write(&buf[..written]);
+		}
+		Ok(written)
+	}
+
+	fn flush(&mut self) -> std::io::Result<()> {
+		self.stdout.flush()?;
+		if let Some(recorder) = &mut self.recorder {
+			recorder.flush();
+		}
+		Ok(())
+	}
+}
+
+impl Drop for Output {
+	fn drop(&mut self) {
+		// Keep the recording of a day that failed before it was saved
+		let _ = self.save();
+	}
+}
+
+/// A renderer that draws to stdout
+pub struct Terminal {
+	out: Output,
+	frame_interval: Option<std::time::Duration>,
+	next_frame: Option<std::time::Instant>,
+
+	cells: std::collections::BTreeMap<(i64, i64), super::Cell>,
+	status: String,
+
+	// The top left and bottom right cells of the grid so far
+	bounds: Option<((i64, i64), (i64, i64))>,
+	cell_width: usize,
+
+	started: bool,
+	redraw: bool,
+}
+
+impl Terminal {
+	/// A renderer with the given options, that writes to the given output
+	pub fn new(options: &super::Options, out: Output) -> Self {
+		Terminal {
+			out,
+			frame_interval: if options.frame_rate == 0 { None } else { Some(std::time::Duration::from_secs(1) / options.frame_rate) },
+			next_frame: None,
+
+			cells: Default::default(),
+			status: String::new(),
+
+			bounds: None,
+			cell_width: 1,
+
+			started: false,
+			redraw: false,
+		}
+	}
+
+	fn start(&mut self) -> Result<(), crate::Error> {
+		use std::io::Write;
+
+		if !self.started {
+			// Switch to the alternate screen, clear it and hide the cursor
+			write!(self.out, "\x1B[?1049h\x1B[2J\x1B[3J\x1B[?25l")?;
+			self.started = true;
+		}
+
+		Ok(())
+	}
+
+	fn draw_cell(&mut self, (x, y): (i64, i64), cell: super::Cell) -> Result<(), crate::Error> {
+		use std::io::Write;
+
+		let ((min_x, min_y), _) = self.bounds.expect("cell is within bounds");
+		let row = y - min_y + 2;
+		let col = (x - min_x) * i64::try_from(self.cell_width)? + 1;
+		write!(self.out, "\x1B[{row};{col}H")?;
+		write_cell(&mut self.out, cell)?;
+		Ok(())
+	}
+
+	fn draw_status(&mut self) -> Result<(), crate::Error> {
+		use std::io::Write;
+
+		write!(self.out, "\x1B[1;1H\x1B[2K{}", self.status)?;
+		Ok(())
+	}
+}
+
+impl super::Renderer for Terminal {
+	fn set(&mut self, pos: (i64, i64), cell: super::Cell) -> Result<(), crate::Error> {
+		self.start()?;
+
+		if self.cells.insert(pos, cell) == Some(cell) {
+			return Ok(());
+		}
+
+		let cell_width = cell.glyph.chars().count();
+		if self.bounds.is_none() {
+			self.cell_width = cell_width;
+		}
+
+		let bounds = match self.bounds {
+			Some(((min_x, min_y), (max_x, max_y))) => ((min_x.min(pos.0), min_y.min(pos.1)), (max_x.max(pos.0), max_y.max(pos.1))),
+			None => (pos, pos),
+		};
+
+		if self.bounds != Some(bounds) {
+			self.bounds = Some(bounds);
+			self.redraw = true;
+
+			// The status line and the grid
+			let ((min_x, min_y), (max_x, max_y)) = bounds;
+			self.out.fit(usize::try_from(max_x - min_x + 1)? * self.cell_width, usize::try_from(max_y - min_y + 1)? + 1);
+		}
+
+		if !self.redraw {
+			self.draw_cell(pos, cell)?;
+		}
+
+		Ok(())
+	}
+
+	fn status(&mut self, text: &str) -> Result<(), crate::Error> {
+		self.start()?;
+
+		self.status = text.to_owned();
+		self.out.fit(text.chars().count(), 1);
+
+		if !self.redraw {
+			self.draw_status()?;
+		}
+
+		Ok(())
+	}
+
+	fn frame(&mut self) -> Result<(), crate::Error> {
+		use std::io::Write;
+
+		self.start()?;
+
+		if self.redraw {
+			write!(self.out, "\x1B[2J")?;
+			self.draw_status()?;
+
+			let cells: Vec<_> = self.cells.iter().map(|(&pos, &cell)| (pos, cell)).collect();
+			for (pos, cell) in cells {
+				self.draw_cell(pos, cell)?;
+			}
+
+			self.redraw = false;
+		}
+
+		self.out.flush()?;
+
+		if let Some(frame_interval) = self.frame_interval {
+			let now = std::time::Instant::now();
+			let next_frame = self.next_frame.unwrap_or(now);
+			if next_frame > now {
+				std::thread::sleep(next_frame - now);
+			}
+			self.next_frame = Some(next_frame.max(now) + frame_interval);
+		}
+
+		Ok(())
+	}
+
+	fn finish(&mut self) -> Result<(), crate::Error> {
+		use std::io::Write;
+
+		if !self.started {
+			return Ok(());
+		}
+
+		self.started = false;
+
+		// Show the cursor and switch back to the main screen
+		write!(self.out, "\x1B[?25h\x1B[?1049l")?;
+
+		if !self.status.is_empty() {
+			writeln!(self.out, "{}", self.status)?;
+		}
+
+		if let Some(((min_x, min_y), (max_x, max_y))) = self.bounds {
+			for y in min_y..=max_y {
+				for x in min_x..=max_x {
+					match self.cells.get(&(x, y)) {
+						Some(&cell) => write_cell(&mut self.out, cell)?,
+						None => write!(self.out, "{:1$}", "", self.cell_width)?,
+					}
+				}
+				writeln!(self.out)?;
+			}
+		}
+
+		self.out.save()?;
+
+		Ok(())
+	}
+}
+
+impl Drop for Terminal {
+	fn drop(&mut self) {
+		use std::io::Write;
+
+		// Restore the terminal if the day failed before it finished rendering
+		if self.started {
+			let _ = write!(self.out, "\x1B[?25h\x1B[?1049l");
+			let _ = self.out.flush();
+		}
+	}
+}
+
+fn write_cell(out: &mut impl std::io::Write, super::Cell { glyph, color: super::Color(r, g, b) }: super::Cell) -> Result<(), crate::Error> {
+	write!(out, "\x1B[38;2;{r};{g};{b}m{glyph}\x1B[0m")?;
+	Ok(())
+}